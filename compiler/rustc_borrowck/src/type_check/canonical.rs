@@ -1,9 +1,11 @@
 use std::fmt;
 
 use rustc_infer::infer::canonical::Canonical;
+use rustc_infer::infer::NllRegionVariableOrigin;
 use rustc_infer::traits::query::NoSolution;
 use rustc_middle::mir::ConstraintCategory;
-use rustc_middle::ty::{self, ToPredicate, TypeFoldable};
+use rustc_middle::ty::fold::BottomUpFolder;
+use rustc_middle::ty::{self, ToPredicate, TypeFoldable, TypeVisitable};
 use rustc_span::def_id::DefId;
 use rustc_span::Span;
 use rustc_trait_selection::traits::query::type_op::{self, TypeOpOutput};
@@ -141,6 +143,7 @@ pub(super) fn prove_predicate(
     ) {
         let param_env = self.param_env;
         let predicate = predicate.to_predicate(self.tcx());
+        let predicate = self.reveal_opaque_types_in_defining_scope(predicate);
         self.fully_perform_op(
             locations,
             category,
@@ -151,6 +154,45 @@ pub(super) fn prove_predicate(
         })
     }
 
+    /// With `-Zreveal-opaque-types-in-defining-scope`, type checking may have proven
+    /// obligations on an opaque type defined by this body for its hidden type. The
+    /// canonical query can't see that type, so it is revealed here as well.
+    fn reveal_opaque_types_in_defining_scope(
+        &self,
+        predicate: ty::Predicate<'tcx>,
+    ) -> ty::Predicate<'tcx> {
+        let tcx = self.tcx();
+        if !tcx.sess.opts.unstable_opts.reveal_opaque_types_in_defining_scope
+            || !predicate.has_opaque_types()
+        {
+            return predicate;
+        }
+        let typeck_results = tcx.typeck(self.body().source.def_id().expect_local());
+        predicate.fold_with(&mut BottomUpFolder {
+            tcx,
+            ty_op: |ty| {
+                let ty::Opaque(def_id, substs) = *ty.kind() else { return ty };
+                let Some(hidden_type) = def_id
+                    .as_local()
+                    .and_then(|def_id| typeck_results.concrete_opaque_types.get(&def_id))
+                else {
+                    return ty;
+                };
+                let hidden_ty = ty::EarlyBinder(hidden_type.ty).subst(tcx, substs);
+                tcx.fold_regions(hidden_ty, |region, _| {
+                    if region.is_erased() {
+                        let origin = NllRegionVariableOrigin::Existential { from_forall: false };
+                        self.infcx.next_nll_region_var(origin)
+                    } else {
+                        region
+                    }
+                })
+            },
+            lt_op: |lt| lt,
+            ct_op: |ct| ct,
+        })
+    }
+
     pub(super) fn normalize<T>(&mut self, value: T, location: impl NormalizeLocation) -> T
     where
         T: type_op::normalize::Normalizable<'tcx> + fmt::Display + Copy + 'tcx,
@@ -1,8 +1,7 @@
 use crate::obligation_forest::{ForestObligation, ObligationForest};
 use rustc_graphviz as dot;
-use std::env::var_os;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{self, BufWriter};
 use std::path::Path;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
@@ -12,29 +11,26 @@ impl<O: ForestObligation> ObligationForest<O> {
     /// create files with name of the format `<counter>_<description>.gv`. The counter is
     /// global and is maintained internally.
     ///
-    /// Calling this will do nothing unless the environment variable
-    /// `DUMP_OBLIGATION_FOREST_GRAPHVIZ` is defined.
+    /// This is called after every round of `process_obligations` if the processor returns a
+    /// directory from `ObligationProcessor::dump_dir`, which for trait obligations is the
+    /// case with `-Zdump-obligation-forest=<dir>`.
     ///
     /// A few post-processing that you might want to do make the forest easier to visualize:
     ///
     ///  * `sed 's,std::[a-z]*::,,g'` — Deletes the `std::<package>::` prefix of paths.
     ///  * `sed 's,"Binder(TraitPredicate(<\(.*\)>)) (\([^)]*\))","\1 (\2)",'` — Transforms
     ///    `Binder(TraitPredicate(<predicate>))` into just `<predicate>`.
-    #[allow(dead_code)]
-    pub fn dump_graphviz<P: AsRef<Path>>(&self, dir: P, description: &str) {
+    pub fn dump_graphviz<P: AsRef<Path>>(&self, dir: P, description: &str) -> io::Result<()> {
         static COUNTER: AtomicUsize = AtomicUsize::new(0);
 
-        if var_os("DUMP_OBLIGATION_FOREST_GRAPHVIZ").is_none() {
-            return;
-        }
-
         let counter = COUNTER.fetch_add(1, Ordering::AcqRel);
 
+        std::fs::create_dir_all(dir.as_ref())?;
         let file_path = dir.as_ref().join(format!("{:010}_{}.gv", counter, description));
 
-        let mut gv_file = BufWriter::new(File::create(file_path).unwrap());
+        let mut gv_file = BufWriter::new(File::create(file_path)?);
 
-        dot::render(&self, &mut gv_file).unwrap();
+        dot::render(&self, &mut gv_file)
     }
 }
 
@@ -52,7 +48,10 @@ fn node_id(&self, index: &Self::Node) -> dot::Id<'_> {
 
     fn node_label(&self, index: &Self::Node) -> dot::LabelText<'_> {
         let node = &self.nodes[*index];
-        let label = format!("{:?} ({:?})", node.obligation.as_cache_key(), node.state.get());
+        let mut label = format!("{:?} ({:?})", node.obligation.as_cache_key(), node.state.get());
+        if let Some(note) = node.obligation.dump_note() {
+            label.push_str(&format!(" [{}]", note));
+        }
 
         dot::LabelText::LabelStr(label.into())
     }
@@ -75,7 +75,9 @@
 use std::collections::hash_map::Entry;
 use std::fmt::Debug;
 use std::hash;
+use std::io;
 use std::marker::PhantomData;
+use std::path::Path;
 
 mod graphviz;
 
@@ -90,6 +92,12 @@ pub trait ForestObligation: Clone + Debug {
     /// then it must be sound to use the result of processing one obligation
     /// (e.g. success for error) for the other obligation
     fn as_cache_key(&self) -> Self::CacheKey;
+
+    /// Extra information shown next to this obligation when the forest is dumped,
+    /// such as what is keeping it from making progress.
+    fn dump_note(&self) -> Option<String> {
+        None
+    }
 }
 
 pub trait ObligationProcessor {
@@ -102,6 +110,34 @@ pub trait ObligationProcessor {
 
     fn needs_process_obligation(&self, obligation: &Self::Obligation) -> bool;
 
+    /// Whether `obligation` should be processed in the first pass of each round, before
+    /// the obligations for which this returns `false`. This is the scheduling policy of
+    /// the forest: obligations that are cheap to process and likely to constrain
+    /// inference variables should go first, so the others see more resolved types.
+    ///
+    /// This is called at least once for every pending obligation in every round in which
+    /// [`ObligationProcessor::has_early_pass`] returns `true`, so it has to be cheap.
+    fn process_early(&self, _obligation: &Self::Obligation) -> bool {
+        false
+    }
+
+    /// Whether to do a separate first pass over the obligations selected by
+    /// [`ObligationProcessor::process_early`] in each round. If this returns `false`,
+    /// all obligations are processed in a single pass in the order they were registered.
+    fn has_early_pass(&self) -> bool {
+        false
+    }
+
+    /// The directory to dump a graphviz snapshot of the forest into after each round
+    /// of processing, if any. See [`ObligationForest::dump_graphviz`].
+    fn dump_dir(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Called when writing a snapshot into [`ObligationProcessor::dump_dir`] failed.
+    /// The snapshot of that round is skipped and processing continues.
+    fn dump_failed(&self, _dir: &Path, _error: io::Error) {}
+
     fn process_obligation(
         &mut self,
         obligation: &mut Self::Obligation,
@@ -420,53 +456,70 @@ pub fn process_obligations<P>(&mut self, processor: &mut P) -> P::OUT
         loop {
             let mut has_changed = false;
 
-            // Note that the loop body can append new nodes, and those new nodes
-            // will then be processed by subsequent iterations of the loop.
-            //
-            // We can't use an iterator for the loop because `self.nodes` is
-            // appended to and the borrow checker would complain. We also can't use
-            // `for index in 0..self.nodes.len() { ... }` because the range would
-            // be computed with the initial length, and we would miss the appended
-            // nodes. Therefore we use a `while` loop.
-            let mut index = 0;
-            while let Some(node) = self.nodes.get_mut(index) {
-                if node.state.get() != NodeState::Pending
-                    || !processor.needs_process_obligation(&node.obligation)
-                {
-                    index += 1;
-                    continue;
-                }
+            // If the processor asks for it, each round consists of two passes: the
+            // first one only processes the obligations that `processor.process_early`
+            // selects, the second one all others.
+            let has_early_pass = processor.has_early_pass();
+            let passes: &[bool] = if has_early_pass { &[true, false] } else { &[false] };
+            for &early in passes {
+                // Note that the loop body can append new nodes, and those new nodes
+                // will then be processed by subsequent iterations of the loop.
+                //
+                // We can't use an iterator for the loop because `self.nodes` is
+                // appended to and the borrow checker would complain. We also can't use
+                // `for index in 0..self.nodes.len() { ... }` because the range would
+                // be computed with the initial length, and we would miss the appended
+                // nodes. Therefore we use a `while` loop.
+                let mut index = 0;
+                while let Some(node) = self.nodes.get_mut(index) {
+                    if node.state.get() != NodeState::Pending
+                        || (has_early_pass && processor.process_early(&node.obligation) != early)
+                        || !processor.needs_process_obligation(&node.obligation)
+                    {
+                        index += 1;
+                        continue;
+                    }
 
-                // `processor.process_obligation` can modify the predicate within
-                // `node.obligation`, and that predicate is the key used for
-                // `self.active_cache`. This means that `self.active_cache` can get
-                // out of sync with `nodes`. It's not very common, but it does
-                // happen, and code in `compress` has to allow for it.
+                    // `processor.process_obligation` can modify the predicate within
+                    // `node.obligation`, and that predicate is the key used for
+                    // `self.active_cache`. This means that `self.active_cache` can get
+                    // out of sync with `nodes`. It's not very common, but it does
+                    // happen, and code in `compress` has to allow for it.
 
-                match processor.process_obligation(&mut node.obligation) {
-                    ProcessResult::Unchanged => {
-                        // No change in state.
-                    }
-                    ProcessResult::Changed(children) => {
-                        // We are not (yet) stalled.
-                        has_changed = true;
-                        node.state.set(NodeState::Success);
-
-                        for child in children {
-                            let st = self.register_obligation_at(child, Some(index));
-                            if let Err(()) = st {
-                                // Error already reported - propagate it
-                                // to our node.
-                                self.error_at(index);
+                    match processor.process_obligation(&mut node.obligation) {
+                        ProcessResult::Unchanged => {
+                            // No change in state.
+                        }
+                        ProcessResult::Changed(children) => {
+                            // We are not (yet) stalled.
+                            has_changed = true;
+                            node.state.set(NodeState::Success);
+
+                            for child in children {
+                                let st = self.register_obligation_at(child, Some(index));
+                                if let Err(()) = st {
+                                    // Error already reported - propagate it
+                                    // to our node.
+                                    self.error_at(index);
+                                }
                             }
                         }
+                        ProcessResult::Error(err) => {
+                            has_changed = true;
+                            outcome.record_error(Error {
+                                error: err,
+                                backtrace: self.error_at(index),
+                            });
+                        }
                     }
-                    ProcessResult::Error(err) => {
-                        has_changed = true;
-                        outcome.record_error(Error { error: err, backtrace: self.error_at(index) });
-                    }
+                    index += 1;
+                }
+            }
+
+            if let Some(dir) = processor.dump_dir() {
+                if let Err(error) = self.dump_graphviz(dir, "round") {
+                    processor.dump_failed(dir, error);
                 }
-                index += 1;
             }
 
             // If unchanged, then we saw no successful obligations, which means
@@ -89,6 +89,50 @@ fn process_backedge<'c, I>(
     }
 }
 
+/// Processes the obligations starting with "early" in a first pass of each
+/// round if `has_early_pass` is set, and records the order they were processed in.
+struct EarlyPassProcessor {
+    has_early_pass: bool,
+    processed: Vec<&'static str>,
+}
+
+impl ObligationProcessor for EarlyPassProcessor {
+    type Obligation = &'static str;
+    type Error = &'static str;
+    type OUT = TestOutcome<&'static str, &'static str>;
+
+    fn needs_process_obligation(&self, _obligation: &Self::Obligation) -> bool {
+        true
+    }
+
+    fn process_early(&self, obligation: &Self::Obligation) -> bool {
+        obligation.starts_with("early")
+    }
+
+    fn has_early_pass(&self) -> bool {
+        self.has_early_pass
+    }
+
+    fn process_obligation(
+        &mut self,
+        obligation: &mut Self::Obligation,
+    ) -> ProcessResult<Self::Obligation, Self::Error> {
+        self.processed.push(*obligation);
+        ProcessResult::Changed(vec![])
+    }
+
+    fn process_backedge<'c, I>(
+        &mut self,
+        _cycle: I,
+        _marker: PhantomData<&'c Self::Obligation>,
+    ) -> Result<(), Self::Error>
+    where
+        I: Clone + Iterator<Item = &'c Self::Obligation>,
+    {
+        Ok(())
+    }
+}
+
 #[test]
 fn push_pop() {
     let mut forest = ObligationForest::new();
@@ -389,6 +433,35 @@ fn done_dependency() {
     assert_eq!(err.len(), 0);
 }
 
+#[test]
+fn duplicate_subtrees() {
+    // check that a node registering the same child twice at every level is
+    // processed once per level, and not once per path through the tree
+    const LEVELS: [&str; 17] = [
+        "L0", "L1", "L2", "L3", "L4", "L5", "L6", "L7", "L8", "L9", "L10", "L11", "L12", "L13",
+        "L14", "L15", "L16",
+    ];
+
+    let mut forest = ObligationForest::new();
+    forest.register_obligation(LEVELS[0]);
+
+    let mut processed = 0;
+    let TestOutcome { completed: ok, errors: err, .. } = forest.process_obligations(&mut C(
+        |obligation| {
+            processed += 1;
+            let level = LEVELS.iter().position(|l| *l == *obligation).unwrap();
+            match LEVELS.get(level + 1) {
+                Some(&child) => ProcessResult::Changed(vec![child, child]),
+                None => ProcessResult::Changed(vec![]),
+            }
+        },
+        |_| {},
+    ));
+    assert_eq!(processed, LEVELS.len());
+    assert_eq!(ok.len(), LEVELS.len());
+    assert_eq!(err.len(), 0);
+}
+
 #[test]
 fn orphan() {
     // check that orphaned nodes are handled correctly
@@ -483,3 +556,21 @@ fn simultaneous_register_and_error() {
     assert_eq!(ok.len(), 0);
     assert_eq!(err, vec![super::Error { error: "An error", backtrace: vec!["A"] }]);
 }
+
+#[test]
+fn early_pass() {
+    for has_early_pass in [true, false] {
+        let mut forest = ObligationForest::new();
+        forest.register_obligation("late");
+        forest.register_obligation("early");
+
+        let mut processor = EarlyPassProcessor { has_early_pass, processed: vec![] };
+        let TestOutcome { errors: err, .. } = forest.process_obligations(&mut processor);
+        assert_eq!(err.len(), 0);
+
+        // Without an early pass, every obligation is still processed, in the
+        // order it was registered.
+        let expected = if has_early_pass { ["early", "late"] } else { ["late", "early"] };
+        assert_eq!(processor.processed, expected);
+    }
+}
@@ -129,6 +129,14 @@ lint_noop_method_call = call to `.{$method}()` on a reference in this situation
     .label = unnecessary method call
     .note = the type `{$receiver_ty}` which `{$method}` is being called on is the same as the type returned from `{$method}`, so the method call does not do anything and can be removed
 
+lint_lifetime_dependent_specialization = specializing impl selected for `{$self_ty}: {$trait_path}` based on lifetimes
+    .selected_note = this impl was selected, but is only known to apply for some lifetimes
+    .parent_note = instead of the impl it specializes, which applies for all lifetimes
+
+lint_selected_specializing_impl = specializing impl selected for `{$self_ty}: {$trait_path}`
+    .selected_note = this impl is used
+    .parent_note = instead of the impl it specializes
+
 lint_pass_by_value = passing `{$ty}` by reference
     .suggestion = try passing by value
 
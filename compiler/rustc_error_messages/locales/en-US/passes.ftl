@@ -190,6 +190,10 @@ passes_must_not_suspend =
     `must_not_suspend` attribute should be applied to a struct, enum, or trait
     .label = is not a struct, enum, or trait
 
+passes_fundamental =
+    `fundamental` attribute should be applied to a struct, enum, union, or trait
+    .label = is not a struct, enum, union, or trait
+
 passes_cold =
     {passes_should_be_applied_to_fn}
     .warn = {-passes_previously_accepted}
@@ -707,3 +711,6 @@ passes_ignored_derived_impls =
       [one] trait {$trait_list}, but this is
      *[other] traits {$trait_list}, but these are
     } intentionally ignored during dead code analysis
+
+passes_diagnostic_on_unimplemented_only_for_traits =
+    `#[diagnostic::on_unimplemented]` can only be applied to trait definitions
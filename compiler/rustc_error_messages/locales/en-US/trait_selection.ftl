@@ -12,6 +12,9 @@ trait_selection_empty_on_clause_in_rustc_on_unimplemented = empty `on`-clause in
 trait_selection_invalid_on_clause_in_rustc_on_unimplemented = invalid `on`-clause in `#[rustc_on_unimplemented]`
     .label = invalid on-clause here
 
+trait_selection_invalid_implements_condition_in_rustc_on_unimplemented = invalid `implements` condition in `#[rustc_on_unimplemented]`
+    .label = expected a generic parameter and the diagnostic item of a trait, like `implements = "T: Iterator"`
+
 trait_selection_no_value_in_rustc_on_unimplemented = this attribute must have a valid value
     .label = expected value here
     .note = eg `#[rustc_on_unimplemented(message="foo")]`
@@ -126,6 +126,24 @@ pub struct Diagnostic {
     /// With `-Ztrack_diagnostics` enabled,
     /// we print where in rustc this error was emitted.
     pub emitted_at: DiagnosticLocation,
+
+    /// The chain of causes that required the obligation this diagnostic is
+    /// about. Only emitted in JSON output, the human readable output renders
+    /// it as notes.
+    pub cause_chain: Option<ObligationCauseNode>,
+}
+
+/// One link in the chain of obligation causes attached to a diagnostic, e.g.
+/// "`T: Clone` is required by the impl of `Clone` for `Vec<T>`".
+#[derive(Clone, Debug, Encodable, Decodable)]
+pub struct ObligationCauseNode {
+    /// The kind of cause, e.g. `impl_derived` or `bound`.
+    pub kind: String,
+    /// The predicate that had to hold, if known.
+    pub predicate: Option<String>,
+    pub span: Option<Span>,
+    /// The cause that required this one in turn.
+    pub required_by: Option<Box<ObligationCauseNode>>,
 }
 
 #[derive(Clone, Debug, Encodable, Decodable)]
@@ -231,6 +249,7 @@ pub fn new_with_messages(level: Level, messages: Vec<(DiagnosticMessage, Style)>
             sort_span: DUMMY_SP,
             is_lint: false,
             emitted_at: DiagnosticLocation::caller(),
+            cause_chain: None,
         }
     }
 
@@ -251,6 +270,7 @@ pub fn new_with_code<M: Into<DiagnosticMessage>>(
             sort_span: DUMMY_SP,
             is_lint: false,
             emitted_at: DiagnosticLocation::caller(),
+            cause_chain: None,
         }
     }
 
@@ -926,6 +946,11 @@ pub fn set_is_lint(&mut self) -> &mut Self {
         self
     }
 
+    pub fn set_cause_chain(&mut self, cause_chain: ObligationCauseNode) -> &mut Self {
+        self.cause_chain = Some(cause_chain);
+        self
+    }
+
     pub fn code(&mut self, s: DiagnosticId) -> &mut Self {
         self.code = Some(s);
         self
@@ -1,7 +1,7 @@
 use crate::diagnostic::IntoDiagnosticArg;
 use crate::{
     Diagnostic, DiagnosticId, DiagnosticMessage, DiagnosticStyledString, ErrorGuaranteed,
-    SubdiagnosticMessage,
+    ObligationCauseNode, SubdiagnosticMessage,
 };
 use crate::{Handler, Level, MultiSpan, StashKey};
 use rustc_lint_defs::Applicability;
@@ -565,6 +565,7 @@ pub fn span_labels(
     ) -> &mut Self);
     forward!(pub fn help_use_latest_edition(&mut self,) -> &mut Self);
     forward!(pub fn set_is_lint(&mut self,) -> &mut Self);
+    forward!(pub fn set_cause_chain(&mut self, cause_chain: ObligationCauseNode) -> &mut Self);
 
     forward!(pub fn disable_suggestions(&mut self,) -> &mut Self);
     forward!(pub fn clear_suggestions(&mut self,) -> &mut Self);
@@ -16,7 +16,8 @@
 use crate::translation::{to_fluent_args, Translate};
 use crate::DiagnosticId;
 use crate::{
-    CodeSuggestion, FluentBundle, LazyFallbackBundle, MultiSpan, SpanLabel, SubDiagnostic,
+    CodeSuggestion, FluentBundle, LazyFallbackBundle, MultiSpan, ObligationCauseNode, SpanLabel,
+    SubDiagnostic,
 };
 use rustc_lint_defs::Applicability;
 
@@ -226,6 +227,21 @@ struct Diagnostic {
     children: Vec<Diagnostic>,
     /// The message as rustc would render it.
     rendered: Option<String>,
+    /// Why the obligation this diagnostic is about was required, innermost
+    /// cause first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cause_chain: Option<DiagnosticObligationCause>,
+}
+
+#[derive(Serialize)]
+struct DiagnosticObligationCause {
+    /// The kind of cause, e.g. "impl_derived".
+    kind: String,
+    /// The predicate that was required, if known.
+    predicate: Option<String>,
+    span: Option<DiagnosticSpan>,
+    /// The cause that required this one in turn.
+    required_by: Option<Box<DiagnosticObligationCause>>,
 }
 
 #[derive(Serialize)]
@@ -329,6 +345,7 @@ fn from_errors_diagnostic(diag: &crate::Diagnostic, je: &JsonEmitter) -> Diagnos
                 spans: DiagnosticSpan::from_suggestion(sugg, &args, je),
                 children: vec![],
                 rendered: None,
+                cause_chain: None,
             }
         });
 
@@ -377,6 +394,10 @@ fn flush(&mut self) -> io::Result<()> {
                 .chain(sugg)
                 .collect(),
             rendered: Some(output),
+            cause_chain: diag
+                .cause_chain
+                .as_ref()
+                .map(|cause| DiagnosticObligationCause::from_cause_node(cause, je)),
         }
     }
 
@@ -397,6 +418,21 @@ fn from_sub_diagnostic(
                 .unwrap_or_else(|| DiagnosticSpan::from_multispan(&diag.span, args, je)),
             children: vec![],
             rendered: None,
+            cause_chain: None,
+        }
+    }
+}
+
+impl DiagnosticObligationCause {
+    fn from_cause_node(cause: &ObligationCauseNode, je: &JsonEmitter) -> DiagnosticObligationCause {
+        DiagnosticObligationCause {
+            kind: cause.kind.clone(),
+            predicate: cause.predicate.clone(),
+            span: cause.span.map(|span| DiagnosticSpan::from_span_etc(span, true, None, None, je)),
+            required_by: cause
+                .required_by
+                .as_ref()
+                .map(|parent| Box::new(DiagnosticObligationCause::from_cause_node(parent, je))),
         }
     }
 }
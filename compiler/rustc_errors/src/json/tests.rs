@@ -203,3 +203,70 @@ fn span_after_crlf() {
         },
     )
 }
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+struct CauseTestData {
+    cause_chain: Option<CauseNodeTestData>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+struct CauseNodeTestData {
+    kind: String,
+    predicate: Option<String>,
+    required_by: Option<Box<CauseNodeTestData>>,
+}
+
+#[test]
+fn cause_chain() {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), "fn main() {}".to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let je = JsonEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            None,
+            sm,
+            None,
+            fallback_bundle,
+            true,
+            HumanReadableErrorType::Short(ColorConfig::Never),
+            None,
+            false,
+            false,
+        );
+
+        let handler = Handler::with_emitter(true, None, Box::new(je));
+        let mut diag = crate::Diagnostic::new(crate::Level::Error { lint: false }, "foo");
+        diag.set_cause_chain(ObligationCauseNode {
+            kind: "impl_derived".to_owned(),
+            predicate: Some("T: Clone".to_owned()),
+            span: Some(Span::with_root_ctxt(BytePos(0), BytePos(2))),
+            required_by: Some(Box::new(ObligationCauseNode {
+                kind: "bound".to_owned(),
+                predicate: Some("Vec<T>: Clone".to_owned()),
+                span: None,
+                required_by: None,
+            })),
+        });
+        handler.emit_diagnostic(&mut diag);
+
+        let bytes = output.lock().unwrap();
+        let actual_output = str::from_utf8(&bytes).unwrap();
+        let actual_output: CauseTestData = serde_json::from_str(actual_output).unwrap();
+        assert_eq!(
+            actual_output.cause_chain,
+            Some(CauseNodeTestData {
+                kind: "impl_derived".to_owned(),
+                predicate: Some("T: Clone".to_owned()),
+                required_by: Some(Box::new(CauseNodeTestData {
+                    kind: "bound".to_owned(),
+                    predicate: Some("Vec<T>: Clone".to_owned()),
+                    required_by: None,
+                })),
+            })
+        );
+    })
+}
@@ -944,6 +944,16 @@ fn cfg_accessible(
 
     /// Tools registered with `#![register_tool]` and used by tool attributes and lints.
     fn registered_tools(&self) -> &FxHashSet<Ident>;
+
+    /// Records a trait impl removed by `#[cfg(feature = "...")]` from the item `parent_node`,
+    /// so that its paths can be resolved once the crate is expanded.
+    fn append_stripped_cfg_impl(
+        &mut self,
+        parent_node: NodeId,
+        trait_path: ast::Path,
+        self_ty_path: ast::Path,
+        feature: Symbol,
+    );
 }
 
 pub trait LintStoreExpand {
@@ -28,7 +28,7 @@
 use rustc_session::lint::BuiltinLintDiagnostics;
 use rustc_session::parse::{feature_err, ParseSess};
 use rustc_session::Limit;
-use rustc_span::symbol::{sym, Ident};
+use rustc_span::symbol::{sym, Ident, Symbol};
 use rustc_span::{FileName, LocalExpnId, Span};
 
 use smallvec::SmallVec;
@@ -1040,6 +1040,12 @@ fn take_mac_call(self) -> (P<ast::MacCall>, Self::AttrsTy, AddSemicolon) {
         unreachable!()
     }
     fn pre_flat_map_node_collect_attr(_cfg: &StripUnconfigured<'_>, _attr: &ast::Attribute) {}
+    /// Returns the paths of the trait and the self type of the trait impl that is removed if the
+    /// `#[cfg]` attribute `_attr` on this node evaluates to `false`, along with the feature the
+    /// attribute checks for, if it only checks for a feature.
+    fn cfg_stripped_impl(&self, _attr: &ast::Attribute) -> Option<(ast::Path, ast::Path, Symbol)> {
+        None
+    }
     fn post_flat_map_node_collect_bang(_output: &mut Self::OutputTy, _add_semicolon: AddSemicolon) {
     }
     fn wrap_flat_map_node_noop_flat_map(
@@ -1072,6 +1078,18 @@ fn take_mac_call(self) -> (P<ast::MacCall>, Self::AttrsTy, AddSemicolon) {
             _ => unreachable!(),
         }
     }
+    fn cfg_stripped_impl(&self, attr: &ast::Attribute) -> Option<(ast::Path, ast::Path, Symbol)> {
+        let ItemKind::Impl(impl_) = &self.kind else { return None };
+        let trait_path = &impl_.of_trait.as_ref()?.path;
+        let TyKind::Path(None, self_ty_path) = &impl_.self_ty.kind else { return None };
+        let [predicate] = &attr.meta_item_list()?[..] else { return None };
+        let predicate = predicate.meta_item()?;
+        if !predicate.has_name(sym::feature) {
+            return None;
+        }
+        let feature = predicate.value_str()?;
+        Some((trait_path.clone(), self_ty_path.clone(), feature))
+    }
     fn wrap_flat_map_node_noop_flat_map(
         mut node: Self,
         collector: &mut InvocationCollector<'_, '_>,
@@ -1717,9 +1735,18 @@ fn flat_map_node<Node: InvocationCollectorNode<OutputTy: Default>>(
             return match self.take_first_attr(&mut node) {
                 Some((attr, pos, derives)) => match attr.name_or_empty() {
                     sym::cfg => {
+                        let stripped_impl = node.cfg_stripped_impl(&attr);
                         if self.expand_cfg_true(&mut node, attr, pos) {
                             continue;
                         }
+                        if let Some((trait_path, self_ty_path, feature)) = stripped_impl {
+                            self.cx.resolver.append_stripped_cfg_impl(
+                                self.cx.current_expansion.lint_node_id,
+                                trait_path,
+                                self_ty_path,
+                                feature,
+                            );
+                        }
                         Default::default()
                     }
                     sym::cfg_attr => {
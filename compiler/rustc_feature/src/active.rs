@@ -332,6 +332,11 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, cfg_target_thread_local, "1.7.0", Some(29594), None),
     /// Allow conditional compilation depending on rust version
     (active, cfg_version, "1.45.0", Some(64796), None),
+    // no-tracking-issue-start
+    /// Allows `#[closed_marker]` on marker traits, promising that no impls exist
+    /// outside of the crate defining the trait.
+    (active, closed_marker_traits, "CURRENT_RUSTC_VERSION", None, None),
+    // no-tracking-issue-end
     /// Allows `for<...>` on closures and generators.
     (active, closure_lifetime_binder, "1.64.0", Some(97362), None),
     /// Allows `#[track_caller]` on closures and generators.
@@ -376,6 +381,10 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, deprecated_safe, "1.61.0", Some(94978), None),
     /// Allows having using `suggestion` in the `#[deprecated]` attribute.
     (active, deprecated_suggestion, "1.61.0", Some(94785), None),
+    // no-tracking-issue-start
+    /// Allows using the `#[diagnostic]` attribute tool namespace
+    (active, diagnostic_namespace, "CURRENT_RUSTC_VERSION", None, None),
+    // no-tracking-issue-end
     /// Tells rustdoc to automatically generate `#[doc(cfg(...))]`.
     (active, doc_auto_cfg, "1.58.0", Some(43781), None),
     /// Allows `#[doc(cfg(...))]`.
@@ -472,10 +481,20 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, no_sanitize, "1.42.0", Some(39699), None),
     /// Allows using the `non_exhaustive_omitted_patterns` lint.
     (active, non_exhaustive_omitted_patterns_lint, "1.57.0", Some(89554), None),
+    // no-tracking-issue-start
+    /// Allows traits with associated consts to be object safe, as long as every const has a default.
+    /// Such consts then require `Self: Sized`, so they can't be used through trait objects.
+    (active, object_safe_assoc_consts, "CURRENT_RUSTC_VERSION", None, None),
+    // no-tracking-issue-end
     /// Allows making `dyn Trait` well-formed even if `Trait` is not object safe.
     /// In that case, `dyn Trait: Trait` does not hold. Moreover, coercions and
     /// casts in safe Rust to `dyn Trait` for such a `Trait` is also forbidden.
     (active, object_safe_for_dispatch, "1.40.0", Some(43561), None),
+    // no-tracking-issue-start
+    /// Allows traits with generic associated types that are only used by `where Self: Sized`
+    /// methods to be object safe.
+    (active, object_safe_sized_gats, "CURRENT_RUSTC_VERSION", None, None),
+    // no-tracking-issue-end
     /// Allows using `#[optimize(X)]`.
     (active, optimize_attribute, "1.34.0", Some(54882), None),
     /// Allows `extern "platform-intrinsic" { ... }`.
@@ -504,6 +523,11 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, simd_ffi, "1.0.0", Some(27731), None),
     /// Allows specialization of implementations (RFC 1210).
     (incomplete, specialization, "1.7.0", Some(31844), None),
+    // no-tracking-issue-start
+    /// Allows `#[specialization_marker]` on traits, letting min_specialization
+    /// impls specialize on them like `#[rustc_unsafe_specialization_marker]` traits.
+    (active, specialization_marker_traits, "CURRENT_RUSTC_VERSION", None, None),
+    // no-tracking-issue-end
     /// Allows attributes on expressions and non-item statements.
     (active, stmt_expr_attributes, "1.6.0", Some(15701), None),
     /// Allows lints part of the strict provenance effort.
@@ -443,6 +443,14 @@ pub struct BuiltinAttribute {
     gated!(
         marker, Normal, template!(Word), WarnFollowing, marker_trait_attr, experimental!(marker)
     ),
+    gated!(
+        closed_marker, Normal, template!(Word), WarnFollowing, closed_marker_traits,
+        experimental!(closed_marker)
+    ),
+    gated!(
+        specialization_marker, Normal, template!(Word), WarnFollowing,
+        specialization_marker_traits, experimental!(specialization_marker)
+    ),
     gated!(
         thread_local, Normal, template!(Word), WarnFollowing,
         "`#[thread_local]` is an experimental feature, and does not currently handle destructors",
@@ -822,6 +830,8 @@ pub struct BuiltinAttribute {
     rustc_attr!(TEST, rustc_dump_env_program_clauses, Normal, template!(Word), WarnFollowing),
     rustc_attr!(TEST, rustc_object_lifetime_default, Normal, template!(Word), WarnFollowing),
     rustc_attr!(TEST, rustc_dump_vtable, Normal, template!(Word), WarnFollowing),
+    rustc_attr!(TEST, rustc_dump_specialization_dag, Normal, template!(Word), WarnFollowing),
+    rustc_attr!(TEST, rustc_dump_auto_trait_bounds, Normal, template!(Word), WarnFollowing),
     rustc_attr!(TEST, rustc_dummy, Normal, template!(Word /* doesn't matter*/), DuplicatesOk),
     gated!(
         omit_gdb_pretty_printer_section, Normal, template!(Word), WarnFollowing,
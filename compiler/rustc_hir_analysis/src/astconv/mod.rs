@@ -1384,6 +1384,14 @@ trait here instead: `trait NewTrait: {} {{}}`",
                             tcx.associated_items(pred.def_id())
                                 .in_definition_order()
                                 .filter(|item| item.kind == ty::AssocKind::Type)
+                                .filter(|item| {
+                                    !(tcx.features().object_safe_sized_gats
+                                        && traits::gat_is_sized_confined(
+                                            tcx,
+                                            base_trait_ref.def_id(),
+                                            item.def_id,
+                                        ))
+                                })
                                 .map(|item| item.def_id),
                         );
                     }
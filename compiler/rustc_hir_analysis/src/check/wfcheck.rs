@@ -678,7 +678,7 @@ fn region_known_to_outlive<'tcx>(
 ) -> bool {
     resolve_regions_with_wf_tys(tcx, id, param_env, &wf_tys, |mut infcx, _| {
         use rustc_infer::infer::outlives::obligations::TypeOutlivesDelegate;
-        let origin = infer::RelateRegionParamBound(DUMMY_SP);
+        let origin = infer::RelateRegionParamBound(DUMMY_SP, None);
         // `region_a: region_b` -> `region_b <= region_a`
         infcx.push_sub_region_constraint(
             origin,
@@ -1133,6 +1133,7 @@ fn check_trait(tcx: TyCtxt<'_>, item: &hir::Item<'_>) {
     let def_id = item.owner_id.def_id;
     let trait_def = tcx.trait_def(def_id);
     if trait_def.is_marker
+        || trait_def.is_closed_marker
         || matches!(trait_def.specialization_kind, TraitSpecializationKind::Marker)
     {
         for associated_def_id in &*tcx.associated_item_def_ids(def_id) {
@@ -1329,12 +1330,17 @@ fn check_where_clauses<'tcx>(wfcx: &WfCheckingCtxt<'_, 'tcx>, span: Span, def_id
             }
             GenericParamDefKind::Const { .. } => {
                 if is_our_default(param) {
-                    // FIXME(const_generics_defaults): This
-                    // is incorrect when dealing with unused substs, for example
-                    // for `struct Foo<const N: usize, const M: usize = { 1 - 2 }>`
-                    // we should eagerly error.
+                    // Without `generic_const_exprs`, a default cannot mention any of the
+                    // generic parameters. So even though it has the substs of the parameters
+                    // preceding it, it must be evaluatable as-is, and for example
+                    // `struct Foo<const N: usize, const M: usize = { 1 - 2 }>` eagerly errors.
+                    //
+                    // With `generic_const_exprs`, the default has the substs of all the
+                    // parameters preceding it, whether or not it uses them. So any default
+                    // preceded by another parameter is skipped here and only has to be
+                    // evaluatable where it is used, be it `{ N + 1 }` or `{ 1 - 2 }`.
                     let default_ct = tcx.const_param_default(param.def_id);
-                    if !default_ct.needs_subst() {
+                    if !default_ct.needs_subst() || !tcx.features().generic_const_exprs {
                         wfcx.register_wf_obligation(
                             tcx.def_span(param.def_id),
                             None,
@@ -260,10 +260,13 @@ fn visit_implementation_of_dispatch_from_dyn<'tcx>(tcx: TyCtxt<'tcx>, impl_did:
                                  for structs containing the field being coerced, \
                                  ZST fields with 1 byte alignment, and nothing else",
                             )
-                            .note(&format!(
-                                "extra field `{}` of type `{}` is not allowed",
-                                field.name, ty_a,
-                            ))
+                            .span_note(
+                                tcx.def_span(field.did),
+                                &format!(
+                                    "extra field `{}` of type `{}` is not allowed",
+                                    field.name, ty_a,
+                                ),
+                            )
                             .emit();
 
                             return false;
@@ -1,4 +1,5 @@
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::sync::{par_iter, ParallelIterator};
 use rustc_errors::struct_span_err;
 use rustc_hir as hir;
 use rustc_hir::def::DefKind;
@@ -10,6 +11,7 @@
 use rustc_trait_selection::traits::{self, SkipLeakCheck};
 use smallvec::SmallVec;
 use std::collections::hash_map::Entry;
+use std::iter;
 
 pub fn crate_inherent_impls_overlap_check(tcx: TyCtxt<'_>, (): ()) {
     let mut inherent_overlap_checker = InherentOverlapChecker { tcx };
@@ -134,25 +136,44 @@ fn check_for_common_items_in_impls(
         }
     }
 
-    fn check_for_overlapping_inherent_impls(
-        &self,
-        overlap_mode: OverlapMode,
-        impl1_def_id: DefId,
-        impl2_def_id: DefId,
-    ) {
-        traits::overlapping_impls(
-            self.tcx,
-            impl1_def_id,
-            impl2_def_id,
-            // We go ahead and just skip the leak check for
-            // inherent impls without warning.
-            SkipLeakCheck::Yes,
-            overlap_mode,
-        )
-        .map_or(true, |overlap| {
-            self.check_for_common_items_in_impls(impl1_def_id, impl2_def_id, overlap);
-            false
-        });
+    /// Runs the checks collected for the inherent impls of a single type.
+    ///
+    /// The overlap checks are independent of each other and make up most of the
+    /// work here, so they are run in parallel up front. Errors are then reported
+    /// in the order in which the checks were collected, which keeps the emitted
+    /// diagnostics deterministic.
+    fn run_checks(&self, overlap_mode: OverlapMode, checks: Vec<InherentImplCheck>) {
+        let tcx = self.tcx;
+        let overlaps: Vec<_> = par_iter(&checks)
+            .map(|check| match *check {
+                InherentImplCheck::DuplicateItems(_) => None,
+                InherentImplCheck::Overlap(impl1_def_id, impl2_def_id) => {
+                    traits::overlapping_impls(
+                        tcx,
+                        impl1_def_id,
+                        impl2_def_id,
+                        // We go ahead and just skip the leak check for
+                        // inherent impls without warning.
+                        SkipLeakCheck::Yes,
+                        overlap_mode,
+                    )
+                    .ok()
+                }
+            })
+            .collect();
+
+        for (check, overlap) in iter::zip(checks, overlaps) {
+            match check {
+                InherentImplCheck::DuplicateItems(impl_def_id) => {
+                    self.check_for_duplicate_items_in_impl(impl_def_id);
+                }
+                InherentImplCheck::Overlap(impl1_def_id, impl2_def_id) => {
+                    if let Some(overlap) = overlap {
+                        self.check_for_common_items_in_impls(impl1_def_id, impl2_def_id, overlap);
+                    }
+                }
+            }
+        }
     }
 
     fn check_item(&mut self, id: hir::ItemId) {
@@ -170,21 +191,19 @@ fn check_item(&mut self, id: hir::ItemId) {
             .map(|impl_def_id| (impl_def_id, self.tcx.associated_items(*impl_def_id)))
             .collect::<SmallVec<[_; 8]>>();
 
+        let mut checks = Vec::new();
+
         // Perform a O(n^2) algorithm for small n,
         // otherwise switch to an allocating algorithm with
         // faster asymptotic runtime.
         const ALLOCATING_ALGO_THRESHOLD: usize = 500;
         if impls.len() < ALLOCATING_ALGO_THRESHOLD {
             for (i, &(&impl1_def_id, impl_items1)) in impls_items.iter().enumerate() {
-                self.check_for_duplicate_items_in_impl(impl1_def_id);
+                checks.push(InherentImplCheck::DuplicateItems(impl1_def_id));
 
                 for &(&impl2_def_id, impl_items2) in &impls_items[(i + 1)..] {
                     if self.impls_have_common_items(impl_items1, impl_items2) {
-                        self.check_for_overlapping_inherent_impls(
-                            overlap_mode,
-                            impl1_def_id,
-                            impl2_def_id,
-                        );
+                        checks.push(InherentImplCheck::Overlap(impl1_def_id, impl2_def_id));
                     }
                 }
             }
@@ -316,20 +335,27 @@ struct ConnectedRegion {
                 impl_blocks.sort_unstable();
                 for (i, &impl1_items_idx) in impl_blocks.iter().enumerate() {
                     let &(&impl1_def_id, impl_items1) = &impls_items[impl1_items_idx];
-                    self.check_for_duplicate_items_in_impl(impl1_def_id);
+                    checks.push(InherentImplCheck::DuplicateItems(impl1_def_id));
 
                     for &impl2_items_idx in impl_blocks[(i + 1)..].iter() {
                         let &(&impl2_def_id, impl_items2) = &impls_items[impl2_items_idx];
                         if self.impls_have_common_items(impl_items1, impl_items2) {
-                            self.check_for_overlapping_inherent_impls(
-                                overlap_mode,
-                                impl1_def_id,
-                                impl2_def_id,
-                            );
+                            checks.push(InherentImplCheck::Overlap(impl1_def_id, impl2_def_id));
                         }
                     }
                 }
             }
         }
+
+        self.run_checks(overlap_mode, checks);
     }
 }
+
+/// A check to run on the inherent impls of a type, see `InherentOverlapChecker::run_checks`.
+enum InherentImplCheck {
+    /// Check for duplicate items within a single impl.
+    DuplicateItems(DefId),
+    /// Check whether two impls overlap and, if so, whether they define items with
+    /// the same name.
+    Overlap(DefId, DefId),
+}
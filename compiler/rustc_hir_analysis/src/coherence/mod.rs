@@ -5,6 +5,7 @@
 // done by the orphan and overlap modules. Then we build up various
 // mappings. That mapping code resides here.
 
+use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::{error_code, struct_span_err};
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_middle::ty::query::Providers;
@@ -16,6 +17,7 @@
 mod inherent_impls;
 mod inherent_impls_overlap;
 mod orphan;
+pub mod test;
 mod unsafety;
 
 fn check_impl(tcx: TyCtxt<'_>, impl_def_id: LocalDefId, trait_ref: ty::TraitRef<'_>) {
@@ -33,6 +35,7 @@ fn check_impl(tcx: TyCtxt<'_>, impl_def_id: LocalDefId, trait_ref: ty::TraitRef<
 
     enforce_trait_manually_implementable(tcx, impl_def_id, trait_ref.def_id);
     enforce_empty_impls_for_marker_traits(tcx, impl_def_id, trait_ref.def_id);
+    enforce_lifetime_independent_specialization_marker_impls(tcx, impl_def_id, trait_ref.def_id);
 }
 
 fn enforce_trait_manually_implementable(
@@ -103,6 +106,45 @@ fn enforce_empty_impls_for_marker_traits(
     .emit();
 }
 
+/// Impls of `#[specialization_marker]` traits may be used to select a specializing
+/// impl, which must not depend on lifetimes as those are erased before codegen.
+fn enforce_lifetime_independent_specialization_marker_impls(
+    tcx: TyCtxt<'_>,
+    impl_def_id: LocalDefId,
+    trait_def_id: DefId,
+) {
+    if !tcx.has_attr(trait_def_id, sym::specialization_marker) {
+        return;
+    }
+    let trait_ref = tcx.impl_trait_ref(impl_def_id).unwrap();
+
+    // Every lifetime in the impl header has to be a distinct lifetime parameter.
+    let mut seen_regions = FxHashSet::default();
+    let repeats_or_names_lifetime = tcx.any_free_region_meets(&trait_ref.substs, |region| {
+        !matches!(*region, ty::ReEarlyBound(_)) || !seen_regions.insert(region)
+    });
+    // So does any bound that mentions a lifetime, be it an outlives bound or a trait
+    // bound like `T: Trait<'static>`. Higher-ranked lifetimes are fine.
+    let has_lifetime_bounds =
+        tcx.predicates_of(impl_def_id).predicates.iter().any(|(pred, _)| pred.has_free_regions());
+
+    if repeats_or_names_lifetime || has_lifetime_bounds {
+        let trait_name = tcx.item_name(trait_def_id);
+        tcx.sess
+            .struct_span_err(
+                tcx.def_span(impl_def_id),
+                &format!(
+                    "impls of the specialization marker trait `{trait_name}` cannot depend on lifetimes"
+                ),
+            )
+            .note(
+                "these impls may not name `'static`, repeat a lifetime, \
+                 or have bounds that mention lifetimes",
+            )
+            .emit();
+    }
+}
+
 pub fn provide(providers: &mut Providers) {
     use self::builtin::coerce_unsized_info;
     use self::inherent_impls::{crate_incoherent_impls, crate_inherent_impls, inherent_impls};
@@ -2,9 +2,12 @@
 //! crate or pertains to a type defined in this crate.
 
 use rustc_data_structures::fx::FxHashSet;
-use rustc_errors::{struct_span_err, DelayDm};
+use rustc_errors::{struct_span_err, Applicability, DelayDm};
 use rustc_errors::{Diagnostic, ErrorGuaranteed};
 use rustc_hir as hir;
+use rustc_hir::def::Res;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter;
 use rustc_middle::ty::subst::GenericArgKind;
 use rustc_middle::ty::subst::InternalSubsts;
 use rustc_middle::ty::util::IgnoreRegions;
@@ -13,6 +16,7 @@
 };
 use rustc_session::lint;
 use rustc_span::def_id::{DefId, LocalDefId};
+use rustc_span::symbol::{sym, Symbol};
 use rustc_span::Span;
 use rustc_trait_selection::traits;
 use std::ops::ControlFlow;
@@ -29,6 +33,9 @@ pub(crate) fn orphan_check_impl(
     if tcx.trait_is_auto(trait_ref.def_id) {
         lint_auto_trait_impl(tcx, trait_ref, impl_def_id);
     }
+    if ret.is_ok() {
+        lint_unstable_fundamental_ty(tcx, impl_def_id);
+    }
 
     ret
 }
@@ -99,10 +106,26 @@ fn visit_path(&mut self, path: &'v hir::Path<'v>, _id: hir::HirId) {
         span_bug!(sp, "opaque type not found, but `has_opaque_types` is set")
     }
 
+    if tcx.trait_def(trait_def_id).is_closed_marker && !trait_def_id.is_local() {
+        let reported = tcx
+            .sess
+            .struct_span_err(
+                tr.path.span,
+                &format!(
+                    "cannot implement closed marker trait `{}` outside of the crate defining it",
+                    tcx.def_path_str(trait_def_id)
+                ),
+            )
+            .span_note(tcx.def_span(trait_def_id), "trait marked as `#[closed_marker]` here")
+            .emit();
+        return Err(reported);
+    }
+
     match traits::orphan_check(tcx, item.owner_id.to_def_id()) {
         Ok(()) => {}
         Err(err) => emit_orphan_check_error(
             tcx,
+            def_id,
             sp,
             item.span,
             tr.path.span,
@@ -204,6 +227,7 @@ fn visit_path(&mut self, path: &'v hir::Path<'v>, _id: hir::HirId) {
 
 fn emit_orphan_check_error<'tcx>(
     tcx: TyCtxt<'tcx>,
+    impl_def_id: LocalDefId,
     sp: Span,
     full_impl_span: Span,
     trait_span: Span,
@@ -226,6 +250,7 @@ fn emit_orphan_check_error<'tcx>(
                 "only traits defined in the current crate {msg}"
             );
             err.span_label(sp, "impl doesn't use only types from inside the current crate");
+            let mut suggested_newtype = false;
             for &(mut ty, is_target_ty) in &tys {
                 ty = tcx.erase_regions(ty);
                 ty = match ty.kind() {
@@ -250,6 +275,7 @@ fn emit_orphan_check_error<'tcx>(
                             ptr_ty,
                             &mut err,
                         );
+                        suggested_newtype = true;
 
                         (format!("`{}`", ty), " because raw pointers are always foreign")
                     }
@@ -265,6 +291,17 @@ fn emit_orphan_check_error<'tcx>(
                     err.span_label(trait_span, &msg);
                 }
             }
+            if !suggested_newtype && tys.iter().any(|&(_, is_target_ty)| is_target_ty) {
+                suggest_local_newtype(
+                    tcx,
+                    impl_def_id,
+                    full_impl_span,
+                    self_ty,
+                    self_ty_span,
+                    None,
+                    &mut err,
+                );
+            }
             err.note("define and implement a trait or new type instead");
             err.emit()
         }
@@ -276,63 +313,77 @@ fn emit_orphan_check_error<'tcx>(
                 }
             }
 
-            match local_type {
-                Some(local_type) => struct_span_err!(
-                    tcx.sess,
-                    sp,
-                    E0210,
-                    "type parameter `{}` must be covered by another type \
-                    when it appears before the first local type (`{}`)",
-                    param_ty,
-                    local_type
-                )
-                .span_label(
-                    sp,
-                    format!(
+            let mut err = match local_type {
+                Some(local_type) => {
+                    let mut err = struct_span_err!(
+                        tcx.sess,
+                        sp,
+                        E0210,
                         "type parameter `{}` must be covered by another type \
-                    when it appears before the first local type (`{}`)",
-                        param_ty, local_type
-                    ),
-                )
-                .note(
-                    "implementing a foreign trait is only possible if at \
-                        least one of the types for which it is implemented is local, \
-                        and no uncovered type parameters appear before that first \
-                        local type",
-                )
-                .note(
-                    "in this case, 'before' refers to the following order: \
-                        `impl<..> ForeignTrait<T1, ..., Tn> for T0`, \
-                        where `T0` is the first and `Tn` is the last",
-                )
-                .emit(),
-                None => struct_span_err!(
-                    tcx.sess,
-                    sp,
-                    E0210,
-                    "type parameter `{}` must be used as the type parameter for some \
-                    local type (e.g., `MyStruct<{}>`)",
-                    param_ty,
-                    param_ty
-                )
-                .span_label(
-                    sp,
-                    format!(
+                        when it appears before the first local type (`{}`)",
+                        param_ty,
+                        local_type
+                    );
+                    err.span_label(
+                        sp,
+                        format!(
+                            "type parameter `{}` must be covered by another type \
+                        when it appears before the first local type (`{}`)",
+                            param_ty, local_type
+                        ),
+                    )
+                    .note(
+                        "implementing a foreign trait is only possible if at \
+                            least one of the types for which it is implemented is local, \
+                            and no uncovered type parameters appear before that first \
+                            local type",
+                    )
+                    .note(
+                        "in this case, 'before' refers to the following order: \
+                            `impl<..> ForeignTrait<T1, ..., Tn> for T0`, \
+                            where `T0` is the first and `Tn` is the last",
+                    );
+                    err
+                }
+                None => {
+                    let mut err = struct_span_err!(
+                        tcx.sess,
+                        sp,
+                        E0210,
                         "type parameter `{}` must be used as the type parameter for some \
-                    local type",
+                        local type (e.g., `MyStruct<{}>`)",
                         param_ty,
-                    ),
-                )
-                .note(
-                    "implementing a foreign trait is only possible if at \
-                        least one of the types for which it is implemented is local",
-                )
-                .note(
-                    "only traits defined in the current crate can be \
-                        implemented for a type parameter",
-                )
-                .emit(),
-            }
+                        param_ty
+                    );
+                    err.span_label(
+                        sp,
+                        format!(
+                            "type parameter `{}` must be used as the type parameter for some \
+                        local type",
+                            param_ty,
+                        ),
+                    )
+                    .note(
+                        "implementing a foreign trait is only possible if at \
+                            least one of the types for which it is implemented is local",
+                    )
+                    .note(
+                        "only traits defined in the current crate can be \
+                            implemented for a type parameter",
+                    );
+                    err
+                }
+            };
+            suggest_local_newtype(
+                tcx,
+                impl_def_id,
+                full_impl_span,
+                self_ty,
+                self_ty_span,
+                Some(param_ty),
+                &mut err,
+            );
+            err.emit()
         }
     })
 }
@@ -358,6 +409,270 @@ fn emit_newtype_suggestion_for_raw_ptr(
     }
 }
 
+/// Suggests wrapping the foreign self type of an impl in a local new type, which forwards to
+/// the wrapped value through `Deref`, and implementing the trait for that new type instead.
+/// The items of the impl are kept, with their uses of `self` forwarded to the wrapped value.
+/// If the impl was rejected for the uncovered type parameter `uncovered_param`, the
+/// suggestion names it.
+///
+/// The suggestion is only made for a positive impl without attributes which defines every
+/// required item of a trait that has no supertraits and no where-clauses on `Self`, whose
+/// items can be forwarded (see `forward_impl_items_to_inner`), and if the name of the new
+/// type is neither taken in the crate nor picked for another impl.
+fn suggest_local_newtype<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    impl_def_id: LocalDefId,
+    full_impl_span: Span,
+    self_ty: Ty<'tcx>,
+    self_ty_span: Span,
+    uncovered_param: Option<Ty<'tcx>>,
+    diag: &mut Diagnostic,
+) {
+    let (params, field_ty) = match uncovered_param {
+        // `impl<T> ForeignTrait for T`: wrap the type parameter itself.
+        Some(param_ty) if param_ty == self_ty => (format!("<{}>", param_ty), param_ty.to_string()),
+        Some(_) => return,
+        // Wrapping a type that mentions generic parameters would require copying
+        // over the generics of the impl as well, so only handle concrete types.
+        None if !self_ty.needs_subst() => {
+            let Ok(snippet) = tcx.sess.source_map().span_to_snippet(self_ty_span) else {
+                return;
+            };
+            (String::new(), snippet)
+        }
+        None => return,
+    };
+    let Some(name) = newtype_name(tcx, self_ty) else { return };
+    let trait_def_id = tcx.trait_id_of_impl(impl_def_id.to_def_id()).unwrap();
+    let implemented_items = tcx.impl_item_implementor_ids(impl_def_id);
+    let hir = tcx.hir();
+    let fits = !tcx.sess.contains_name(hir.krate_attrs(), sym::no_core)
+        && hir.attrs(hir.local_def_id_to_hir_id(impl_def_id)).is_empty()
+        && tcx.impl_polarity(impl_def_id) == ImplPolarity::Positive
+        && self_ty.is_sized(tcx, tcx.param_env(impl_def_id))
+        && !tcx.has_attr(trait_def_id, sym::rustc_deny_explicit_impl)
+        && tcx.super_predicates_of(trait_def_id).predicates.is_empty()
+        // Where-clauses that don't mention `Self` hold for the new type as they did before.
+        && tcx.explicit_predicates_of(trait_def_id).predicates.iter().all(|(pred, _)| {
+            pred.visit_with(&mut MentionsSelfVisitor).is_continue()
+        })
+        && tcx
+            .associated_items(trait_def_id)
+            .in_definition_order()
+            .all(|item| {
+                item.defaultness(tcx).has_value() || implemented_items.contains_key(&item.def_id)
+            });
+    if !fits {
+        return;
+    }
+    let Some(forwarding) = forward_impl_items_to_inner(tcx, impl_def_id) else { return };
+    // Checking the identifiers of the HIR items also covers the names brought in by `use`
+    // items. The wrapper would also clash with the one suggested for another orphan impl.
+    let name_is_free = hir.items().all(|id| {
+        let other_def_id = id.owner_id.def_id;
+        hir.item(id).ident.name != name
+            && (other_def_id == impl_def_id
+                || !matches!(tcx.def_kind(other_def_id), hir::def::DefKind::Impl)
+                || tcx.impl_trait_ref(other_def_id).map_or(true, |trait_ref| {
+                    trait_ref.def_id.is_local()
+                        || newtype_name(tcx, tcx.type_of(other_def_id)) != Some(name)
+                }))
+    });
+    if !name_is_free {
+        return;
+    }
+
+    let wrapper = format!("{name}{params}");
+    let mut sugg = vec![
+        (
+            full_impl_span.shrink_to_lo(),
+            format!(
+                "struct {wrapper}({field_ty});\n\n\
+                impl{params} core::ops::Deref for {wrapper} {{\n    \
+                    type Target = {field_ty};\n\n    \
+                    fn deref(&self) -> &{field_ty} {{\n        \
+                        &self.0\n    \
+                    }}\n\
+                }}\n\n"
+            ),
+        ),
+        (self_ty_span, wrapper),
+    ];
+    sugg.extend(forwarding);
+    // The wrapper's name is only checked against the items of the crate, not resolved in the
+    // scope of the impl, so it could still shadow or be shadowed by another name there, and
+    // the items of the impl are only rewritten syntactically.
+    let msg = match uncovered_param {
+        Some(param_ty) => {
+            format!("consider wrapping the uncovered type parameter `{param_ty}` in a local type")
+        }
+        None => "consider introducing a new wrapper type".to_owned(),
+    };
+    diag.multipart_suggestion(msg, sugg, Applicability::MaybeIncorrect);
+}
+
+/// Rewrites the uses of `self` in the items of an impl so that they go through the field of
+/// the new type that wraps the old self type: `self.len()` becomes `self.0.len()`, `*self`
+/// becomes `self.0`, and `self` used as a value becomes `&self.0` for a `&self` receiver.
+///
+/// Returns `None` if the items can't be forwarded this way, because they mention `Self`,
+/// whose meaning would change, take `self` by an explicit type, or use `self` from within a
+/// macro expansion.
+fn forward_impl_items_to_inner(
+    tcx: TyCtxt<'_>,
+    impl_def_id: LocalDefId,
+) -> Option<Vec<(Span, String)>> {
+    let hir = tcx.hir();
+    let hir::ItemKind::Impl(impl_) = hir.expect_item(impl_def_id).kind else {
+        bug!("expected impl, found {:?}", impl_def_id);
+    };
+    let mut visitor =
+        ForwardSelfVisitor { tcx, self_param: None, forwarding: vec![], unforwardable: false };
+    for item_ref in impl_.items {
+        let item = hir.impl_item(item_ref.id);
+        visitor.visit_generics(item.generics);
+        match item.kind {
+            hir::ImplItemKind::Const(ty, body_id) => {
+                visitor.self_param = None;
+                visitor.visit_ty(ty);
+                visitor.visit_nested_body(body_id);
+            }
+            hir::ImplItemKind::Fn(ref sig, body_id) => {
+                let body = hir.body(body_id);
+                // The type of an implicit `self` is `Self`, but that one is rewritten along
+                // with the self type of the impl. Explicit `self: Box<Self>` is not.
+                let inputs = if sig.decl.implicit_self.has_implicit_self() {
+                    &sig.decl.inputs[1..]
+                } else {
+                    sig.decl.inputs
+                };
+                for ty in inputs {
+                    visitor.visit_ty(ty);
+                }
+                visitor.visit_fn_ret_ty(&sig.decl.output);
+                visitor.self_param = match (sig.decl.implicit_self, body.params.first()) {
+                    (kind, Some(param)) if kind.has_implicit_self() => match param.pat.kind {
+                        hir::PatKind::Binding(_, hir_id, ..) => Some((hir_id, kind)),
+                        _ => return None,
+                    },
+                    _ => None,
+                };
+                visitor.visit_body(body);
+            }
+            hir::ImplItemKind::Type(ty) => visitor.visit_ty(ty),
+        }
+        if visitor.unforwardable {
+            return None;
+        }
+    }
+    Some(visitor.forwarding)
+}
+
+struct ForwardSelfVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The `self` parameter of the function being visited, with how it is received.
+    self_param: Option<(hir::HirId, hir::ImplicitSelfKind)>,
+    forwarding: Vec<(Span, String)>,
+    unforwardable: bool,
+}
+
+impl ForwardSelfVisitor<'_> {
+    fn self_kind(&self, expr: &hir::Expr<'_>) -> Option<hir::ImplicitSelfKind> {
+        let (self_hir_id, kind) = self.self_param?;
+        match expr.kind {
+            hir::ExprKind::Path(hir::QPath::Resolved(None, path))
+                if path.res == Res::Local(self_hir_id) =>
+            {
+                Some(kind)
+            }
+            _ => None,
+        }
+    }
+
+    fn forward(&mut self, span: Span, inner: &str) {
+        if span.from_expansion() {
+            self.unforwardable = true;
+        } else {
+            self.forwarding.push((span, inner.to_owned()));
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for ForwardSelfVisitor<'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        match ex.kind {
+            // Method calls, field accesses and indexing auto-reference and auto-dereference
+            // the wrapped value just as they did `self`.
+            hir::ExprKind::MethodCall(segment, receiver, args, _)
+                if self.self_kind(receiver).is_some() =>
+            {
+                self.forward(receiver.span, "self.0");
+                self.visit_path_segment(segment);
+                for arg in args {
+                    self.visit_expr(arg);
+                }
+            }
+            hir::ExprKind::Field(base, _) if self.self_kind(base).is_some() => {
+                self.forward(base.span, "self.0");
+            }
+            hir::ExprKind::Index(base, index) if self.self_kind(base).is_some() => {
+                self.forward(base.span, "self.0");
+                self.visit_expr(index);
+            }
+            hir::ExprKind::Unary(hir::UnOp::Deref, inner)
+                if matches!(
+                    self.self_kind(inner),
+                    Some(hir::ImplicitSelfKind::ImmRef | hir::ImplicitSelfKind::MutRef)
+                ) =>
+            {
+                self.forward(ex.span, "self.0");
+            }
+            _ => match self.self_kind(ex) {
+                Some(hir::ImplicitSelfKind::ImmRef) => self.forward(ex.span, "&self.0"),
+                Some(hir::ImplicitSelfKind::MutRef) => self.forward(ex.span, "&mut self.0"),
+                Some(_) => self.forward(ex.span, "self.0"),
+                None => intravisit::walk_expr(self, ex),
+            },
+        }
+    }
+
+    fn visit_path(&mut self, path: &'tcx hir::Path<'tcx>, _id: hir::HirId) {
+        if matches!(path.res, Res::SelfTyAlias { .. } | Res::SelfCtor(_)) {
+            self.unforwardable = true;
+        }
+        intravisit::walk_path(self, path);
+    }
+}
+
+struct MentionsSelfVisitor;
+
+impl<'tcx> TypeVisitor<'tcx> for MentionsSelfVisitor {
+    type BreakTy = ();
+
+    fn visit_ty(&mut self, t: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
+        if t.is_param(0) { ControlFlow::BREAK } else { t.super_visit_with(self) }
+    }
+}
+
+/// Picks a name for a local new type wrapping `ty`, e.g. `LocalVec` for `Vec<u8>`.
+fn newtype_name<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Symbol> {
+    let name = match ty.kind() {
+        ty::Adt(def, _) => tcx.item_name(def.did()).to_string(),
+        ty::Param(param) => param.name.to_string(),
+        _ if ty.is_primitive() => ty.to_string(),
+        _ => return None,
+    };
+    let mut chars = name.chars();
+    let first = chars.next()?.to_uppercase();
+    Some(Symbol::intern(&format!("Local{}{}", first, chars.as_str())))
+}
+
 /// Lint impls of auto traits if they are likely to have
 /// unsound or surprising effects on auto impls.
 fn lint_auto_trait_impl<'tcx>(
@@ -499,3 +814,40 @@ fn visit_ty(&mut self, t: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
         })
         .is_break()
 }
+
+/// Lints impls which only pass the orphan check because a foreign type outside
+/// of the standard library is marked `#[fundamental]`.
+fn lint_unstable_fundamental_ty(tcx: TyCtxt<'_>, impl_def_id: LocalDefId) {
+    let hir_id = tcx.hir().local_def_id_to_hir_id(impl_def_id);
+    if tcx.lint_level_at_node(lint::builtin::UNSTABLE_FUNDAMENTAL_TYPE, hir_id).0
+        == lint::Level::Allow
+    {
+        return;
+    }
+
+    let Some(fundamental_ty) =
+        traits::orphan_check_unstable_fundamental_ty(tcx, impl_def_id.to_def_id())
+    else {
+        return;
+    };
+
+    tcx.struct_span_lint_hir(
+        lint::builtin::UNSTABLE_FUNDAMENTAL_TYPE,
+        hir_id,
+        tcx.def_span(impl_def_id),
+        DelayDm(|| {
+            format!(
+                "impl relies on the upstream type `{}` being `#[fundamental]`",
+                tcx.item_name(fundamental_ty)
+            )
+        }),
+        |lint| {
+            lint.note(&format!(
+                "`{}` is only `#[fundamental]` through an unstable feature of crate `{}`",
+                tcx.item_name(fundamental_ty),
+                tcx.crate_name(fundamental_ty.krate),
+            ))
+            .note("without the attribute, this impl would violate the orphan rules")
+        },
+    );
+}
@@ -0,0 +1,52 @@
+use rustc_middle::ty::TyCtxt;
+use rustc_span::symbol::sym;
+use rustc_trait_selection::traits::auto_trait::{AutoTraitFinder, AutoTraitResult};
+
+pub fn test_specialization_dag(tcx: TyCtxt<'_>) {
+    // For unit testing: check for a special "rustc_dump_specialization_dag"
+    // attribute on traits and report an error for each of their impls.
+    for id in tcx.hir().items() {
+        let trait_id = id.owner_id.to_def_id();
+        if tcx.has_attr(trait_id, sym::rustc_dump_specialization_dag) {
+            for node in tcx.specialization_dag(trait_id) {
+                let mut err = tcx.sess.struct_span_err(
+                    tcx.def_span(node.impl_def_id),
+                    &format!("always applicable: {}", node.always_applicable),
+                );
+                if let Some(parent) = node.parent {
+                    err.span_note(tcx.def_span(parent), "specializes this impl");
+                }
+                err.emit();
+            }
+        }
+    }
+}
+
+pub fn test_auto_trait_bounds(tcx: TyCtxt<'_>) {
+    // For unit testing: check for a special "rustc_dump_auto_trait_bounds"
+    // attribute on types and report an error with the bounds under which
+    // they implement `Send` and `Sync`.
+    let finder = AutoTraitFinder::new(tcx);
+    for id in tcx.hir().items() {
+        let def_id = id.owner_id.to_def_id();
+        if !tcx.has_attr(def_id, sym::rustc_dump_auto_trait_bounds) {
+            continue;
+        }
+        let ty = tcx.type_of(def_id);
+        let param_env = tcx.param_env(def_id);
+        for trait_name in [sym::Send, sym::Sync] {
+            let Some(trait_def_id) = tcx.get_diagnostic_item(trait_name) else { continue };
+            let msg = match finder.find_auto_trait_bounds(ty, param_env, trait_def_id) {
+                AutoTraitResult::PositiveImpl(bounds) => {
+                    let bounds: Vec<_> = bounds.iter().map(|pred| pred.to_string()).collect();
+                    format!("`{trait_name}` bounds: [{}]", bounds.join(", "))
+                }
+                AutoTraitResult::NegativeImpl => format!("`{trait_name}` is not implemented"),
+                AutoTraitResult::ExplicitImpl => {
+                    format!("`{trait_name}` is implemented explicitly")
+                }
+            };
+            tcx.sess.span_err(tcx.def_span(def_id), &msg);
+        }
+    }
+}
@@ -953,9 +953,12 @@ fn trait_def(tcx: TyCtxt<'_>, def_id: DefId) -> ty::TraitDef {
     }
 
     let is_marker = tcx.has_attr(def_id, sym::marker);
+    let is_closed_marker = tcx.has_attr(def_id, sym::closed_marker);
     let skip_array_during_method_dispatch =
         tcx.has_attr(def_id, sym::rustc_skip_array_during_method_dispatch);
-    let spec_kind = if tcx.has_attr(def_id, sym::rustc_unsafe_specialization_marker) {
+    let spec_kind = if tcx.has_attr(def_id, sym::rustc_unsafe_specialization_marker)
+        || tcx.has_attr(def_id, sym::specialization_marker)
+    {
         ty::trait_def::TraitSpecializationKind::Marker
     } else if tcx.has_attr(def_id, sym::rustc_specialization_trait) {
         ty::trait_def::TraitSpecializationKind::AlwaysApplicable
@@ -1064,6 +1067,7 @@ fn trait_def(tcx: TyCtxt<'_>, def_id: DefId) -> ty::TraitDef {
         paren_sugar,
         is_auto,
         is_marker,
+        is_closed_marker,
         skip_array_during_method_dispatch,
         spec_kind,
         must_implement_one_of,
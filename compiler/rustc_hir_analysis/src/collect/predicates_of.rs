@@ -248,6 +248,17 @@ fn gather_explicit_predicates_of(tcx: TyCtxt<'_>, def_id: DefId) -> ty::GenericP
         }
     }
 
+    // With `object_safe_assoc_consts`, an associated const with a default value can't be
+    // used through a trait object, as it would have to use the default even for types
+    // whose impl overrides it. Requiring `Self: Sized` for it keeps the trait object safe.
+    if let Node::TraitItem(&TraitItem { kind: TraitItemKind::Const(_, Some(_)), .. }) = node
+        && tcx.features().object_safe_assoc_consts
+        && let Some(sized) = tcx.lang_items().sized_trait()
+    {
+        let trait_ref = ty::Binder::dummy(tcx.mk_trait_ref(sized, [tcx.types.self_param]));
+        predicates.insert((trait_ref.without_const().to_predicate(tcx), tcx.def_span(def_id)));
+    }
+
     if tcx.features().generic_const_exprs {
         predicates.extend(const_evaluatable_predicates_of(tcx, def_id.expect_local()));
     }
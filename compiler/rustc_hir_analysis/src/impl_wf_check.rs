@@ -65,7 +65,11 @@ fn check_mod_impl_wf(tcx: TyCtxt<'_>, module_def_id: LocalDefId) {
 }
 
 pub fn provide(providers: &mut Providers) {
-    *providers = Providers { check_mod_impl_wf, ..*providers };
+    *providers = Providers {
+        check_mod_impl_wf,
+        impl_is_always_applicable: min_specialization::impl_is_always_applicable,
+        ..*providers
+    };
 }
 
 fn enforce_impl_params_are_constrained(tcx: TyCtxt<'_>, impl_def_id: LocalDefId) {
@@ -64,6 +64,13 @@
 //! when it doesn't apply, but we allow it in the short term since it can't
 //! cause use after frees with purely safe code in the same way as specializing
 //! on traits with methods can.
+//!
+//! ### Associated type equalities
+//!
+//! The specializing impl may also equate associated types of the trait bounds
+//! of the base impl with concrete types that don't mention any lifetimes, such
+//! as `I: Iterator<Item = u8>`, as long as the base impl leaves its own
+//! associated types `default`. See `is_specializable_projection`.
 
 use crate::constrained_generic_params as cgp;
 use crate::errors::SubstsOnOverriddenImpl;
@@ -89,6 +96,32 @@ pub(super) fn check_min_specialization(tcx: TyCtxt<'_>, impl_def_id: LocalDefId)
     }
 }
 
+/// Query provider for `impl_is_always_applicable`.
+///
+/// This runs the same checks as `check_always_applicable`, but against the
+/// trait itself instead of the parent impl and without reporting errors, so
+/// it holds if the impl applies to all instantiations of its trait ref,
+/// ignoring bounds on specialization traits.
+pub(super) fn impl_is_always_applicable(tcx: TyCtxt<'_>, impl_def_id: DefId) -> bool {
+    let trait_ref = tcx.impl_trait_ref(impl_def_id).unwrap();
+    let impl_substs = InternalSubsts::identity_for_item(tcx, impl_def_id);
+    let parent_substs = trait_ref.substs.to_vec();
+    let trait_node = Node::Trait(trait_ref.def_id);
+    let span = tcx.def_span(impl_def_id);
+
+    check_static_lifetimes(tcx, &parent_substs, span, false)
+        && check_duplicate_params(tcx, impl_substs, &parent_substs, span, false)
+        && check_predicates(
+            tcx,
+            impl_def_id,
+            impl_substs,
+            trait_node,
+            trait_ref.substs,
+            span,
+            false,
+        )
+}
+
 fn parent_specialization_node(tcx: TyCtxt<'_>, impl1_def_id: LocalDefId) -> Option<Node> {
     let trait_ref = tcx.impl_trait_ref(impl1_def_id)?;
     let trait_def = tcx.trait_def(trait_ref.def_id);
@@ -105,6 +138,9 @@ fn parent_specialization_node(tcx: TyCtxt<'_>, impl1_def_id: LocalDefId) -> Opti
 }
 
 /// Check that `impl1` is a sound specialization
+///
+/// The individual checks return whether the impl passes them, and only emit
+/// errors if `report` is set, so that `impl_is_always_applicable` can share them.
 #[instrument(level = "debug", skip(tcx))]
 fn check_always_applicable(tcx: TyCtxt<'_>, impl1_def_id: LocalDefId, impl2_node: Node) {
     if let Some((impl1_substs, impl2_substs)) = get_impl_substs(tcx, impl1_def_id, impl2_node) {
@@ -119,9 +155,17 @@ fn check_always_applicable(tcx: TyCtxt<'_>, impl1_def_id: LocalDefId, impl2_node
 
         let span = tcx.def_span(impl1_def_id);
         check_constness(tcx, impl1_def_id, impl2_node, span);
-        check_static_lifetimes(tcx, &parent_substs, span);
-        check_duplicate_params(tcx, impl1_substs, &parent_substs, span);
-        check_predicates(tcx, impl1_def_id, impl1_substs, impl2_node, impl2_substs, span);
+        check_static_lifetimes(tcx, &parent_substs, span, true);
+        check_duplicate_params(tcx, impl1_substs, &parent_substs, span, true);
+        check_predicates(
+            tcx,
+            impl1_def_id.to_def_id(),
+            impl1_substs,
+            impl2_node,
+            impl2_substs,
+            span,
+            true,
+        );
     }
 }
 
@@ -268,15 +312,20 @@ fn check_duplicate_params<'tcx>(
     impl1_substs: SubstsRef<'tcx>,
     parent_substs: &Vec<GenericArg<'tcx>>,
     span: Span,
-) {
+    report: bool,
+) -> bool {
     let mut base_params = cgp::parameters_for(parent_substs, true);
     base_params.sort_by_key(|param| param.0);
     if let (_, [duplicate, ..]) = base_params.partition_dedup() {
-        let param = impl1_substs[duplicate.0 as usize];
-        tcx.sess
-            .struct_span_err(span, &format!("specializing impl repeats parameter `{}`", param))
-            .emit();
+        if report {
+            let param = impl1_substs[duplicate.0 as usize];
+            tcx.sess
+                .struct_span_err(span, &format!("specializing impl repeats parameter `{}`", param))
+                .emit();
+        }
+        return false;
     }
+    true
 }
 
 /// Check that `'static` lifetimes are not introduced by the specializing impl.
@@ -291,10 +340,15 @@ fn check_static_lifetimes<'tcx>(
     tcx: TyCtxt<'tcx>,
     parent_substs: &Vec<GenericArg<'tcx>>,
     span: Span,
-) {
+    report: bool,
+) -> bool {
     if tcx.any_free_region_meets(parent_substs, |r| r.is_static()) {
-        tcx.sess.struct_span_err(span, "cannot specialize on `'static` lifetime").emit();
+        if report {
+            tcx.sess.struct_span_err(span, "cannot specialize on `'static` lifetime").emit();
+        }
+        return false;
     }
+    true
 }
 
 /// Check whether predicates on the specializing impl (`impl1`) are allowed.
@@ -307,15 +361,17 @@ fn check_static_lifetimes<'tcx>(
 ///     * This check is done using the `trait_predicates_eq` function below.
 /// * A well-formed predicate of a type argument of the trait being implemented,
 ///   including the `Self`-type.
+/// * An associated type equality allowed by `is_specializable_projection`.
 #[instrument(level = "debug", skip(tcx))]
 fn check_predicates<'tcx>(
     tcx: TyCtxt<'tcx>,
-    impl1_def_id: LocalDefId,
+    impl1_def_id: DefId,
     impl1_substs: SubstsRef<'tcx>,
     impl2_node: Node,
     impl2_substs: SubstsRef<'tcx>,
     span: Span,
-) {
+    report: bool,
+) -> bool {
     let instantiated = tcx.predicates_of(impl1_def_id).instantiate(tcx, impl1_substs);
     let impl1_predicates: Vec<_> = traits::elaborate_predicates_with_span(
         tcx,
@@ -368,17 +424,14 @@ fn check_predicates<'tcx>(
     });
 
     // Include the well-formed predicates of the type parameters of the impl.
+    let impl1_hir_id = impl1_def_id
+        .as_local()
+        .map_or(hir::CRATE_HIR_ID, |def_id| tcx.hir().local_def_id_to_hir_id(def_id));
     for arg in tcx.impl_trait_ref(impl1_def_id).unwrap().substs {
         let infcx = &tcx.infer_ctxt().build();
-        let obligations = wf::obligations(
-            infcx,
-            tcx.param_env(impl1_def_id),
-            tcx.hir().local_def_id_to_hir_id(impl1_def_id),
-            0,
-            arg,
-            span,
-        )
-        .unwrap();
+        let obligations =
+            wf::obligations(infcx, tcx.param_env(impl1_def_id), impl1_hir_id, 0, arg, span)
+                .unwrap();
 
         assert!(!obligations.needs_infer());
         impl2_predicates.extend(
@@ -390,11 +443,61 @@ fn check_predicates<'tcx>(
             .map(|obligation| obligation.predicate),
     );
 
+    let mut allowed = true;
     for (predicate, span) in impl1_predicates {
-        if !impl2_predicates.iter().any(|pred2| trait_predicates_eq(tcx, predicate, *pred2, span)) {
-            check_specialization_on(tcx, predicate, span)
+        if !impl2_predicates
+            .iter()
+            .any(|pred2| trait_predicates_eq(tcx, predicate, *pred2, span, report))
+            && !is_specializable_projection(tcx, predicate, impl2_node, &impl2_predicates)
+        {
+            allowed &= check_specialization_on(tcx, predicate, span, report);
         }
     }
+    allowed
+}
+
+/// Checks if an associated type equality on the specializing impl, like
+/// `I: Iterator<Item = u8>`, can be specialized on.
+///
+/// This is the case if:
+///
+/// * The associated type is equated with a type that mentions neither
+///   generic parameters nor lifetimes, so the specialization can't depend on
+///   how regions are instantiated.
+/// * The trait bound the associated type belongs to is present on the base
+///   impl, so the projection can be normalized there as well.
+/// * The base impl leaves all of its associated types `default`, so code
+///   using the base impl can't rely on which of the impls applies.
+fn is_specializable_projection<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    predicate: ty::Predicate<'tcx>,
+    impl2_node: Node,
+    impl2_predicates: &[ty::Predicate<'tcx>],
+) -> bool {
+    let Some(ty::PredicateKind::Projection(ty::ProjectionPredicate { projection_ty, term })) =
+        predicate.kind().no_bound_vars()
+    else {
+        return false;
+    };
+
+    if impl2_node.is_from_trait() || term.needs_subst() || term.has_free_regions() {
+        return false;
+    }
+
+    let trait_ref = projection_ty.trait_ref(tcx);
+    let bound_on_base_impl = impl2_predicates.iter().any(|pred2| {
+        matches!(
+            pred2.kind().skip_binder(),
+            ty::PredicateKind::Trait(trait_pred2) if trait_pred2.trait_ref == trait_ref
+        )
+    });
+
+    bound_on_base_impl
+        && tcx
+            .associated_items(impl2_node.def_id())
+            .in_definition_order()
+            .filter(|item| item.kind == ty::AssocKind::Type)
+            .all(|item| tcx.impl_defaultness(item.def_id).is_default())
 }
 
 /// Checks if some predicate on the specializing impl (`predicate1`) is the same
@@ -419,12 +522,14 @@ fn check_predicates<'tcx>(
 /// impl<T: Bound + Specialize> const Tr for T { } // should be T: ~const Bound
 /// ```
 ///
-/// So we make that check in this function and try to raise a helpful error message.
+/// So we make that check in this function and try to raise a helpful error message
+/// if `report` is set. This doesn't affect whether the impl applies.
 fn trait_predicates_eq<'tcx>(
     tcx: TyCtxt<'tcx>,
     predicate1: ty::Predicate<'tcx>,
     predicate2: ty::Predicate<'tcx>,
     span: Span,
+    report: bool,
 ) -> bool {
     let pred1_kind = predicate1.kind().skip_binder();
     let pred2_kind = predicate2.kind().skip_binder();
@@ -450,7 +555,7 @@ fn trait_predicates_eq<'tcx>(
     // Check that the predicate on the specializing impl is at least as const as
     // the one on the base.
     match (trait_pred2.constness, trait_pred1.constness) {
-        (ty::BoundConstness::ConstIfConst, ty::BoundConstness::NotConst) => {
+        (ty::BoundConstness::ConstIfConst, ty::BoundConstness::NotConst) if report => {
             tcx.sess.struct_span_err(span, "missing `~const` qualifier for specialization").emit();
         }
         _ => {}
@@ -460,43 +565,34 @@ fn trait_predicates_eq<'tcx>(
 }
 
 #[instrument(level = "debug", skip(tcx))]
-fn check_specialization_on<'tcx>(tcx: TyCtxt<'tcx>, predicate: ty::Predicate<'tcx>, span: Span) {
-    match predicate.kind().skip_binder() {
+fn check_specialization_on<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    predicate: ty::Predicate<'tcx>,
+    span: Span,
+    report: bool,
+) -> bool {
+    let message = match predicate.kind().skip_binder() {
         // Global predicates are either always true or always false, so we
         // are fine to specialize on.
-        _ if predicate.is_global() => (),
+        _ if predicate.is_global() => return true,
         // We allow specializing on explicitly marked traits with no associated
         // items.
         ty::PredicateKind::Trait(ty::TraitPredicate { trait_ref, constness: _, polarity: _ }) => {
-            if !matches!(
-                trait_predicate_kind(tcx, predicate),
-                Some(TraitSpecializationKind::Marker)
-            ) {
-                tcx.sess
-                    .struct_span_err(
-                        span,
-                        &format!(
-                            "cannot specialize on trait `{}`",
-                            tcx.def_path_str(trait_ref.def_id),
-                        ),
-                    )
-                    .emit();
+            if matches!(trait_predicate_kind(tcx, predicate), Some(TraitSpecializationKind::Marker))
+            {
+                return true;
             }
+            format!("cannot specialize on trait `{}`", tcx.def_path_str(trait_ref.def_id))
         }
         ty::PredicateKind::Projection(ty::ProjectionPredicate { projection_ty, term }) => {
-            tcx.sess
-                .struct_span_err(
-                    span,
-                    &format!("cannot specialize on associated type `{projection_ty} == {term}`",),
-                )
-                .emit();
-        }
-        _ => {
-            tcx.sess
-                .struct_span_err(span, &format!("cannot specialize on predicate `{}`", predicate))
-                .emit();
+            format!("cannot specialize on associated type `{projection_ty} == {term}`")
         }
+        _ => format!("cannot specialize on predicate `{}`", predicate),
+    };
+    if report {
+        tcx.sess.struct_span_err(span, &message).emit();
     }
+    false
 }
 
 fn trait_predicate_kind<'tcx>(
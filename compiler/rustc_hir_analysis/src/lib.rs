@@ -511,6 +511,16 @@ pub fn check_crate(tcx: TyCtxt<'_>) -> Result<(), ErrorGuaranteed> {
         });
     })?;
 
+    if tcx.features().rustc_attrs {
+        tcx.sess.track_errors(|| {
+            tcx.sess.time("specialization_dag_testing", || {
+                coherence::test::test_specialization_dag(tcx)
+            });
+            tcx.sess
+                .time("auto_trait_bounds_testing", || coherence::test::test_auto_trait_bounds(tcx));
+        })?;
+    }
+
     if tcx.features().rustc_attrs {
         tcx.sess.track_errors(|| {
             tcx.sess.time("variance_testing", || variance::test::test_variance(tcx));
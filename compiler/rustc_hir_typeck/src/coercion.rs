@@ -116,6 +116,18 @@ fn coerce_mutbls<'tcx>(
     }
 }
 
+/// Converts a `CamelCase` trait name to `snake_case`, for naming a suggested accessor method.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len());
+    for (i, c) in name.char_indices() {
+        if c.is_uppercase() && i != 0 {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
 /// Do not require any adjustments, i.e. coerce `x -> x`.
 fn identity(_: Ty<'_>) -> Vec<Adjustment<'_>> {
     vec![]
@@ -728,21 +740,87 @@ fn coerce_unsized(&self, mut source: Ty<'tcx>, mut target: Ty<'tcx>) -> CoerceRe
         if let Some((sub, sup)) = has_trait_upcasting_coercion
             && !self.tcx().features().trait_upcasting
         {
-            // Renders better when we erase regions, since they're not really the point here.
-            let (sub, sup) = self.tcx.erase_regions((sub, sup));
-            let mut err = feature_err(
-                &self.tcx.sess.parse_sess,
-                sym::trait_upcasting,
-                self.cause.span,
-                &format!("cannot cast `{sub}` to `{sup}`, trait upcasting coercion is experimental"),
-            );
-            err.note(&format!("required when coercing `{source}` into `{target}`"));
-            err.emit();
+            self.report_trait_upcasting_feature_gate(source, target, sub, sup);
         }
 
         Ok(coercion)
     }
 
+    /// Reports a trait upcasting coercion from `sub` to `sup` while the `trait_upcasting`
+    /// feature is disabled, pointing out the supertrait relationship and describing an
+    /// accessor method on the subtrait as a workaround for plain references.
+    fn report_trait_upcasting_feature_gate(
+        &self,
+        source: Ty<'tcx>,
+        target: Ty<'tcx>,
+        sub: Ty<'tcx>,
+        sup: Ty<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        // Renders better when we erase regions, since they're not really the point here.
+        let (sub, sup) = tcx.erase_regions((sub, sup));
+        let span = self.cause.span;
+        let mut err = feature_err(
+            &tcx.sess.parse_sess,
+            sym::trait_upcasting,
+            span,
+            &format!("cannot cast `{sub}` to `{sup}`, trait upcasting coercion is experimental"),
+        );
+        err.note(&format!("required when coercing `{source}` into `{target}`"));
+
+        let (ty::Dynamic(data_a, ..), ty::Dynamic(data_b, ..)) = (sub.kind(), sup.kind()) else {
+            err.emit();
+            return;
+        };
+        let (Some(sub_trait), Some(sup_trait)) =
+            (data_a.principal_def_id(), data_b.principal_def_id())
+        else {
+            err.emit();
+            return;
+        };
+        let sub_name = tcx.def_path_str(sub_trait);
+        let sup_name = tcx.def_path_str(sup_trait);
+        err.span_label(span, format!("`{sub_name}` is upcast to its supertrait `{sup_name}` here"));
+        if sub_trait.is_local() {
+            err.span_note(
+                tcx.def_span(sub_trait),
+                &format!("`{sup_name}` is a supertrait of `{sub_name}`"),
+            );
+        }
+
+        // Without the feature, the subtrait can still hand out the supertrait object itself,
+        // since coercing `&Self` to `&dyn Sup` inside an impl is an ordinary unsizing. Only
+        // point this out if the user can actually add such a method to the subtrait. As the
+        // method doesn't exist yet, this is a help rather than a suggestion for the call site,
+        // which would not compile on its own.
+        if sub_trait.is_local()
+            && let ty::Ref(_, _, mutbl) = *source.kind()
+            && let ty::Ref(..) = *target.kind()
+        {
+            let method = format!(
+                "as_{}{}",
+                to_snake_case(tcx.item_name(sup_trait).as_str()),
+                match mutbl {
+                    hir::Mutability::Mut => "_mut",
+                    hir::Mutability::Not => "",
+                },
+            );
+            let reference = format!("&{}", mutbl.prefix_str());
+            // A reference to a trait object with auto traits needs parentheses.
+            let return_ty = if data_b.auto_traits().next().is_some() {
+                format!("{reference}({sup})")
+            } else {
+                format!("{reference}{sup}")
+            };
+            err.help(&format!(
+                "consider adding a method `fn {method}({reference}self) -> {return_ty}` to \
+                 `{sub_name}` which returns `self` in each impl, and calling it here instead of \
+                 coercing",
+            ));
+        }
+        err.emit();
+    }
+
     fn coerce_dyn_star(
         &self,
         a: Ty<'tcx>,
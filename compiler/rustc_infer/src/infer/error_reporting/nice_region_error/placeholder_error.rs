@@ -4,15 +4,20 @@
 use crate::infer::{SubregionOrigin, TypeTrace};
 use crate::traits::{ObligationCause, ObligationCauseCode};
 use rustc_data_structures::intern::Interned;
-use rustc_errors::{Diagnostic, DiagnosticBuilder, ErrorGuaranteed};
+use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, ErrorGuaranteed};
+use rustc_hir as hir;
 use rustc_hir::def::Namespace;
 use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit::Visitor;
 use rustc_middle::ty::error::ExpectedFound;
+use rustc_middle::ty::fast_reject::{DeepRejectCtxt, TreatParams};
 use rustc_middle::ty::print::{FmtPrinter, Print, RegionHighlightMode};
 use rustc_middle::ty::subst::SubstsRef;
 use rustc_middle::ty::{self, RePlaceholder, ReVar, Region, TyCtxt};
+use rustc_span::{BytePos, Span, Symbol};
 
 use std::fmt::{self, Write};
+use std::iter;
 
 impl<'tcx> NiceRegionError<'_, 'tcx> {
     /// When given a `ConcreteFailure` for a function with arguments containing a named region and
@@ -300,10 +305,85 @@ fn report_trait_placeholder_mismatch(
             any_self_ty_has_vid,
             leading_ellipsis,
         );
+        self.suggest_impl_generic_over_static_lifetime(&mut err, actual_trait_ref);
 
         err
     }
 
+    /// If the trait is only implemented for the actual trait ref by a local impl which requires
+    /// a lifetime to be `'static`, points at that lifetime and suggests making the impl generic
+    /// over it instead.
+    fn suggest_impl_generic_over_static_lifetime(
+        &self,
+        err: &mut Diagnostic,
+        actual_trait_ref: ty::TraitRef<'tcx>,
+    ) {
+        let tcx = self.tcx();
+        if !tcx.any_free_region_meets(&actual_trait_ref, |r| r.is_static()) {
+            return;
+        }
+        let drcx = DeepRejectCtxt { treat_obligation_params: TreatParams::AsInfer };
+        let mut impls = tcx.all_impls(actual_trait_ref.def_id).filter(|&impl_def_id| {
+            let impl_trait_ref = tcx.impl_trait_ref(impl_def_id).unwrap();
+            iter::zip(actual_trait_ref.substs, impl_trait_ref.substs)
+                .all(|(arg, impl_arg)| drcx.generic_args_may_unify(arg, impl_arg))
+        });
+        let (Some(impl_def_id), None) = (impls.next(), impls.next()) else { return };
+        let Some(hir::Node::Item(hir::Item { kind: hir::ItemKind::Impl(impl_), .. })) =
+            impl_def_id.as_local().and_then(|def_id| tcx.hir().find_by_def_id(def_id))
+            else { return };
+
+        struct Lifetimes {
+            static_spans: Vec<Span>,
+            names: Vec<Symbol>,
+        }
+        impl<'v> Visitor<'v> for Lifetimes {
+            fn visit_lifetime(&mut self, lifetime: &'v hir::Lifetime) {
+                match lifetime.name {
+                    hir::LifetimeName::Static => self.static_spans.push(lifetime.span),
+                    hir::LifetimeName::Param(..) => self.names.push(lifetime.name.ident().name),
+                    _ => {}
+                }
+            }
+        }
+        let mut lifetimes = Lifetimes { static_spans: vec![], names: vec![] };
+        lifetimes.visit_generics(impl_.generics);
+        if let Some(of_trait) = &impl_.of_trait {
+            lifetimes.visit_trait_ref(of_trait);
+        }
+        lifetimes.visit_ty(impl_.self_ty);
+        if lifetimes.static_spans.is_empty() {
+            return;
+        }
+        lifetimes.names.extend(impl_.generics.params.iter().map(|param| param.name.ident().name));
+
+        err.span_note(
+            lifetimes.static_spans.clone(),
+            if lifetimes.static_spans.len() == 1 {
+                "the impl requires this lifetime to be `'static`"
+            } else {
+                "the impl requires these lifetimes to be `'static`"
+            },
+        );
+        let new_lt = (b'a'..=b'z')
+            .map(|c| format!("'{}", c as char))
+            .find(|candidate| !lifetimes.names.iter().any(|name| name.as_str() == candidate))
+            .unwrap_or_else(|| "'lt".to_string());
+        let generics_span = impl_.generics.span;
+        let mut sugg = vec![if generics_span.is_empty() {
+            (generics_span, format!("<{new_lt}>"))
+        } else {
+            let after_open_angle = generics_span.lo() + BytePos(1);
+            (generics_span.with_lo(after_open_angle).shrink_to_lo(), format!("{new_lt}, "))
+        }];
+        sugg.extend(lifetimes.static_spans.into_iter().map(|span| (span, new_lt.clone())));
+        err.multipart_suggestion_verbose(
+            "consider making the impl generic over the lifetime",
+            sugg,
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     /// Add notes with details about the expected and actual trait refs, with attention to cases
     /// when placeholder regions are involved: either the trait or the self type containing
     /// them needs to be mentioned the closest to the placeholders.
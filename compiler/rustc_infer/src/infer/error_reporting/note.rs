@@ -67,9 +67,13 @@ pub(super) fn note_region_origin(&self, err: &mut Diagnostic, origin: &Subregion
                         .add_to_diagnostic(err);
                 }
             }
-            infer::RelateRegionParamBound(span) => {
+            infer::RelateRegionParamBound(span, opt_span) => {
                 RegionOriginNote::Plain { span, msg: fluent::infer_relate_region_param_bound }
                     .add_to_diagnostic(err);
+                if let Some(span) = opt_span {
+                    RegionOriginNote::Plain { span, msg: fluent::infer_relate_param_bound_2 }
+                        .add_to_diagnostic(err);
+                }
             }
             infer::CompareImplItemObligation { span, .. } => {
                 RegionOriginNote::Plain { span, msg: fluent::infer_compare_impl_item_obligation }
@@ -240,7 +244,7 @@ pub(super) fn report_concrete_failure(
                 }
                 err
             }
-            infer::RelateRegionParamBound(span) => {
+            infer::RelateRegionParamBound(span, opt_span) => {
                 let mut err =
                     struct_span_err!(self.tcx.sess, span, E0478, "lifetime bound not satisfied");
                 note_and_explain_region(
@@ -256,8 +260,8 @@ pub(super) fn report_concrete_failure(
                     &mut err,
                     "but lifetime parameter must outlive ",
                     sub,
-                    "",
-                    None,
+                    if opt_span.is_some() { " as required by this binding" } else { "" },
+                    opt_span,
                 );
                 err
             }
@@ -389,7 +389,7 @@ pub enum SubregionOrigin<'tcx> {
 
     /// The given region parameter was instantiated with a region
     /// that must outlive some other region.
-    RelateRegionParamBound(Span),
+    RelateRegionParamBound(Span, Option<Span>),
 
     /// Creating a pointer `b` to contents of another reference
     Reborrow(Span),
@@ -1026,8 +1026,18 @@ pub fn region_outlives_predicate(
         predicate: ty::PolyRegionOutlivesPredicate<'tcx>,
     ) {
         let ty::OutlivesPredicate(r_a, r_b) = self.replace_bound_vars_with_placeholders(predicate);
-        let origin =
-            SubregionOrigin::from_obligation_cause(cause, || RelateRegionParamBound(cause.span));
+        let origin = SubregionOrigin::from_obligation_cause(cause, || {
+            RelateRegionParamBound(
+                cause.span,
+                match cause.code().peel_derives() {
+                    traits::ObligationCauseCode::BindingObligation(_, span)
+                    | traits::ObligationCauseCode::ExprBindingObligation(_, span, ..) => {
+                        Some(*span)
+                    }
+                    _ => None,
+                },
+            )
+        });
         self.sub_regions(origin, r_b, r_a); // `b : a` ==> `a <= b`
     }
 
@@ -1961,7 +1971,7 @@ pub fn span(&self) -> Span {
             Subtype(ref a) => a.span(),
             RelateObjectBound(a) => a,
             RelateParamBound(a, ..) => a,
-            RelateRegionParamBound(a) => a,
+            RelateRegionParamBound(a, _) => a,
             Reborrow(a) => a,
             ReborrowUpvar(a, _) => a,
             DataBorrowed(_, a) => a,
@@ -66,7 +66,17 @@ pub fn report_object_safety_error<'tcx>(
             reported_violations.insert(ObjectSafetyViolation::SizedSelf(vec![].into()));
         }
         if reported_violations.insert(violation.clone()) {
-            let spans = violation.spans();
+            let mut spans = violation.spans();
+            if let ObjectSafetyViolation::Method(..) = violation
+                && let Some(item) = tcx
+                    .object_safety_violation_items(trait_def_id)
+                    .iter()
+                    .find(|item| item.violation == *violation)
+                && !item.self_spans.is_empty()
+            {
+                // Point at the uses of `Self` rather than at the whole parameter or return type.
+                spans = item.self_spans.iter().copied().collect();
+            }
             let msg = if trait_span.is_none() || spans.is_empty() {
                 format!("the trait cannot be made into an object because {}", violation.error_msg())
             } else {
@@ -829,6 +829,17 @@ pub fn create_global_ctxt<'tcx>(
 /// Runs the resolution, type-checking, region checking and other
 /// miscellaneous analysis passes on the crate.
 fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
+    // Write the selection and proof trees even if analysis fails, as that is
+    // when they are the most useful.
+    struct DumpSelectionTree<'tcx>(TyCtxt<'tcx>);
+    impl Drop for DumpSelectionTree<'_> {
+        fn drop(&mut self) {
+            traits::dump_selection_tree(self.0);
+            traits::dump_proof_trees(self.0);
+        }
+    }
+    let _dump_selection_tree = DumpSelectionTree(tcx);
+
     rustc_passes::hir_id_validator::check_crate(tcx);
 
     let sess = tcx.sess;
@@ -648,6 +648,9 @@ macro_rules! untracked {
     untracked!(dump_mir_dir, String::from("abc"));
     untracked!(dump_mir_exclude_pass_number, true);
     untracked!(dump_mir_graphviz, true);
+    untracked!(dump_obligation_forest, Some(String::from("abc")));
+    untracked!(dump_proof_tree, Some("Trait".to_string()));
+    untracked!(dump_selection_tree, Some("selection_tree.dot".to_string()));
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
     untracked!(future_incompat_test, true);
@@ -712,6 +715,7 @@ macro_rules! tracked {
     // tidy-alphabetical-start
     tracked!(allow_features, Some(vec![String::from("lang_items")]));
     tracked!(always_encode_mir, true);
+    tracked!(always_explain_intercrate_ambiguity, true);
     tracked!(asm_comments, true);
     tracked!(assume_incomplete_release, true);
     tracked!(binary_dep_depinfo, true);
@@ -724,7 +728,9 @@ macro_rules! tracked {
         })
     );
     tracked!(chalk, true);
+    tracked!(classic_coherence_solver, true);
     tracked!(codegen_backend, Some("abc".to_string()));
+    tracked!(coinductive_traits, true);
     tracked!(crate_attr, vec!["abc".to_string()]);
     tracked!(debug_info_for_profiling, true);
     tracked!(debug_macros, true);
@@ -739,12 +745,14 @@ macro_rules! tracked {
     tracked!(fuel, Some(("abc".to_string(), 99)));
     tracked!(function_sections, Some(false));
     tracked!(human_readable_cgu_names, true);
+    tracked!(implied_bounds_from_impl_headers, true);
     tracked!(inline_in_all_cgus, Some(true));
     tracked!(inline_mir, Some(true));
     tracked!(inline_mir_hint_threshold, Some(123));
     tracked!(inline_mir_threshold, Some(123));
     tracked!(instrument_coverage, Some(InstrumentCoverage::All));
     tracked!(instrument_mcount, true);
+    tracked!(lazy_normalization_in_selection, true);
     tracked!(link_only, true);
     tracked!(llvm_plugins, vec![String::from("plugin_name")]);
     tracked!(location_detail, LocationDetail { file: true, line: false, column: false });
@@ -768,6 +776,7 @@ macro_rules! tracked {
     tracked!(polonius, true);
     tracked!(precise_enum_drop_elaboration, false);
     tracked!(print_fuel, Some("abc".to_string()));
+    tracked!(prioritize_cheap_obligations, true);
     tracked!(profile, true);
     tracked!(profile_emit, Some(PathBuf::from("abc")));
     tracked!(profile_sample_use, Some(PathBuf::from("abc")));
@@ -775,7 +784,9 @@ macro_rules! tracked {
     tracked!(relax_elf_relocations, Some(true));
     tracked!(relro_level, Some(RelroLevel::Full));
     tracked!(remap_cwd_prefix, Some(PathBuf::from("abc")));
+    tracked!(report_all_impl_overlaps, true);
     tracked!(report_delayed_bugs, true);
+    tracked!(reveal_opaque_types_in_defining_scope, true);
     tracked!(sanitizer, SanitizerSet::ADDRESS);
     tracked!(sanitizer_memory_track_origins, 2);
     tracked!(sanitizer_recover, SanitizerSet::ADDRESS);
@@ -68,6 +68,7 @@
 mod pass_by_value;
 mod passes;
 mod redundant_semicolon;
+mod specialization_selection;
 mod traits;
 mod types;
 mod unused;
@@ -100,6 +101,7 @@
 use opaque_hidden_inferred_bound::*;
 use pass_by_value::*;
 use redundant_semicolon::*;
+use specialization_selection::*;
 use traits::*;
 use types::*;
 use unused::*;
@@ -230,6 +232,7 @@ macro_rules! late_lint_mod_passes {
                 InvalidAtomicOrdering: InvalidAtomicOrdering,
                 NamedAsmLabels: NamedAsmLabels,
                 OpaqueHiddenInferredBound: OpaqueHiddenInferredBound,
+                SpecializationSelection: SpecializationSelection,
             ]
         );
     };
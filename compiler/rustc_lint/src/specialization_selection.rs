@@ -0,0 +1,252 @@
+use crate::context::LintContext;
+use crate::LateContext;
+use crate::LateLintPass;
+use rustc_errors::fluent;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
+use rustc_hir::{Expr, ExprKind};
+use rustc_infer::infer::outlives::env::OutlivesEnvironment;
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_middle::traits::ImplSource;
+use rustc_middle::ty::{self, TyCtxt, TypeVisitable};
+use rustc_session::lint::Level;
+use rustc_span::Span;
+use rustc_trait_selection::traits::{
+    self, ObligationCause, ObligationCtxt, SelectionContext, TraitQueryMode,
+};
+
+declare_lint! {
+    /// The `lifetime_dependent_specialization` lint detects uses of a
+    /// specializing impl which is only known to apply for some choice of
+    /// lifetimes, while the impl it specializes applies for all of them.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// #![feature(min_specialization, rustc_attrs)]
+    /// #![warn(lifetime_dependent_specialization)]
+    ///
+    /// #[rustc_unsafe_specialization_marker]
+    /// trait StaticMarker {}
+    /// impl StaticMarker for &'static str {}
+    ///
+    /// trait Describe { fn describe(&self) -> &'static str; }
+    /// impl<T> Describe for T { default fn describe(&self) -> &'static str { "any" } }
+    /// impl<T: StaticMarker> Describe for T { fn describe(&self) -> &'static str { "static" } }
+    ///
+    /// fn f(s: &'static str) -> &'static str { s.describe() }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Lifetimes are erased before code generation, so it can't tell apart
+    /// `&'a str` and `&'static str`. If the specializing impl only applies
+    /// for some lifetimes, code generation may pick a different impl than
+    /// the type checker did, which is unsound. `min_specialization` rejects
+    /// such impls unless they specialize on unsafe specialization markers,
+    /// and this lint points out the places where the choice still depends
+    /// on lifetimes.
+    ///
+    /// This lint is "allow" by default because evaluation reports region
+    /// constraints conservatively, so it can fire for specializations which
+    /// are actually fine.
+    pub LIFETIME_DEPENDENT_SPECIALIZATION,
+    Allow,
+    "specializing impls which are selected based on lifetimes"
+}
+
+declare_lint! {
+    /// The `selected_specializing_impl` lint detects uses of a trait item
+    /// which resolve to a specializing impl, so that the impl it specializes
+    /// is not used.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// #![feature(min_specialization)]
+    /// #![warn(selected_specializing_impl)]
+    ///
+    /// trait Describe {
+    ///     fn describe(&self) -> &'static str;
+    /// }
+    ///
+    /// impl<T> Describe for T {
+    ///     default fn describe(&self) -> &'static str {
+    ///         "something"
+    ///     }
+    /// }
+    ///
+    /// impl Describe for u8 {
+    ///     fn describe(&self) -> &'static str {
+    ///         "a byte"
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     println!("{}", 0u8.describe());
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// With specialization, the most specific impl which applies is used
+    /// without any indication of which impls were considered. This lint lets
+    /// authors verify which impl is actually used at a given place.
+    ///
+    /// This lint is "allow" by default because selecting a specializing impl
+    /// is the intended behavior, and it fires for every such use.
+    pub SELECTED_SPECIALIZING_IMPL,
+    Allow,
+    "uses of trait items which resolve to a specializing impl"
+}
+
+declare_lint_pass!(SpecializationSelection => [
+    LIFETIME_DEPENDENT_SPECIALIZATION,
+    SELECTED_SPECIALIZING_IMPL,
+]);
+
+impl<'tcx> LateLintPass<'tcx> for SpecializationSelection {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
+        // Without specialization, the impl we find below never has a parent impl.
+        let features = cx.tcx.features();
+        if !features.specialization && !features.min_specialization {
+            return;
+        }
+        // Selecting the impl for every use is too expensive to do for allowed lints.
+        let is_allowed = |lint| cx.tcx.lint_level_at_node(lint, expr.hir_id).0 == Level::Allow;
+        if is_allowed(SELECTED_SPECIALIZING_IMPL) && is_allowed(LIFETIME_DEPENDENT_SPECIALIZATION) {
+            return;
+        }
+
+        // We only care about uses of trait methods and associated constants.
+        let res = match &expr.kind {
+            ExprKind::MethodCall(..) => cx.typeck_results().type_dependent_def(expr.hir_id),
+            ExprKind::Path(qpath) => match cx.qpath_res(qpath, expr.hir_id) {
+                Res::Def(kind, did) => Some((kind, did)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some((DefKind::AssocFn | DefKind::AssocConst, did)) = res else { return };
+        let Some(trait_id) = cx.tcx.trait_of_item(did) else { return };
+
+        // Not every use of a trait item has substs for the whole trait recorded.
+        let Some(substs) = cx.typeck_results().node_substs_opt(expr.hir_id) else { return };
+        if substs.len() < cx.tcx.generics_of(trait_id).count() {
+            return;
+        }
+        let trait_ref = ty::TraitRef::from_method(cx.tcx, trait_id, substs);
+        if trait_ref.references_error() {
+            return;
+        }
+
+        // Look at the impl that code generation is going to use, so select with erased
+        // regions in a fresh inference context, which does not depend on the state of the
+        // selection cache. Selection errors, including overflow, just mean there is nothing
+        // to point out, as they have been reported by type checking already.
+        let param_env = cx.param_env.with_reveal_all_normalized(cx.tcx);
+        let Ok(trait_ref) = cx.tcx.try_normalize_erasing_regions(param_env, trait_ref) else {
+            return;
+        };
+        let infcx = cx.tcx.infer_ctxt().ignoring_regions().build();
+        let mut selcx = SelectionContext::with_query_mode(&infcx, TraitQueryMode::Canonical);
+        let obligation = traits::Obligation::new(
+            cx.tcx,
+            ObligationCause::dummy_with_span(expr.span),
+            param_env,
+            ty::Binder::dummy(trait_ref).to_poly_trait_predicate(),
+        );
+        let Ok(Some(ImplSource::UserDefined(data))) = selcx.select(&obligation) else {
+            return;
+        };
+        let impl_def_id = data.impl_def_id;
+        let Some(&parent) = cx.tcx.specialization_graph_of(trait_id).parent.get(&impl_def_id)
+        else {
+            return;
+        };
+        if parent == trait_id {
+            return;
+        }
+
+        cx.struct_span_lint(
+            SELECTED_SPECIALIZING_IMPL,
+            expr.span,
+            fluent::lint_selected_specializing_impl,
+            |lint| {
+                lint.set_arg("self_ty", trait_ref.self_ty())
+                    .set_arg("trait_path", trait_ref.print_only_trait_path().to_string())
+                    .span_note(cx.tcx.def_span(impl_def_id), fluent::selected_note)
+                    .span_note(cx.tcx.def_span(parent), fluent::parent_note)
+            },
+        );
+
+        if features.min_specialization && !features.specialization {
+            let applies_for_all_lifetimes = |def_id| {
+                impl_applies_for_all_lifetimes(cx.tcx, cx.param_env, def_id, trait_ref, expr.span)
+            };
+            if !applies_for_all_lifetimes(impl_def_id) && applies_for_all_lifetimes(parent) {
+                cx.struct_span_lint(
+                    LIFETIME_DEPENDENT_SPECIALIZATION,
+                    expr.span,
+                    fluent::lint_lifetime_dependent_specialization,
+                    |lint| {
+                        lint.set_arg("self_ty", trait_ref.self_ty())
+                            .set_arg("trait_path", trait_ref.print_only_trait_path().to_string())
+                            .span_note(cx.tcx.def_span(impl_def_id), fluent::selected_note)
+                            .span_note(cx.tcx.def_span(parent), fluent::parent_note)
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Whether `impl_def_id` is known to apply to `trait_ref` for every choice of
+/// the lifetimes in it, which code generation has erased.
+fn impl_applies_for_all_lifetimes<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    impl_def_id: DefId,
+    trait_ref: ty::TraitRef<'tcx>,
+    span: Span,
+) -> bool {
+    let infcx = tcx.infer_ctxt().build();
+    // Stand in for the erased lifetimes with distinct placeholders, so that the impl
+    // requiring anything of them, like being `'static`, shows up as a region error.
+    let universe = infcx.create_next_universe();
+    let mut next_region = 0;
+    let trait_ref = tcx.fold_regions(trait_ref, |r, _| {
+        if r.is_erased() {
+            let name = ty::BrAnon(next_region, None);
+            next_region += 1;
+            tcx.mk_region(ty::RePlaceholder(ty::PlaceholderRegion { universe, name }))
+        } else {
+            r
+        }
+    });
+
+    let impl_substs = infcx.fresh_substs_for_item(span, impl_def_id);
+    let impl_trait_ref = tcx.bound_impl_trait_ref(impl_def_id).unwrap().subst(tcx, impl_substs);
+    let cause = ObligationCause::dummy_with_span(span);
+    let ocx = ObligationCtxt::new(&infcx);
+    if ocx.eq(&cause, param_env, impl_trait_ref, trait_ref).is_err() {
+        return false;
+    }
+    let predicates = tcx.predicates_of(impl_def_id).instantiate(tcx, impl_substs).predicates;
+    ocx.register_obligations(
+        predicates
+            .into_iter()
+            .map(|predicate| traits::Obligation::new(tcx, cause.clone(), param_env, predicate)),
+    );
+    if !ocx.select_all_or_error().is_empty() {
+        return false;
+    }
+
+    let outlives_env = OutlivesEnvironment::new(param_env);
+    infcx.process_registered_region_obligations(outlives_env.region_bound_pairs(), param_env);
+    infcx.resolve_regions(&outlives_env).is_empty()
+}
@@ -1470,6 +1470,85 @@
     };
 }
 
+declare_lint! {
+    /// The `coherence_relies_on_fundamental_trait` lint detects impls which
+    /// are only disjoint because an upstream `#[fundamental]` trait is not
+    /// implemented for some type which the current crate does not own.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(coherence_relies_on_fundamental_trait)]
+    ///
+    /// trait Handler {}
+    /// impl<F: Fn()> Handler for F {}
+    /// impl Handler for u8 {}
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Coherence normally assumes that an upstream crate may add new impls of
+    /// its traits in a minor release. Marking a trait `#[fundamental]` opts out
+    /// of this, so the impls above are accepted because `u8` does not implement
+    /// `Fn()`. Only the upstream crate could add such an impl, and doing so
+    /// would make the two impls overlap. This lint points out impls whose
+    /// coherence depends on such a promise from an upstream crate.
+    pub COHERENCE_RELIES_ON_FUNDAMENTAL_TRAIT,
+    Allow,
+    "impls which are only disjoint because an upstream `#[fundamental]` trait is not implemented"
+}
+
+declare_lint! {
+    /// The `unstable_fundamental_type` lint detects trait impls which only
+    /// pass the orphan check because an upstream type outside of the standard
+    /// library is marked `#[fundamental]`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs an upstream crate)
+    /// // upstream crate
+    /// #![feature(fundamental)]
+    /// #[fundamental]
+    /// pub struct Wrapper<T>(pub T);
+    ///
+    /// // downstream crate
+    /// #![deny(unstable_fundamental_type)]
+    /// struct Local;
+    /// impl std::fmt::Display for upstream::Wrapper<Local> { /* ... */ }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// error: impl relies on the upstream type `Wrapper` being `#[fundamental]`
+    ///  --> src/main.rs:3:1
+    ///   |
+    /// 3 | impl std::fmt::Display for upstream::Wrapper<Local> {
+    ///   | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    ///   |
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Crates can mark their own types `#[fundamental]` with the unstable
+    /// `fundamental` feature, which lets downstream crates implement foreign
+    /// traits for them as long as a local type is inside. Unlike for `Box` or
+    /// `Pin`, nothing guarantees that the attribute stays: once the upstream
+    /// crate removes it, such impls are rejected by the orphan check.
+    ///
+    /// This lint is "allow" by default and is not a future-incompatibility
+    /// lint. The compiler is not going to reject these impls: only the
+    /// upstream crate can break them, and it needs a nightly compiler to
+    /// use the attribute at all, so its users already opted into unstable
+    /// APIs. The lint is for downstream crates that want to know about this
+    /// dependency on an unstable promise.
+    pub UNSTABLE_FUNDAMENTAL_TYPE,
+    Allow,
+    "impls which only pass the orphan check because of a foreign `#[fundamental]` type"
+}
+
 declare_lint! {
     /// The `deprecated` lint detects use of deprecated items.
     ///
@@ -3206,6 +3285,8 @@
         LATE_BOUND_LIFETIME_ARGUMENTS,
         ORDER_DEPENDENT_TRAIT_OBJECTS,
         COHERENCE_LEAK_CHECK,
+        COHERENCE_RELIES_ON_FUNDAMENTAL_TRAIT,
+        UNSTABLE_FUNDAMENTAL_TYPE,
         DEPRECATED,
         UNUSED_UNSAFE,
         UNUSED_MUT,
@@ -3271,6 +3352,7 @@
         FFI_UNWIND_CALLS,
         REPR_TRANSPARENT_EXTERNAL_PRIVATE_FIELDS,
         NAMED_ARGUMENTS_USED_POSITIONALLY,
+        UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES,
     ]
 }
 
@@ -4003,3 +4085,29 @@
     Warn,
     "named arguments in format used positionally"
 }
+
+declare_lint! {
+    /// The `unknown_or_malformed_diagnostic_attributes` lint detects unrecognized or otherwise
+    /// malformed diagnostic attributes.
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// #![feature(diagnostic_namespace)]
+    /// #[diagnostic::does_not_exist]
+    /// struct Foo;
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// It is usually a mistake to specify a diagnostic attribute that does not exist. Check
+    /// the spelling, and check the diagnostic attribute listing for the correct name. Also
+    /// consider if you are using an old version of the compiler, and the attribute
+    /// is only available in a newer version.
+    pub UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES,
+    Warn,
+    "unrecognized or malformed diagnostic attribute",
+    @feature_gate = sym::diagnostic_namespace;
+}
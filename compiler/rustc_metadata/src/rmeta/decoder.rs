@@ -953,6 +953,10 @@ fn get_debugger_visualizers(self) -> Vec<rustc_span::DebuggerVisualizerFile> {
         self.root.debugger_visualizers.decode(self).collect::<Vec<_>>()
     }
 
+    fn get_stripped_cfg_impls(self, tcx: TyCtxt<'tcx>) -> &'tcx [ty::StrippedCfgImpl] {
+        tcx.arena.alloc_from_iter(self.root.stripped_cfg_impls.decode(self))
+    }
+
     /// Iterates over all the stability attributes in the given crate.
     fn get_lib_features(self, tcx: TyCtxt<'tcx>) -> &'tcx [(Symbol, Option<Symbol>)] {
         tcx.arena.alloc_from_iter(self.root.lib_features.decode(self))
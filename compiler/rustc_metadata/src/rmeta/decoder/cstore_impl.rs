@@ -318,6 +318,7 @@ fn into_args(self) -> (DefId, SimplifiedType) {
 
     used_crate_source => { Lrc::clone(&cdata.source) }
     debugger_visualizers => { cdata.get_debugger_visualizers() }
+    stripped_cfg_impls => { cdata.get_stripped_cfg_impls(tcx) }
 
     exported_symbols => {
         let syms = cdata.exported_symbols(tcx);
@@ -368,6 +369,10 @@ pub(in crate::rmeta) fn provide(providers: &mut Providers) {
             assert_eq!(cnum, LOCAL_CRATE);
             foreign_modules::collect(tcx).into_iter().map(|m| (m.def_id, m)).collect()
         },
+        stripped_cfg_impls: |tcx, cnum| {
+            assert_eq!(cnum, LOCAL_CRATE);
+            &tcx.resolutions(()).stripped_cfg_impls[..]
+        },
 
         // Returns a map from a sufficiently visible external item (i.e., an
         // external item that is visible from at least one local module) to a
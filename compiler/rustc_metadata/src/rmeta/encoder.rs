@@ -638,6 +638,8 @@ macro_rules! stat {
         let debugger_visualizers =
             stat!("debugger-visualizers", || self.encode_debugger_visualizers());
 
+        let stripped_cfg_impls = stat!("stripped-cfg-impls", || self.encode_stripped_cfg_impls());
+
         // Encode exported symbols info. This is prefetched in `encode_metadata` so we encode
         // this as late as possible to give the prefetching as much time as possible to complete.
         let exported_symbols = stat!("exported-symbols", || {
@@ -677,6 +679,7 @@ macro_rules! stat {
                     .contains_name(&attrs, sym::default_lib_allocator),
                 proc_macro_data,
                 debugger_visualizers,
+                stripped_cfg_impls,
                 compiler_builtins: tcx.sess.contains_name(&attrs, sym::compiler_builtins),
                 needs_allocator: tcx.sess.contains_name(&attrs, sym::needs_allocator),
                 needs_panic_runtime: tcx.sess.contains_name(&attrs, sym::needs_panic_runtime),
@@ -1846,6 +1849,11 @@ fn encode_debugger_visualizers(&mut self) -> LazyArray<DebuggerVisualizerFile> {
         self.lazy_array(self.tcx.debugger_visualizers(LOCAL_CRATE).iter())
     }
 
+    fn encode_stripped_cfg_impls(&mut self) -> LazyArray<ty::StrippedCfgImpl> {
+        empty_proc_macro!(self);
+        self.lazy_array(self.tcx.stripped_cfg_impls(LOCAL_CRATE).iter())
+    }
+
     fn encode_crate_deps(&mut self) -> LazyArray<CrateDep> {
         empty_proc_macro!(self);
 
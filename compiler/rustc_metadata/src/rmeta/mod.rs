@@ -244,6 +244,7 @@ pub(crate) struct CrateRoot {
 
     tables: LazyTables,
     debugger_visualizers: LazyArray<rustc_span::DebuggerVisualizerFile>,
+    stripped_cfg_impls: LazyArray<ty::StrippedCfgImpl>,
 
     exported_symbols: LazyArray<(ExportedSymbol<'static>, SymbolExportInfo)>,
 
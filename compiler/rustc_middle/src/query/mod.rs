@@ -1251,14 +1251,56 @@
         desc { |tcx| "finding trait impls of `{}`", tcx.def_path_str(trait_id) }
     }
 
+    /// Given a trait `trait_id`, return all of its blanket impls, in the same order
+    /// as `trait_impls_of(trait_id).blanket_impls()`, together with the traits that
+    /// must be implemented by the impl's self type if it is a type parameter.
+    ///
+    /// Only bounds which can be cheaply rejected during candidate assembly are
+    /// included: auto traits, trait aliases and lang items are filtered out.
+    query blanket_impl_self_bounds(trait_id: DefId) -> &'tcx [(DefId, &'tcx [DefId])] {
+        desc { |tcx| "collecting the self type bounds of blanket impls of `{}`", tcx.def_path_str(trait_id) }
+    }
+
     query specialization_graph_of(trait_id: DefId) -> specialization_graph::Graph {
         arena_cache
         desc { |tcx| "building specialization graph of trait `{}`", tcx.def_path_str(trait_id) }
         cache_on_disk_if { true }
     }
+    /// Returns the impls of a trait along with the impls they specialize.
+    ///
+    /// This is a flattened view of `specialization_graph_of` for tools which
+    /// want to inspect the specialization hierarchy.
+    query specialization_dag(trait_id: DefId)
+        -> &'tcx [specialization_graph::SpecializationDagNode] {
+        desc { |tcx| "computing the specialization DAG of trait `{}`", tcx.def_path_str(trait_id) }
+    }
+    /// Whether the impl applies to every instantiation of its trait ref, ignoring
+    /// bounds on specialization traits. This uses the same rules `min_specialization`
+    /// checks specializing impls against.
+    query impl_is_always_applicable(impl_def_id: DefId) -> bool {
+        desc { |tcx| "checking whether `{}` is always applicable", tcx.def_path_str(impl_def_id) }
+    }
+    /// Whether const contexts may project the `default` associated types of an impl
+    /// out of monomorphic trait refs. This holds for impls of `#[rustc_specialization_trait]`
+    /// traits and for always applicable impls, whose selection doesn't depend on lifetimes.
+    ///
+    /// This is a query so that type checking and const evaluation share the decision.
+    query reveals_defaults_in_const(impl_def_id: DefId) -> bool {
+        desc { |tcx| "checking whether const contexts can reveal the defaults of `{}`", tcx.def_path_str(impl_def_id) }
+    }
     query object_safety_violations(trait_id: DefId) -> &'tcx [traits::ObjectSafetyViolation] {
         desc { |tcx| "determining object safety of trait `{}`", tcx.def_path_str(trait_id) }
     }
+    /// Like `object_safety_violations`, but also records the trait and the associated item
+    /// each violation comes from.
+    query object_safety_violation_items(
+        trait_id: DefId
+    ) -> &'tcx [traits::ObjectSafetyViolationItem] {
+        desc {
+            |tcx| "collecting object safety violations of trait `{}`",
+            tcx.def_path_str(trait_id)
+        }
+    }
 
     /// Gets the ParameterEnvironment for a given item; this environment
     /// will be in "user-facing" mode, meaning that it is suitable for
@@ -1762,6 +1804,11 @@
         desc { "looking up the debugger visualizers for this crate" }
         separate_provide_extern
     }
+    /// Returns the trait impls that were removed from this crate by `#[cfg(feature = "...")]`.
+    query stripped_cfg_impls(_: CrateNum) -> &'tcx [ty::StrippedCfgImpl] {
+        desc { "looking up the trait impls removed by features in a crate" }
+        separate_provide_extern
+    }
     query postorder_cnums(_: ()) -> &'tcx [CrateNum] {
         eval_always
         desc { "generating a postorder list of CrateNums" }
@@ -1025,6 +1025,21 @@ trait objects",
     }
 }
 
+/// An [`ObjectSafetyViolation`] together with where it was found, as returned by the
+/// `object_safety_violation_items` query.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, HashStable)]
+pub struct ObjectSafetyViolationItem {
+    /// The trait that contains the violation: the queried trait or one of its supertraits.
+    pub trait_def_id: DefId,
+    /// The associated item that causes the violation, for violations caused by a method,
+    /// an associated const or a generic associated type.
+    pub item_def_id: Option<DefId>,
+    pub violation: ObjectSafetyViolation,
+    /// For a method that references `Self` in a parameter or its return type, the spans of
+    /// those uses of `Self`. Empty for other violations and for items of foreign traits.
+    pub self_spans: Vec<Span>,
+}
+
 /// Reasons a method might not be object-safe.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, HashStable, PartialOrd, Ord)]
 pub enum MethodViolationCode {
@@ -161,6 +161,16 @@ pub enum SelectionCandidate<'tcx> {
 
     /// Implementation of `const Destruct`, optionally from a custom `impl const Drop`.
     ConstDestructCandidate(Option<DefId>),
+
+    /// Proving the obligation for the hidden type inferred so far for an opaque
+    /// self type in its defining scope, with `-Zreveal-opaque-types-in-defining-scope`.
+    /// Like `AutoImplCandidate`, this is only assembled if no other candidate applies.
+    OpaqueHiddenTypeCandidate,
+
+    /// Proving an auto trait for the anonymous associated type of a return-position
+    /// `impl Trait` in a trait through the type returned by the method of the impl
+    /// providing it, the same way auto traits leak through opaque types.
+    RevealedImplTraitInTraitCandidate,
 }
 
 /// The result of trait evaluation. The order is important
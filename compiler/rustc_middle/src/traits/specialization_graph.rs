@@ -47,6 +47,19 @@ pub fn parent(&self, child: DefId) -> DefId {
     }
 }
 
+/// An impl of a trait together with its position in the specialization graph,
+/// as returned by the `specialization_dag` query.
+#[derive(Copy, Clone, Debug, HashStable)]
+pub struct SpecializationDagNode {
+    pub impl_def_id: DefId,
+    /// The impl this impl specializes, or `None` if it is a root of the graph.
+    pub parent: Option<DefId>,
+    /// Whether the impl applies to every instantiation of its trait ref,
+    /// except for bounds on specialization traits, as computed by the
+    /// `impl_is_always_applicable` query.
+    pub always_applicable: bool,
+}
+
 /// What kind of overlap check are we doing -- this exists just for testing and feature-gating
 /// purposes.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, HashStable, Debug, TyEncodable, TyDecodable)]
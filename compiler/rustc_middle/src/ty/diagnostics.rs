@@ -464,6 +464,14 @@ fn visit_ty(&mut self, t: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
                 }
             }
 
+            // The anonymous associated type of a return-position `impl Trait`
+            // in a trait can't be named.
+            Projection(data)
+                if self.tcx.def_kind(data.item_def_id) != hir::def::DefKind::AssocTy =>
+            {
+                return ControlFlow::Break(());
+            }
+
             Dynamic(dty, _, _) => {
                 for pred in *dty {
                     match pred.skip_binder() {
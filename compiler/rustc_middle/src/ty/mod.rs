@@ -179,6 +179,29 @@ pub struct ResolverGlobalCtxt {
     /// exist under `std`. For example, wrote `str::from_utf8` instead of `std::str::from_utf8`.
     pub confused_type_with_std_module: FxHashMap<Span, Span>,
     pub registered_tools: RegisteredTools,
+    pub stripped_cfg_impls: Vec<StrippedCfgImpl>,
+}
+
+/// A trait impl that was removed from a crate because the `#[cfg(feature = "...")]` attribute on
+/// it evaluated to `false`. Crates record these so that unsatisfied trait bounds in their
+/// dependents can suggest enabling the feature that provides the impl.
+#[derive(Copy, Clone, Debug, TyEncodable, TyDecodable, HashStable)]
+pub struct StrippedCfgImpl {
+    /// The implemented trait.
+    pub trait_: StrippedCfgImplTrait,
+    /// The self type of the impl.
+    pub self_ty: SimplifiedType,
+    /// The feature the impl is gated on.
+    pub feature: Symbol,
+}
+
+#[derive(Copy, Clone, Debug, TyEncodable, TyDecodable, HashStable)]
+pub enum StrippedCfgImplTrait {
+    /// The path of the trait resolved to this trait.
+    Resolved(DefId),
+    /// The path of the trait starts with a crate which is not loaded, usually an optional
+    /// dependency which is enabled by the same feature. Holds the path as written.
+    UnloadedCrate(Symbol),
 }
 
 /// Resolutions that should only be used for lowering.
@@ -2466,8 +2489,13 @@ pub fn trait_is_auto(self, trait_def_id: DefId) -> bool {
         self.trait_def(trait_def_id).has_auto_impl
     }
 
+    /// Returns `true` if cycles involving this trait are accepted during trait
+    /// selection. This is the case for auto traits and `Sized`, or for every
+    /// trait with `-Zcoinductive-traits`.
     pub fn trait_is_coinductive(self, trait_def_id: DefId) -> bool {
-        self.trait_is_auto(trait_def_id) || self.lang_items().sized_trait() == Some(trait_def_id)
+        self.sess.opts.unstable_opts.coinductive_traits
+            || self.trait_is_auto(trait_def_id)
+            || self.lang_items().sized_trait() == Some(trait_def_id)
     }
 
     /// Returns layout of a generator. Layout might be unavailable if the
@@ -2676,6 +2704,7 @@ pub fn provide(providers: &mut ty::query::Providers) {
     super::middle::provide(providers);
     *providers = ty::query::Providers {
         trait_impls_of: trait_def::trait_impls_of_provider,
+        blanket_impl_self_bounds: trait_def::blanket_impl_self_bounds_provider,
         incoherent_impls: trait_def::incoherent_impls_provider,
         const_param_default: consts::const_param_default,
         vtable_allocation: vtable::vtable_allocation_provider,
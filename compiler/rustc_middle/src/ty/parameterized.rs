@@ -65,6 +65,7 @@ impl $crate::ty::ParameterizedOverTcx for $ty {
     ty::Generics,
     ty::ImplPolarity,
     ty::ReprOptions,
+    ty::StrippedCfgImpl,
     ty::TraitDef,
     ty::Visibility<DefIndex>,
     ty::adjustment::CoerceUnsizedInfo,
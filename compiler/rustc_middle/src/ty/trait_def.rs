@@ -1,7 +1,7 @@
 use crate::traits::specialization_graph;
 use crate::ty::fast_reject::{self, SimplifiedType, TreatParams};
 use crate::ty::visit::TypeVisitable;
-use crate::ty::{Ident, Ty, TyCtxt};
+use crate::ty::{self, Ident, Ty, TyCtxt};
 use hir::def_id::LOCAL_CRATE;
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
@@ -31,6 +31,11 @@ pub struct TraitDef {
     /// and thus `impl`s of it are allowed to overlap.
     pub is_marker: bool,
 
+    /// If `true`, then this trait has the `#[closed_marker]` attribute: it is a
+    /// marker trait which may only be implemented in the crate defining it, so
+    /// coherence does not need to hedge against other crates adding impls.
+    pub is_closed_marker: bool,
+
     /// If `true`, then this trait has the `#[rustc_skip_array_during_method_dispatch]`
     /// attribute, indicating that editions before 2021 should not consider this trait
     /// during method dispatch if the receiver is an array.
@@ -87,6 +92,7 @@ pub fn new(
         paren_sugar: bool,
         has_auto_impl: bool,
         is_marker: bool,
+        is_closed_marker: bool,
         skip_array_during_method_dispatch: bool,
         specialization_kind: TraitSpecializationKind,
         must_implement_one_of: Option<Box<[Ident]>>,
@@ -97,6 +103,7 @@ pub fn new(
             paren_sugar,
             has_auto_impl,
             is_marker,
+            is_closed_marker,
             skip_array_during_method_dispatch,
             specialization_kind,
             must_implement_one_of,
@@ -177,6 +184,38 @@ pub fn find_map_relevant_impl<T, F: FnMut(DefId) -> Option<T>>(
             }
         }
 
+        self.find_map_relevant_non_blanket_impl(def_id, self_ty, f)
+    }
+
+    /// Like [`TyCtxt::for_each_relevant_impl`], but additionally passes the self type
+    /// bounds of each blanket impl, as computed by the `blanket_impl_self_bounds` query.
+    /// Non-blanket impls are passed an empty slice.
+    pub fn for_each_relevant_impl_with_self_bounds<F: FnMut(DefId, &'tcx [DefId])>(
+        self,
+        def_id: DefId,
+        self_ty: Ty<'tcx>,
+        mut f: F,
+    ) {
+        for &(impl_def_id, self_bounds) in self.blanket_impl_self_bounds(def_id) {
+            f(impl_def_id, self_bounds);
+        }
+
+        let _: Option<()> = self.find_map_relevant_non_blanket_impl(def_id, self_ty, |did| {
+            f(did, &[]);
+            None
+        });
+    }
+
+    /// Applies function to every non-blanket impl that could possibly match the self type
+    /// `self_ty` and returns the first non-none value.
+    fn find_map_relevant_non_blanket_impl<T, F: FnMut(DefId) -> Option<T>>(
+        self,
+        def_id: DefId,
+        self_ty: Ty<'tcx>,
+        mut f: F,
+    ) -> Option<T> {
+        let impls = self.trait_impls_of(def_id);
+
         // Note that we're using `TreatParams::AsPlaceholder` to query `non_blanket_impls` while using
         // `TreatParams::AsInfer` while actually adding them.
         //
@@ -255,6 +294,39 @@ pub(super) fn trait_impls_of_provider(tcx: TyCtxt<'_>, trait_id: DefId) -> Trait
     impls
 }
 
+// Query provider for `blanket_impl_self_bounds`.
+pub(super) fn blanket_impl_self_bounds_provider(
+    tcx: TyCtxt<'_>,
+    trait_id: DefId,
+) -> &[(DefId, &[DefId])] {
+    let lang_items = tcx.lang_items();
+    let blanket_impls: Vec<_> = tcx
+        .trait_impls_of(trait_id)
+        .blanket_impls
+        .iter()
+        .map(|&impl_def_id| {
+            let ty::Param(self_param) = *tcx.type_of(impl_def_id).kind() else {
+                return (impl_def_id, &[][..]);
+            };
+
+            let self_bounds =
+                tcx.predicates_of(impl_def_id).predicates.iter().filter_map(|&(predicate, _)| {
+                    let trait_pred = predicate.to_opt_poly_trait_pred()?.no_bound_vars()?;
+                    let bound_def_id = trait_pred.def_id();
+                    let is_plain_bound = trait_pred.polarity == ty::ImplPolarity::Positive
+                        && trait_pred.self_ty().is_param(self_param.index)
+                        && !tcx.trait_is_auto(bound_def_id)
+                        && !tcx.is_trait_alias(bound_def_id)
+                        && !lang_items.iter().any(|(_, lang_item)| lang_item == bound_def_id);
+                    is_plain_bound.then_some(bound_def_id)
+                });
+            (impl_def_id, &*tcx.arena.alloc_from_iter(self_bounds))
+        })
+        .collect();
+
+    tcx.arena.alloc_from_iter(blanket_impls)
+}
+
 // Query provider for `incoherent_impls`.
 pub(super) fn incoherent_impls_provider(tcx: TyCtxt<'_>, simp: SimplifiedType) -> &[DefId] {
     let mut impls = Vec::new();
@@ -25,7 +25,8 @@
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::lint::builtin::{
-    CONFLICTING_REPR_HINTS, INVALID_DOC_ATTRIBUTES, UNUSED_ATTRIBUTES,
+    CONFLICTING_REPR_HINTS, INVALID_DOC_ATTRIBUTES, UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES,
+    UNUSED_ATTRIBUTES,
 };
 use rustc_session::parse::feature_err;
 use rustc_span::symbol::{kw, sym, Symbol};
@@ -86,6 +87,7 @@ fn check_attributes(
                 sym::no_coverage => self.check_no_coverage(hir_id, attr, span, target),
                 sym::non_exhaustive => self.check_non_exhaustive(hir_id, attr, span, target),
                 sym::marker => self.check_marker(hir_id, attr, span, target),
+                sym::closed_marker => self.check_closed_marker(attr, span, target),
                 sym::rustc_must_implement_one_of => {
                     self.check_rustc_must_implement_one_of(attr, span, target)
                 }
@@ -141,6 +143,7 @@ fn check_attributes(
                 sym::collapse_debuginfo => self.check_collapse_debuginfo(attr, span, target),
                 sym::const_trait => self.check_const_trait(attr, span, target),
                 sym::must_not_suspend => self.check_must_not_suspend(&attr, span, target),
+                sym::fundamental => self.check_fundamental(&attr, span, target),
                 sym::must_use => self.check_must_use(hir_id, &attr, target),
                 sym::rustc_pass_by_value => self.check_pass_by_value(&attr, span, target),
                 sym::rustc_allow_incoherent_impl => {
@@ -185,6 +188,14 @@ fn check_attributes(
                 _ => {}
             }
 
+            if let ast::AttrKind::Normal(normal) = &attr.kind
+                && let [namespace, name] = &*normal.item.path.segments
+                && namespace.ident.name == sym::diagnostic
+                && name.ident.name == sym::on_unimplemented
+            {
+                self.check_diagnostic_on_unimplemented(hir_id, attr, target);
+            }
+
             let builtin = attr.ident().and_then(|ident| BUILTIN_ATTRIBUTE_MAP.get(&ident.name));
 
             if hir_id != CRATE_HIR_ID {
@@ -223,6 +234,19 @@ fn check_attributes(
         self.check_used(attrs, target);
     }
 
+    /// Checks that `#[diagnostic::on_unimplemented]` is only applied to trait definitions. The
+    /// options of the attribute are checked along with the trait.
+    fn check_diagnostic_on_unimplemented(&self, hir_id: HirId, attr: &Attribute, target: Target) {
+        if target != Target::Trait {
+            self.tcx.emit_spanned_lint(
+                UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES,
+                hir_id,
+                attr.span,
+                errors::DiagnosticOnUnimplementedOnlyForTraits,
+            );
+        }
+    }
+
     fn inline_attr_str_error_with_macro_def(&self, hir_id: HirId, attr: &Attribute, sym: &str) {
         self.tcx.emit_spanned_lint(
             UNUSED_ATTRIBUTES,
@@ -522,6 +546,20 @@ fn check_marker(&self, hir_id: HirId, attr: &Attribute, span: Span, target: Targ
         }
     }
 
+    /// Checks if the `#[closed_marker]` attribute on an `item` is valid. Returns `true` if valid.
+    fn check_closed_marker(&self, attr: &Attribute, span: Span, target: Target) -> bool {
+        match target {
+            Target::Trait => true,
+            _ => {
+                self.tcx.sess.emit_err(errors::AttrShouldBeAppliedToTrait {
+                    attr_span: attr.span,
+                    defn_span: span,
+                });
+                false
+            }
+        }
+    }
+
     /// Checks if the `#[rustc_must_implement_one_of]` attribute on a `target` is valid. Returns `true` if valid.
     fn check_rustc_must_implement_one_of(
         &self,
@@ -1212,6 +1250,17 @@ fn check_must_not_suspend(&self, attr: &Attribute, span: Span, target: Target) -
         }
     }
 
+    /// Checks if `#[fundamental]` is applied to a type or trait. Returns `true` if valid.
+    fn check_fundamental(&self, attr: &Attribute, span: Span, target: Target) -> bool {
+        match target {
+            Target::Struct | Target::Enum | Target::Union | Target::Trait => true,
+            _ => {
+                self.tcx.sess.emit_err(errors::Fundamental { attr_span: attr.span, span });
+                false
+            }
+        }
+    }
+
     /// Checks if `#[cold]` is applied to a non-function. Returns `true` if valid.
     fn check_cold(&self, hir_id: HirId, attr: &Attribute, span: Span, target: Target) {
         match target {
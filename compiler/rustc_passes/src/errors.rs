@@ -363,6 +363,15 @@ pub struct MustNotSuspend {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_fundamental)]
+pub struct Fundamental {
+    #[primary_span]
+    pub attr_span: Span,
+    #[label]
+    pub span: Span,
+}
+
 #[derive(LintDiagnostic)]
 #[diag(passes_cold)]
 #[warning]
@@ -1508,3 +1517,7 @@ pub struct ChangeFieldsToBeOfUnitType {
     #[suggestion_part(code = "()")]
     pub spans: Vec<Span>,
 }
+
+#[derive(LintDiagnostic)]
+#[diag(passes_diagnostic_on_unimplemented_only_for_traits)]
+pub struct DiagnosticOnUnimplementedOnlyForTraits;
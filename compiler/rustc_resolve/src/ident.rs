@@ -7,7 +7,7 @@
 use rustc_span::def_id::LocalDefId;
 use rustc_span::edition::Edition;
 use rustc_span::hygiene::{ExpnId, ExpnKind, LocalExpnId, MacroKind, SyntaxContext};
-use rustc_span::symbol::{kw, Ident};
+use rustc_span::symbol::{kw, sym, Ident};
 use rustc_span::{Span, DUMMY_SP};
 
 use std::ptr;
@@ -529,6 +529,11 @@ struct Flags: u8 {
                         }
                     }
                     Scope::ToolPrelude => match this.registered_tools.get(&ident).cloned() {
+                        // The implicit `diagnostic` tool only exists for attributes, so it
+                        // doesn't make imports of a module with the same name ambiguous.
+                        Some(ident) if is_import && ident.name == sym::diagnostic => {
+                            Err(Determinacy::Determined)
+                        }
                         Some(ident) => ok(Res::ToolMod, ident.span, this.arenas),
                         None => Err(Determinacy::Determined),
                     },
@@ -37,12 +37,13 @@
 use rustc_hir::def_id::{CrateNum, DefId, DefIdMap, LocalDefId};
 use rustc_hir::def_id::{CRATE_DEF_ID, LOCAL_CRATE};
 use rustc_hir::definitions::{DefPathData, Definitions};
-use rustc_hir::TraitCandidate;
+use rustc_hir::{PrimTy, TraitCandidate};
 use rustc_index::vec::IndexVec;
 use rustc_metadata::creader::{CStore, CrateLoader};
 use rustc_middle::metadata::ModChild;
 use rustc_middle::middle::privacy::EffectiveVisibilities;
 use rustc_middle::span_bug;
+use rustc_middle::ty::fast_reject::SimplifiedType;
 use rustc_middle::ty::{self, DefIdTree, MainDefinition, RegisteredTools};
 use rustc_middle::ty::{ResolverGlobalCtxt, ResolverOutputs};
 use rustc_query_system::ich::StableHashingContext;
@@ -57,7 +58,7 @@
 use smallvec::{smallvec, SmallVec};
 use std::cell::{Cell, RefCell};
 use std::collections::BTreeSet;
-use std::{fmt, ptr};
+use std::{fmt, mem, ptr};
 
 use diagnostics::{ImportSuggestion, LabelSuggestion, Suggestion};
 use imports::{Import, ImportKind, ImportResolver, NameResolution};
@@ -1036,6 +1037,10 @@ pub struct Resolver<'a> {
     /// they are declared in the static array generated by proc_macro_harness.
     proc_macros: Vec<NodeId>,
     confused_type_with_std_module: FxHashMap<Span, Span>,
+    /// Trait impls removed by `#[cfg(feature = "...")]`, along with the item they were removed
+    /// from, whose paths are resolved once the crate is expanded.
+    stripped_cfg_impl_paths: Vec<(NodeId, Path, Path, Symbol)>,
+    stripped_cfg_impls: Vec<ty::StrippedCfgImpl>,
 
     effective_visibilities: EffectiveVisibilities,
 }
@@ -1353,6 +1358,8 @@ pub fn new(
             main_def: Default::default(),
             trait_impls: Default::default(),
             proc_macros: Default::default(),
+            stripped_cfg_impl_paths: Default::default(),
+            stripped_cfg_impls: Default::default(),
             confused_type_with_std_module: Default::default(),
             effective_visibilities: Default::default(),
         };
@@ -1435,6 +1442,7 @@ pub fn into_outputs(self) -> ResolverOutputs {
             proc_macros,
             confused_type_with_std_module,
             registered_tools: self.registered_tools,
+            stripped_cfg_impls: self.stripped_cfg_impls,
         };
         let ast_lowering = ty::ResolverAstLowering {
             legacy_const_generic_args: self.legacy_const_generic_args,
@@ -1477,6 +1485,7 @@ pub fn clone_outputs(&self) -> ResolverOutputs {
             proc_macros,
             confused_type_with_std_module: self.confused_type_with_std_module.clone(),
             registered_tools: self.registered_tools.clone(),
+            stripped_cfg_impls: self.stripped_cfg_impls.clone(),
             effective_visibilities: self.effective_visibilities.clone(),
         };
         let ast_lowering = ty::ResolverAstLowering {
@@ -1546,6 +1555,7 @@ pub fn resolve_crate(&mut self, krate: &Crate) {
             self.session.time("finalize_macro_resolutions", || self.finalize_macro_resolutions());
             self.session.time("late_resolve_crate", || self.late_resolve_crate(krate));
             self.session.time("resolve_main", || self.resolve_main());
+            self.session.time("resolve_stripped_cfg_impls", || self.resolve_stripped_cfg_impls());
             self.session.time("resolve_check_unused", || self.check_unused(krate));
             self.session.time("resolve_report_errors", || self.report_errors(krate));
             self.session.time("resolve_postprocess", || self.crate_loader.postprocess(krate));
@@ -2020,6 +2030,69 @@ fn resolve_main(&mut self) {
         self.main_def = Some(MainDefinition { res, is_import, span });
     }
 
+    /// Resolves the paths of the trait impls removed by `#[cfg(feature = "...")]` in the module
+    /// they were removed from, so that dependents can compare them with the unsatisfied bounds.
+    fn resolve_stripped_cfg_impls(&mut self) {
+        let stripped_cfg_impl_paths = mem::take(&mut self.stripped_cfg_impl_paths);
+        for (parent_node, trait_path, self_ty_path, feature) in stripped_cfg_impl_paths {
+            let Some(parent_def_id) = self.opt_local_def_id(parent_node) else { continue };
+            let module = self.get_nearest_non_block_module(parent_def_id.to_def_id());
+            let parent_scope = &ParentScope::module(module, self);
+            let path_res = |path_result: PathResult<'a>| match path_result {
+                PathResult::Module(ModuleOrUniformRoot::Module(module)) => module.res(),
+                PathResult::NonModule(partial_res) => partial_res.full_res(),
+                _ => None,
+            };
+
+            let trait_segments = Segment::from_path(&trait_path);
+            let trait_path_result =
+                self.maybe_resolve_path(&trait_segments, Some(TypeNS), parent_scope);
+            let trait_ = match trait_path_result {
+                // The traits of optional dependencies which are enabled by the same feature
+                // can't be resolved, as the dependency is not loaded.
+                PathResult::Failed { is_error_from_last_segment: false, .. }
+                    if trait_path.segments.len() > 1 =>
+                {
+                    let path = Symbol::intern(&path_names_to_string(&trait_path));
+                    ty::StrippedCfgImplTrait::UnloadedCrate(path)
+                }
+                trait_path_result => match path_res(trait_path_result) {
+                    Some(Res::Def(DefKind::Trait, def_id)) => {
+                        ty::StrippedCfgImplTrait::Resolved(def_id)
+                    }
+                    _ => continue,
+                },
+            };
+
+            let self_ty_segments = Segment::from_path(&self_ty_path);
+            let self_ty_path_result =
+                self.maybe_resolve_path(&self_ty_segments, Some(TypeNS), parent_scope);
+            let self_ty = match path_res(self_ty_path_result) {
+                Some(Res::Def(DefKind::Struct | DefKind::Enum | DefKind::Union, def_id)) => {
+                    SimplifiedType::AdtSimplifiedType(def_id)
+                }
+                Some(Res::Def(DefKind::ForeignTy, def_id)) => {
+                    SimplifiedType::ForeignSimplifiedType(def_id)
+                }
+                Some(Res::PrimTy(prim_ty)) => match prim_ty {
+                    PrimTy::Int(int_ty) => SimplifiedType::IntSimplifiedType(ty::int_ty(int_ty)),
+                    PrimTy::Uint(uint_ty) => {
+                        SimplifiedType::UintSimplifiedType(ty::uint_ty(uint_ty))
+                    }
+                    PrimTy::Float(float_ty) => {
+                        SimplifiedType::FloatSimplifiedType(ty::float_ty(float_ty))
+                    }
+                    PrimTy::Str => SimplifiedType::StrSimplifiedType,
+                    PrimTy::Bool => SimplifiedType::BoolSimplifiedType,
+                    PrimTy::Char => SimplifiedType::CharSimplifiedType,
+                },
+                _ => continue,
+            };
+
+            self.stripped_cfg_impls.push(ty::StrippedCfgImpl { trait_, self_ty, feature });
+        }
+    }
+
     // Items that go to reexport table encoded to metadata and visible through it to other crates.
     fn is_reexport(&self, binding: &NameBinding<'a>) -> Option<def::Res<!>> {
         if binding.is_import() {
@@ -21,6 +21,7 @@
 use rustc_hir::def_id::{CrateNum, LocalDefId};
 use rustc_middle::middle::stability;
 use rustc_middle::ty::RegisteredTools;
+use rustc_session::lint::builtin::UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES;
 use rustc_session::lint::builtin::{LEGACY_DERIVE_HELPERS, SOFT_UNSTABLE};
 use rustc_session::lint::builtin::{UNUSED_MACROS, UNUSED_MACRO_RULES};
 use rustc_session::lint::BuiltinLintDiagnostics;
@@ -133,9 +134,9 @@ pub(crate) fn registered_tools(sess: &Session, attrs: &[ast::Attribute]) -> FxHa
             }
         }
     }
-    // We implicitly add `rustfmt` and `clippy` to known tools,
+    // We implicitly add `rustfmt`, `clippy` and `diagnostic` to known tools,
     // but it's not an error to register them explicitly.
-    let predefined_tools = [sym::clippy, sym::rustfmt];
+    let predefined_tools = [sym::clippy, sym::rustfmt, sym::diagnostic];
     registered_tools.extend(predefined_tools.iter().cloned().map(Ident::with_dummy_span));
     registered_tools
 }
@@ -465,6 +466,16 @@ fn declare_proc_macro(&mut self, id: NodeId) {
     fn registered_tools(&self) -> &RegisteredTools {
         &self.registered_tools
     }
+
+    fn append_stripped_cfg_impl(
+        &mut self,
+        parent_node: NodeId,
+        trait_path: ast::Path,
+        self_ty_path: ast::Path,
+        feature: Symbol,
+    ) {
+        self.stripped_cfg_impl_paths.push((parent_node, trait_path, self_ty_path, feature));
+    }
 }
 
 impl<'a> Resolver<'a> {
@@ -519,6 +530,28 @@ fn smart_resolve_macro_path(
             _ => panic!("expected `DefKind::Macro` or `Res::NonMacroAttr`"),
         };
 
+        if res == Res::NonMacroAttr(NonMacroAttrKind::Tool)
+            && let [namespace, attribute, ..] = &*path.segments
+            && namespace.ident.name == sym::diagnostic
+        {
+            if !self.session.features_untracked().diagnostic_namespace {
+                feature_err(
+                    &self.session.parse_sess,
+                    sym::diagnostic_namespace,
+                    namespace.ident.span,
+                    "`#[diagnostic]` attribute name space is experimental",
+                )
+                .emit();
+            } else if path.segments.len() > 2 || attribute.ident.name != sym::on_unimplemented {
+                self.session.parse_sess.buffer_lint(
+                    UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES,
+                    attribute.ident.span,
+                    node_id,
+                    "unknown diagnostic attribute",
+                );
+            }
+        }
+
         self.check_stability_and_deprecation(&ext, path, node_id);
 
         let unexpected_res = if ext.macro_kind() != kind {
@@ -25,6 +25,7 @@
 pub mod parse;
 
 mod code_stats;
+pub mod trait_dumps;
 #[macro_use]
 pub mod config;
 pub mod cstore;
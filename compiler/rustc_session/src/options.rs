@@ -1213,6 +1213,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "only allow the listed language features to be enabled in code (space separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    always_explain_intercrate_ambiguity: bool = (false, parse_bool, [TRACKED],
+        "explain which impls other crates could add to make two impls overlap, even when \
+        a local impl applies to the ambiguous predicate (default: no)"),
     #[rustc_lint_opt_deny_field_access("use `Session::asm_comments` instead of this field")]
     asm_comments: bool = (false, parse_bool, [TRACKED],
         "generate comments into the assembly (may change behavior) (default: no)"),
@@ -1235,8 +1238,14 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "the codegen unit partitioning strategy to use"),
     chalk: bool = (false, parse_bool, [TRACKED],
         "enable the experimental Chalk-based trait solving engine"),
+    classic_coherence_solver: bool = (false, parse_bool, [TRACKED],
+        "with `-Zchalk`, use the classic trait solver for the negative reasoning of coherence \
+        (default: no)"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
         "the backend to use"),
+    coinductive_traits: bool = (false, parse_bool, [TRACKED],
+        "treat all trait goals as coinductive, so that cycles between them hold; this is \
+        unsound and only meant for testing the trait solver (default: no)"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
@@ -1293,6 +1302,15 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         computed `block` spans (one span encompassing a block's terminator and \
         all statements). If `-Z instrument-coverage` is also enabled, create \
         an additional `.html` file showing the computed coverage spans."),
+    dump_obligation_forest: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "dump a graphviz snapshot of the trait obligation forest into the given directory \
+        after every round of fulfillment (default: no)"),
+    dump_proof_tree: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "print the proof tree of every fully inferred trait goal whose printed form \
+        contains the given string (default: no)"),
+    dump_selection_tree: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "dump the trait selection tree of every obligation selected during analysis \
+        as a `.dot` file (default: no)"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
@@ -1329,6 +1347,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "generate human-readable, predictable names for codegen units (default: no)"),
     identify_regions: bool = (false, parse_bool, [UNTRACKED],
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
+    implied_bounds_from_impl_headers: bool = (false, parse_bool, [TRACKED],
+        "assume the where clauses of types named in an impl header within the items of that \
+        impl (default: no)"),
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
@@ -1366,6 +1387,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "keep hygiene data after analysis (default: no)"),
     layout_seed: Option<u64> = (None, parse_opt_number, [TRACKED],
         "seed layout randomization"),
+    lazy_normalization_in_selection: bool = (false, parse_bool, [TRACKED],
+        "relate unnormalized projections in impl headers when matching impls during \
+        selection, and only normalize them in the resulting nested obligations (default: no)"),
     link_native_libraries: bool = (true, parse_bool, [UNTRACKED],
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
@@ -1469,6 +1493,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "print the result of the monomorphization collection pass"),
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
         "print layout information for each type encountered (default: no)"),
+    prioritize_cheap_obligations: bool = (false, parse_bool, [TRACKED],
+        "in each round of fulfillment, process obligations that are likely to be cheap \
+        and to constrain inference variables before the others (default: no)"),
     proc_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
          "show backtraces for panics during proc-macro execution (default: no)"),
     proc_macro_execution_strategy: ProcMacroExecutionStrategy = (ProcMacroExecutionStrategy::SameThread,
@@ -1495,8 +1522,14 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "choose which RELRO level to use"),
     remap_cwd_prefix: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
         "remap paths under the current working directory to this path prefix"),
+    report_all_impl_overlaps: bool = (false, parse_bool, [TRACKED],
+        "report every impl a conflicting trait impl overlaps with, instead of only the first \
+        (default: no)"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
         "immediately print bugs registered with `delay_span_bug` (default: no)"),
+    reveal_opaque_types_in_defining_scope: bool = (false, parse_bool, [TRACKED],
+        "prove obligations on an opaque type in its defining scope for the hidden type \
+        inferred for it so far, if nothing else can prove them (default: no)"),
     sanitizer: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED],
         "use a sanitizer"),
     sanitizer_memory_track_origins: usize = (0, parse_sanitizer_memory_track_origins, [TRACKED],
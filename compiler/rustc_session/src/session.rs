@@ -12,6 +12,7 @@
 };
 use crate::parse::{add_feature_diagnostics, ParseSess};
 use crate::search_paths::{PathKind, SearchPath};
+use crate::trait_dumps::TraitDumps;
 use crate::{filesearch, lint};
 
 pub use rustc_ast::attr::MarkedAttrs;
@@ -175,6 +176,9 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: CodeStats,
 
+    /// Trait selection results recorded for the `-Zdump-*` options of the trait solver.
+    pub trait_dumps: TraitDumps,
+
     /// Tracks fuel info if `-zfuel=crate=n` is specified.
     optimization_fuel: Lock<OptimizationFuel>,
 
@@ -1435,6 +1439,7 @@ pub fn build_session(
             normalize_projection_ty: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
+        trait_dumps: Default::default(),
         optimization_fuel,
         print_fuel,
         jobserver: jobserver::client(),
@@ -0,0 +1,59 @@
+//! Trait selection results recorded for `-Zdump-selection-tree` and
+//! `-Zdump-proof-tree`, which are written out once analysis is done.
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lock;
+
+/// A single selection recorded for `-Zdump-selection-tree`. Everything is
+/// stored pre-rendered, so that it can outlive the inference context the
+/// selection happened in.
+#[derive(Clone, Debug)]
+pub struct SelectionTreeNode {
+    /// The obligation that was selected.
+    pub obligation: String,
+    /// The candidates that were assembled for the obligation, or `None` if the
+    /// result was taken from the selection cache.
+    pub candidates: Option<Vec<String>>,
+    /// The outcome of selection: the chosen candidate, an ambiguity or an error.
+    pub result: String,
+    /// The nested obligations of the chosen candidate.
+    pub nested: Vec<String>,
+}
+
+/// The proof tree of a goal, as built by `SelectionContext::proof_tree`. Like
+/// [`SelectionTreeNode`], everything is stored pre-rendered with inference
+/// variables resolved, so the tree doesn't depend on the inference context it
+/// was built in.
+#[derive(Clone, Debug)]
+pub struct ProofTree {
+    /// The goal that was proven.
+    pub goal: String,
+    /// The candidate used to prove the goal. This is `None` for goals that are
+    /// not trait goals, goals that are part of a cycle, and goals for which no
+    /// candidate could be selected.
+    pub candidate: Option<String>,
+    /// The proof trees of the nested goals of `candidate`.
+    pub nested: Vec<ProofTree>,
+    /// Whether the goal holds.
+    pub certainty: ProofTreeCertainty,
+}
+
+/// Whether the goal of a [`ProofTree`] holds, ordered from most to least certain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProofTreeCertainty {
+    /// The goal holds.
+    Yes,
+    /// The goal is ambiguous, or proving it overflowed.
+    Maybe,
+    /// The goal does not hold.
+    No,
+}
+
+#[derive(Default)]
+pub struct TraitDumps {
+    /// Selections recorded for `-Zdump-selection-tree`.
+    pub selection_tree: Lock<Vec<SelectionTreeNode>>,
+
+    /// Proof trees built for `-Zdump-proof-tree`, keyed by their printed goal.
+    pub proof_trees: Lock<FxIndexMap<String, ProofTree>>,
+}
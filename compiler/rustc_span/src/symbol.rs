@@ -470,6 +470,8 @@
         clone,
         clone_closures,
         clone_from,
+        closed_marker,
+        closed_marker_traits,
         closure,
         closure_lifetime_binder,
         closure_to_fn_coercion,
@@ -604,6 +606,7 @@
         destruct,
         destructuring_assignment,
         diagnostic,
+        diagnostic_namespace,
         direct,
         discriminant_kind,
         discriminant_type,
@@ -788,6 +791,7 @@
         impl_trait_in_bindings,
         impl_trait_in_fn_trait_return,
         impl_trait_projections,
+        implements,
         implied_by,
         import,
         import_name_type,
@@ -1011,7 +1015,9 @@
         not,
         notable_trait,
         note,
+        object_safe_assoc_consts,
         object_safe_for_dispatch,
+        object_safe_sized_gats,
         of,
         offset,
         omit_gdb_pretty_printer_section,
@@ -1219,8 +1225,10 @@
         rustc_dirty,
         rustc_do_not_const_check,
         rustc_dummy,
+        rustc_dump_auto_trait_bounds,
         rustc_dump_env_program_clauses,
         rustc_dump_program_clauses,
+        rustc_dump_specialization_dag,
         rustc_dump_user_substs,
         rustc_dump_vtable,
         rustc_effective_visibility,
@@ -1378,6 +1386,8 @@
         slicing_syntax,
         soft,
         specialization,
+        specialization_marker,
+        specialization_marker_traits,
         speed,
         spotlight,
         sqrtf32,
@@ -49,6 +49,14 @@ pub struct InvalidOnClauseInOnUnimplemented {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(trait_selection_invalid_implements_condition_in_rustc_on_unimplemented, code = "E0232")]
+pub struct InvalidImplementsConditionInOnUnimplemented {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(trait_selection_no_value_in_rustc_on_unimplemented, code = "E0232")]
 #[note]
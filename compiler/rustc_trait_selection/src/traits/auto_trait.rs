@@ -194,6 +194,65 @@ pub fn find_auto_trait_generics<A>(
 
         AutoTraitResult::PositiveImpl(auto_trait_callback(info))
     }
+
+    /// Like `find_auto_trait_generics`, but returns the bounds of a synthesized positive impl
+    /// as plain predicates instead of handing the raw region data to a callback. This is meant
+    /// for tools that compare auto trait impls across versions of a crate, e.g. to catch a
+    /// type that accidentally stopped being `Send`.
+    ///
+    /// Only predicates that aren't already in `orig_env` are returned. Region variables are
+    /// replaced by the named regions they were inferred to be equal to, and region constraints
+    /// that still mention a region variable afterwards are dropped.
+    pub fn find_auto_trait_bounds(
+        &self,
+        ty: Ty<'tcx>,
+        orig_env: ty::ParamEnv<'tcx>,
+        trait_did: DefId,
+    ) -> AutoTraitResult<Vec<ty::Predicate<'tcx>>> {
+        let tcx = self.tcx;
+        self.find_auto_trait_generics(ty, orig_env, trait_did, |info| {
+            let mut replacer = RegionReplacer { vid_to_region: &info.vid_to_region, tcx };
+            let region_of = |target| match target {
+                RegionTarget::Region(r) => Some(r),
+                RegionTarget::RegionVid(vid) => info.vid_to_region.get(&vid).copied(),
+            };
+
+            let mut bounds: FxIndexSet<ty::Predicate<'tcx>> = info
+                .full_user_env
+                .caller_bounds()
+                .iter()
+                .map(|pred| pred.fold_with(&mut replacer))
+                .collect();
+            for constraint in info.region_data.constraints.keys() {
+                let (sub, sup) = match *constraint {
+                    Constraint::VarSubVar(a, b) => {
+                        (RegionTarget::RegionVid(a), RegionTarget::RegionVid(b))
+                    }
+                    Constraint::RegSubVar(a, b) => {
+                        (RegionTarget::Region(a), RegionTarget::RegionVid(b))
+                    }
+                    Constraint::VarSubReg(a, b) => {
+                        (RegionTarget::RegionVid(a), RegionTarget::Region(b))
+                    }
+                    Constraint::RegSubReg(a, b) => {
+                        (RegionTarget::Region(a), RegionTarget::Region(b))
+                    }
+                };
+                let (Some(sub), Some(sup)) = (region_of(sub), region_of(sup)) else { continue };
+                if sub == sup || sup.is_static() || sub.is_var() || sup.is_var() {
+                    continue;
+                }
+                bounds.insert(
+                    ty::Binder::dummy(ty::PredicateKind::RegionOutlives(ty::OutlivesPredicate(
+                        sup, sub,
+                    )))
+                    .to_predicate(tcx),
+                );
+            }
+
+            bounds.into_iter().filter(|pred| !orig_env.caller_bounds().contains(pred)).collect()
+        })
+    }
 }
 
 impl<'tcx> AutoTraitFinder<'tcx> {
@@ -273,9 +332,18 @@ fn evaluate_predicates(
         let mut new_env = param_env;
         let dummy_cause = ObligationCause::dummy();
 
-        while let Some(pred) = predicates.pop_front() {
-            infcx.clear_caches();
+        // The caches of the inference context are only valid as long as the `ParamEnv`
+        // stays the same, which it does unless a selection changes `user_computed_preds`.
+        // Keep them and the elaborated `new_env` until that happens, instead of
+        // recomputing both for every predicate.
+        let mut env_user_preds: Option<FxIndexSet<_>> = None;
+        infcx.clear_caches();
 
+        while let Some(pred) = predicates.pop_front() {
+            // Predicates which only differ in inference variables that have since been
+            // unified are the same goal, so only select them once. Regions are left
+            // alone, as different ones can still lead to different outlives bounds.
+            let pred = infcx.resolve_vars_if_possible(pred);
             if !already_visited.insert(pred) {
                 continue;
             }
@@ -344,6 +412,12 @@ fn evaluate_predicates(
                 _ => panic!("Unexpected error for '{:?}': {:?}", ty, result),
             };
 
+            if env_user_preds.as_ref().map_or(false, |preds| preds.iter().eq(&user_computed_preds))
+            {
+                continue;
+            }
+            env_user_preds = Some(user_computed_preds.clone());
+
             let normalized_preds = elaborate_predicates(
                 tcx,
                 computed_preds.clone().chain(user_computed_preds.iter().cloned()),
@@ -354,6 +428,7 @@ fn evaluate_predicates(
                 param_env.reveal(),
                 param_env.constness(),
             );
+            infcx.clear_caches();
         }
 
         let final_user_env = ty::ParamEnv::new(
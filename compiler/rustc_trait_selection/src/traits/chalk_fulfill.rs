@@ -16,6 +16,10 @@ pub struct FulfillmentContext<'tcx> {
     relationships: FxHashMap<ty::TyVid, ty::FoundRelationships>,
 
     usable_in_snapshot: bool,
+
+    /// Whether `select_all_or_error` also reports the remaining ambiguities
+    /// when selection already produced errors.
+    error_tolerant: bool,
 }
 
 impl FulfillmentContext<'_> {
@@ -24,12 +28,17 @@ pub(super) fn new() -> Self {
             obligations: FxIndexSet::default(),
             relationships: FxHashMap::default(),
             usable_in_snapshot: false,
+            error_tolerant: false,
         }
     }
 
     pub(crate) fn new_in_snapshot() -> Self {
         FulfillmentContext { usable_in_snapshot: true, ..Self::new() }
     }
+
+    pub(super) fn new_error_tolerant() -> Self {
+        FulfillmentContext { error_tolerant: true, ..Self::new() }
+    }
 }
 
 impl<'tcx> TraitEngine<'tcx> for FulfillmentContext<'tcx> {
@@ -59,25 +68,20 @@ fn register_predicate_obligation(
     }
 
     fn select_all_or_error(&mut self, infcx: &InferCtxt<'tcx>) -> Vec<FulfillmentError<'tcx>> {
-        {
-            let errors = self.select_where_possible(infcx);
-
-            if !errors.is_empty() {
-                return errors;
-            }
+        let mut errors = self.select_where_possible(infcx);
+        if !errors.is_empty() && !self.error_tolerant {
+            return errors;
         }
 
         // any remaining obligations are errors
-        self.obligations
-            .iter()
-            .map(|obligation| FulfillmentError {
-                obligation: obligation.clone(),
-                code: FulfillmentErrorCode::CodeAmbiguity,
-                // FIXME - does Chalk have a notation of 'root obligation'?
-                // This is just for diagnostics, so it's okay if this is wrong
-                root_obligation: obligation.clone(),
-            })
-            .collect()
+        errors.extend(self.obligations.iter().map(|obligation| FulfillmentError {
+            obligation: obligation.clone(),
+            code: FulfillmentErrorCode::CodeAmbiguity,
+            // FIXME - does Chalk have a notation of 'root obligation'?
+            // This is just for diagnostics, so it's okay if this is wrong
+            root_obligation: obligation.clone(),
+        }));
+        errors
     }
 
     fn select_where_possible(&mut self, infcx: &InferCtxt<'tcx>) -> Vec<FulfillmentError<'tcx>> {
@@ -7,15 +7,15 @@
 use crate::infer::outlives::env::OutlivesEnvironment;
 use crate::infer::{CombinedSnapshot, InferOk};
 use crate::traits::outlives_bounds::InferCtxtExt as _;
-use crate::traits::select::IntercrateAmbiguityCause;
+use crate::traits::select::{CoherenceMode, IntercrateAmbiguityCause};
 use crate::traits::util::impl_subject_and_oblig;
 use crate::traits::SkipLeakCheck;
 use crate::traits::{
     self, Normalized, Obligation, ObligationCause, ObligationCtxt, PredicateObligation,
-    PredicateObligations, SelectionContext,
+    PredicateObligations, SelectionContext, TraitSolver,
 };
 use rustc_data_structures::fx::FxIndexSet;
-use rustc_errors::Diagnostic;
+use rustc_errors::{DelayDm, Diagnostic};
 use rustc_hir::def_id::{DefId, CRATE_DEF_ID, LOCAL_CRATE};
 use rustc_hir::CRATE_HIR_ID;
 use rustc_infer::infer::{InferCtxt, TyCtxtInferExt};
@@ -23,7 +23,9 @@
 use rustc_middle::traits::specialization_graph::OverlapMode;
 use rustc_middle::ty::fast_reject::{DeepRejectCtxt, TreatParams};
 use rustc_middle::ty::visit::TypeVisitable;
+use rustc_middle::ty::subst::GenericArgKind;
 use rustc_middle::ty::{self, ImplSubject, Ty, TyCtxt, TypeVisitor};
+use rustc_session::lint::builtin::COHERENCE_RELIES_ON_FUNDAMENTAL_TRAIT;
 use rustc_span::symbol::sym;
 use rustc_span::DUMMY_SP;
 use std::fmt::Debug;
@@ -46,13 +48,22 @@ pub enum Conflict {
 
 pub struct OverlapResult<'tcx> {
     pub impl_header: ty::ImplHeader<'tcx>,
-    pub intercrate_ambiguity_causes: FxIndexSet<IntercrateAmbiguityCause>,
+    pub intercrate_ambiguity_causes: FxIndexSet<IntercrateAmbiguityCause<'tcx>>,
 
     /// `true` if the overlap might've been permitted before the shift
     /// to universes.
     pub involves_placeholder: bool,
 }
 
+/// Returned by [`overlapping_impls`] when the two impls don't overlap.
+#[derive(Debug, Default)]
+pub struct Disjoint<'tcx> {
+    /// The trait ref whose failing to hold makes the impls disjoint, if that is only
+    /// known because its trait is a `#[fundamental]` trait of an upstream crate. See
+    /// [`lint_fundamental_negative_reasoning`].
+    pub fundamental_trait_ref: Option<ty::TraitRef<'tcx>>,
+}
+
 pub fn add_placeholder_note(err: &mut Diagnostic) {
     err.note(
         "this behavior recently changed as a result of a bug fix; \
@@ -60,9 +71,9 @@ pub fn add_placeholder_note(err: &mut Diagnostic) {
     );
 }
 
-/// If there are types that satisfy both impls, returns `Some`
+/// If there are types that satisfy both impls, returns `Ok`
 /// with a suitably-freshened `ImplHeader` with those types
-/// substituted. Otherwise, returns `None`.
+/// substituted. Otherwise, returns `Err`.
 #[instrument(skip(tcx, skip_leak_check), level = "debug")]
 pub fn overlapping_impls<'tcx>(
     tcx: TyCtxt<'tcx>,
@@ -70,7 +81,7 @@ pub fn overlapping_impls<'tcx>(
     impl2_def_id: DefId,
     skip_leak_check: SkipLeakCheck,
     overlap_mode: OverlapMode,
-) -> Option<OverlapResult<'tcx>> {
+) -> Result<OverlapResult<'tcx>, Disjoint<'tcx>> {
     // Before doing expensive operations like entering an inference context, do
     // a quick check via fast_reject to tell if the impl headers could possibly
     // unify.
@@ -91,24 +102,22 @@ pub fn overlapping_impls<'tcx>(
     if !may_overlap {
         // Some types involved are definitely different, so the impls couldn't possibly overlap.
         debug!("overlapping_impls: fast_reject early-exit");
-        return None;
+        return Err(Disjoint::default());
     }
 
     let infcx = tcx.infer_ctxt().build();
     let selcx = &mut SelectionContext::intercrate(&infcx);
-    let overlaps =
-        overlap(selcx, skip_leak_check, impl1_def_id, impl2_def_id, overlap_mode).is_some();
-    if !overlaps {
-        return None;
-    }
+    overlap(selcx, skip_leak_check, impl1_def_id, impl2_def_id, overlap_mode)?;
 
     // In the case where we detect an error, run the check again, but
     // this time tracking intercrate ambiguity causes for better
     // diagnostics. (These take time and can lead to false errors.)
     let infcx = tcx.infer_ctxt().build();
-    let selcx = &mut SelectionContext::intercrate(&infcx);
-    selcx.enable_tracking_intercrate_ambiguity_causes();
-    Some(overlap(selcx, skip_leak_check, impl1_def_id, impl2_def_id, overlap_mode).unwrap())
+    let selcx = &mut SelectionContext::with_coherence_mode(
+        &infcx,
+        CoherenceMode::Yes { collect_ambiguity_causes: true },
+    );
+    Ok(overlap(selcx, skip_leak_check, impl1_def_id, impl2_def_id, overlap_mode).unwrap())
 }
 
 fn with_fresh_ty_vars<'cx, 'tcx>(
@@ -141,7 +150,7 @@ fn overlap<'cx, 'tcx>(
     impl1_def_id: DefId,
     impl2_def_id: DefId,
     overlap_mode: OverlapMode,
-) -> Option<OverlapResult<'tcx>> {
+) -> Result<OverlapResult<'tcx>, Disjoint<'tcx>> {
     debug!(
         "overlap(impl1_def_id={:?}, impl2_def_id={:?}, overlap_mode={:?})",
         impl1_def_id, impl2_def_id, overlap_mode
@@ -158,14 +167,14 @@ fn overlap_within_probe<'cx, 'tcx>(
     impl2_def_id: DefId,
     overlap_mode: OverlapMode,
     snapshot: &CombinedSnapshot<'tcx>,
-) -> Option<OverlapResult<'tcx>> {
+) -> Result<OverlapResult<'tcx>, Disjoint<'tcx>> {
     let infcx = selcx.infcx();
 
     if overlap_mode.use_negative_impl() {
         if negative_impl(infcx.tcx, impl1_def_id, impl2_def_id)
             || negative_impl(infcx.tcx, impl2_def_id, impl1_def_id)
         {
-            return None;
+            return Err(Disjoint::default());
         }
     }
 
@@ -178,20 +187,20 @@ fn overlap_within_probe<'cx, 'tcx>(
     let impl1_header = with_fresh_ty_vars(selcx, param_env, impl1_def_id);
     let impl2_header = with_fresh_ty_vars(selcx, param_env, impl2_def_id);
 
-    let obligations = equate_impl_headers(selcx, &impl1_header, &impl2_header)?;
+    let Some(obligations) = equate_impl_headers(selcx, &impl1_header, &impl2_header) else {
+        return Err(Disjoint::default());
+    };
     debug!("overlap: unification check succeeded");
 
     if overlap_mode.use_implicit_negative() {
-        if implicit_negative(selcx, param_env, &impl1_header, impl2_header, obligations) {
-            return None;
-        }
+        implicit_negative(selcx, param_env, &impl1_header, impl2_header, obligations)?;
     }
 
     // We disable the leak when creating the `snapshot` by using
     // `infcx.probe_maybe_disable_leak_check`.
     if infcx.leak_check(true, snapshot).is_err() {
         debug!("overlap: leak check failed");
-        return None;
+        return Err(Disjoint::default());
     }
 
     let intercrate_ambiguity_causes = selcx.take_intercrate_ambiguity_causes();
@@ -201,7 +210,7 @@ fn overlap_within_probe<'cx, 'tcx>(
         matches!(selcx.infcx().region_constraints_added_in_snapshot(snapshot), Some(true));
 
     let impl_header = selcx.infcx().resolve_vars_if_possible(impl1_header);
-    Some(OverlapResult { impl_header, intercrate_ambiguity_causes, involves_placeholder })
+    Ok(OverlapResult { impl_header, intercrate_ambiguity_causes, involves_placeholder })
 }
 
 fn equate_impl_headers<'cx, 'tcx>(
@@ -220,14 +229,14 @@ fn equate_impl_headers<'cx, 'tcx>(
 }
 
 /// Given impl1 and impl2 check if both impls can be satisfied by a common type (including
-/// where-clauses) If so, return false, otherwise return true, they are disjoint.
+/// where-clauses) If so, return `Ok`, otherwise return `Err`, they are disjoint.
 fn implicit_negative<'cx, 'tcx>(
     selcx: &mut SelectionContext<'cx, 'tcx>,
     param_env: ty::ParamEnv<'tcx>,
     impl1_header: &ty::ImplHeader<'tcx>,
     impl2_header: ty::ImplHeader<'tcx>,
     obligations: PredicateObligations<'tcx>,
-) -> bool {
+) -> Result<(), Disjoint<'tcx>> {
     // There's no overlap if obligations are unsatisfiable or if the obligation negated is
     // satisfied.
     //
@@ -254,29 +263,102 @@ fn implicit_negative<'cx, 'tcx>(
         impl1_header, impl2_header, obligations
     );
     let infcx = selcx.infcx();
+    let tcx = infcx.tcx;
+    // Point the obligations at the impl they come from, so that any intercrate
+    // ambiguity causes recorded while evaluating them carry a useful span.
+    let impl1_span = tcx.def_span(impl1_header.impl_def_id);
+    let impl2_span = tcx.def_span(impl2_header.impl_def_id);
     let opt_failing_obligation = impl1_header
         .predicates
         .iter()
-        .copied()
-        .chain(impl2_header.predicates)
-        .map(|p| infcx.resolve_vars_if_possible(p))
-        .map(|p| Obligation {
-            cause: ObligationCause::dummy(),
+        .map(|&p| (p, impl1_span))
+        .chain(impl2_header.predicates.into_iter().map(|p| (p, impl2_span)))
+        .map(|(p, span)| Obligation {
+            cause: ObligationCause::dummy_with_span(span),
             param_env,
             recursion_depth: 0,
-            predicate: p,
+            predicate: infcx.resolve_vars_if_possible(p),
         })
         .chain(obligations)
         .find(|o| !selcx.predicate_may_hold_fatal(o));
 
     if let Some(failing_obligation) = opt_failing_obligation {
         debug!("overlap: obligation unsatisfiable {:?}", failing_obligation);
-        true
+        Err(Disjoint { fundamental_trait_ref: fundamental_trait_ref(selcx, &failing_obligation) })
     } else {
-        false
+        Ok(())
     }
 }
 
+/// Returns the trait ref of `failing_obligation` if the only reason it is known not to hold is
+/// that its trait is a `#[fundamental]` trait of an upstream crate. Without that attribute,
+/// `trait_ref_is_knowable` would have considered the obligation ambiguous, as the upstream
+/// crate could add an impl for it.
+///
+/// A failing projection obligation, like the `Output` of an `Fn()` bound, is considered if the
+/// trait ref it projects from does not hold either.
+fn fundamental_trait_ref<'cx, 'tcx>(
+    selcx: &mut SelectionContext<'cx, 'tcx>,
+    failing_obligation: &PredicateObligation<'tcx>,
+) -> Option<ty::TraitRef<'tcx>> {
+    let infcx = selcx.infcx();
+    let tcx = infcx.tcx;
+    let predicate = infcx.resolve_vars_if_possible(failing_obligation.predicate);
+    let trait_ref = match predicate.kind().skip_binder() {
+        ty::PredicateKind::Trait(trait_pred)
+            if trait_pred.polarity == ty::ImplPolarity::Positive =>
+        {
+            trait_pred.trait_ref
+        }
+        ty::PredicateKind::Projection(proj_pred) => {
+            let trait_ref = proj_pred.projection_ty.trait_ref(tcx);
+            let trait_obligation =
+                failing_obligation.with(tcx, predicate.kind().rebind(trait_ref).without_const());
+            if selcx.predicate_may_hold_fatal(&trait_obligation) {
+                return None;
+            }
+            trait_ref
+        }
+        _ => return None,
+    };
+    trait_ref_relies_on_fundamental_trait(tcx, trait_ref).then_some(trait_ref)
+}
+
+/// Lints the local one of two trait impls which are only disjoint because `trait_ref` does
+/// not hold, as returned in [`Disjoint::fundamental_trait_ref`] by [`overlapping_impls`].
+pub fn lint_fundamental_negative_reasoning<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    impl1_def_id: DefId,
+    impl2_def_id: DefId,
+    trait_ref: ty::TraitRef<'tcx>,
+) {
+    // The later impl is the one which is checked against the other, so lint that one if we can.
+    let (local_impl, other_impl) = match (impl1_def_id.as_local(), impl2_def_id.as_local()) {
+        (_, Some(impl2)) => (impl2, impl1_def_id),
+        (Some(impl1), None) => (impl1, impl2_def_id),
+        (None, None) => return,
+    };
+    tcx.struct_span_lint_hir(
+        COHERENCE_RELIES_ON_FUNDAMENTAL_TRAIT,
+        tcx.hir().local_def_id_to_hir_id(local_impl),
+        tcx.def_span(local_impl),
+        DelayDm(|| {
+            format!(
+                "impl is only disjoint from another impl because `{}` does not implement `{}`",
+                trait_ref.self_ty(),
+                trait_ref.print_only_trait_path(),
+            )
+        }),
+        |lint| {
+            lint.span_note(tcx.def_span(other_impl), "the other impl is here").note(&format!(
+                "`{}` is a `#[fundamental]` trait, so its crate promises not to add this impl, \
+                 which would make the two impls overlap",
+                tcx.def_path_str(trait_ref.def_id),
+            ))
+        },
+    );
+}
+
 /// Given impl1 and impl2 check if both impls are never satisfied by a common type (including
 /// where-clauses) If so, return true, they are disjoint and false otherwise.
 fn negative_impl<'tcx>(tcx: TyCtxt<'tcx>, impl1_def_id: DefId, impl2_def_id: DefId) -> bool {
@@ -374,7 +456,14 @@ fn resolve_negative_obligation<'tcx>(
     };
 
     let param_env = o.param_env;
-    if !super::fully_solve_obligation(&infcx, o).is_empty() {
+    let solver = if tcx.sess.opts.unstable_opts.classic_coherence_solver {
+        TraitSolver::Classic
+    } else {
+        TraitSolver::from_session(tcx)
+    };
+    let ocx = ObligationCtxt::new_with_solver(&infcx, solver);
+    ocx.register_obligation(o);
+    if !ocx.select_all_or_error().is_empty() {
         return false;
     }
 
@@ -384,7 +473,6 @@ fn resolve_negative_obligation<'tcx>(
         (CRATE_HIR_ID, CRATE_DEF_ID)
     };
 
-    let ocx = ObligationCtxt::new(&infcx);
     let wf_tys = ocx.assumed_wf_types(param_env, DUMMY_SP, body_def_id);
     let outlives_env = OutlivesEnvironment::with_bounds(
         param_env,
@@ -402,6 +490,13 @@ pub fn trait_ref_is_knowable<'tcx>(
     trait_ref: ty::TraitRef<'tcx>,
 ) -> Result<(), Conflict> {
     debug!("trait_ref_is_knowable(trait_ref={:?})", trait_ref);
+    if tcx.trait_def(trait_ref.def_id).is_closed_marker {
+        // Closed marker traits can only be implemented in the crate defining
+        // them, and that crate has opted out of any future-compatibility
+        // hedging, so all of their impls are already known.
+        return Ok(());
+    }
+
     if orphan_check_trait_ref(tcx, trait_ref, InCrate::Remote).is_ok() {
         // A downstream or cousin crate is allowed to implement some
         // substitution of this trait-ref.
@@ -434,6 +529,23 @@ pub fn trait_ref_is_knowable<'tcx>(
     }
 }
 
+/// Whether `trait_ref` being unimplemented relies on an upstream crate not
+/// adding impls of one of its `#[fundamental]` traits.
+///
+/// This is the case if the trait is a foreign `#[fundamental]` trait and the
+/// current crate does not own `trait_ref`, so only an upstream crate could
+/// ever implement it. `Sized` is fundamental as well, but cannot be
+/// implemented by anyone, so it is ignored.
+pub fn trait_ref_relies_on_fundamental_trait<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    trait_ref: ty::TraitRef<'tcx>,
+) -> bool {
+    !trait_ref.def_id.is_local()
+        && tcx.has_attr(trait_ref.def_id, sym::fundamental)
+        && tcx.lang_items().sized_trait() != Some(trait_ref.def_id)
+        && orphan_check_trait_ref(tcx, trait_ref, InCrate::Local).is_err()
+}
+
 pub fn trait_ref_is_local_or_fundamental<'tcx>(
     tcx: TyCtxt<'tcx>,
     trait_ref: ty::TraitRef<'tcx>,
@@ -441,6 +553,48 @@ pub fn trait_ref_is_local_or_fundamental<'tcx>(
     trait_ref.def_id.krate == LOCAL_CRATE || tcx.has_attr(trait_ref.def_id, sym::fundamental)
 }
 
+/// Whether `def` is a `#[fundamental]` type which is defined outside of the
+/// standard library.
+fn is_unstable_fundamental_ty(tcx: TyCtxt<'_>, def: ty::AdtDef<'_>) -> bool {
+    def.is_fundamental()
+        && !matches!(tcx.crate_name(def.did().krate), sym::std | sym::core | sym::alloc)
+}
+
+/// Returns a foreign `#[fundamental]` type outside of the standard library
+/// which the orphan check of `impl_def_id` has to look through to succeed.
+///
+/// Such types are only fundamental through an unstable feature of their
+/// crate, which may remove the attribute again, so impls relying on them are
+/// linted by `UNSTABLE_FUNDAMENTAL_TYPE`. Types of the standard library,
+/// like `Box` or `Pin`, are fundamental for good and exempt.
+pub fn orphan_check_unstable_fundamental_ty(tcx: TyCtxt<'_>, impl_def_id: DefId) -> Option<DefId> {
+    let trait_ref = tcx.impl_trait_ref(impl_def_id).unwrap();
+    if trait_ref.def_id.is_local() {
+        return None;
+    }
+
+    // Only run the orphan check again if there is such a type at all.
+    let mentions_unstable_fundamental_ty = trait_ref.substs.types().any(|ty| {
+        ty.walk().any(|arg| match arg.unpack() {
+            GenericArgKind::Type(ty) => match *ty.kind() {
+                ty::Adt(def, _) => !def.did().is_local() && is_unstable_fundamental_ty(tcx, def),
+                _ => false,
+            },
+            _ => false,
+        })
+    });
+    if !mentions_unstable_fundamental_ty {
+        return None;
+    }
+
+    let mut checker = OrphanChecker::new(tcx, InCrate::Local);
+    checker.skip_unstable_fundamental = true;
+    match trait_ref.visit_with(&mut checker) {
+        ControlFlow::Break(OrphanCheckEarlyExit::LocalTy(_)) => None,
+        _ => checker.skipped_fundamental_ty,
+    }
+}
+
 pub enum OrphanCheckErr<'tcx> {
     NonLocalInputType(Vec<(Ty<'tcx>, bool /* Is this the first input type? */)>),
     UncoveredTy(Ty<'tcx>, Option<Ty<'tcx>>),
@@ -594,6 +748,10 @@ struct OrphanChecker<'tcx> {
     /// Ignore orphan check failures and exclusively search for the first
     /// local type.
     search_first_local_ty: bool,
+    /// Treat foreign `#[fundamental]` types outside of the standard library as
+    /// non-fundamental, recording the first one in `skipped_fundamental_ty`.
+    skip_unstable_fundamental: bool,
+    skipped_fundamental_ty: Option<DefId>,
     non_local_tys: Vec<(Ty<'tcx>, bool)>,
 }
 
@@ -604,6 +762,8 @@ fn new(tcx: TyCtxt<'tcx>, in_crate: InCrate) -> Self {
             in_crate,
             in_self_ty: true,
             search_first_local_ty: false,
+            skip_unstable_fundamental: false,
+            skipped_fundamental_ty: None,
             non_local_tys: Vec::new(),
         }
     }
@@ -672,7 +832,12 @@ fn visit_ty(&mut self, ty: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
                 if self.def_id_is_local(def.did()) {
                     ControlFlow::Break(OrphanCheckEarlyExit::LocalTy(ty))
                 } else if def.is_fundamental() {
-                    substs.visit_with(self)
+                    if self.skip_unstable_fundamental && is_unstable_fundamental_ty(self.tcx, def) {
+                        self.skipped_fundamental_ty.get_or_insert(def.did());
+                        self.found_non_local_ty(ty)
+                    } else {
+                        substs.visit_with(self)
+                    }
                 } else {
                     self.found_non_local_ty(ty)
                 }
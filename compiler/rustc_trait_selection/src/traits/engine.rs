@@ -20,27 +20,65 @@
 use rustc_middle::ty::ToPredicate;
 use rustc_middle::ty::TypeFoldable;
 use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::Limit;
 use rustc_span::Span;
 
+/// Which fulfillment engine to prove obligations with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraitSolver {
+    /// The `FulfillmentContext` built on top of `SelectionContext`.
+    Classic,
+    /// The chalk-based `ChalkFulfillmentContext`.
+    Chalk,
+}
+
+impl TraitSolver {
+    /// The solver selected for the whole compilation, which is chalk with `-Zchalk`.
+    pub fn from_session(tcx: TyCtxt<'_>) -> TraitSolver {
+        if tcx.sess.opts.unstable_opts.chalk { TraitSolver::Chalk } else { TraitSolver::Classic }
+    }
+}
+
 pub trait TraitEngineExt<'tcx> {
     fn new(tcx: TyCtxt<'tcx>) -> Box<Self>;
     fn new_in_snapshot(tcx: TyCtxt<'tcx>) -> Box<Self>;
+    fn new_error_tolerant(tcx: TyCtxt<'tcx>) -> Box<Self>;
+    /// Creates a fulfillment context using `solver` rather than the solver selected for the
+    /// whole compilation. This allows moving individual consumers to a different solver, or
+    /// comparing the two within one compilation.
+    ///
+    /// Chalk relies on the well-formed types in scope being part of the param env, which
+    /// is only the case with `-Zchalk`, so `TraitSolver::Chalk` may only be used then.
+    fn new_with_solver(tcx: TyCtxt<'tcx>, solver: TraitSolver) -> Box<Self>;
 }
 
 impl<'tcx> TraitEngineExt<'tcx> for dyn TraitEngine<'tcx> {
     fn new(tcx: TyCtxt<'tcx>) -> Box<Self> {
-        if tcx.sess.opts.unstable_opts.chalk {
-            Box::new(ChalkFulfillmentContext::new())
-        } else {
-            Box::new(FulfillmentContext::new())
-        }
+        Self::new_with_solver(tcx, TraitSolver::from_session(tcx))
     }
 
     fn new_in_snapshot(tcx: TyCtxt<'tcx>) -> Box<Self> {
-        if tcx.sess.opts.unstable_opts.chalk {
-            Box::new(ChalkFulfillmentContext::new_in_snapshot())
-        } else {
-            Box::new(FulfillmentContext::new_in_snapshot())
+        match TraitSolver::from_session(tcx) {
+            TraitSolver::Chalk => Box::new(ChalkFulfillmentContext::new_in_snapshot()),
+            TraitSolver::Classic => Box::new(FulfillmentContext::new_in_snapshot()),
+        }
+    }
+
+    fn new_error_tolerant(tcx: TyCtxt<'tcx>) -> Box<Self> {
+        match TraitSolver::from_session(tcx) {
+            TraitSolver::Chalk => Box::new(ChalkFulfillmentContext::new_error_tolerant()),
+            TraitSolver::Classic => Box::new(FulfillmentContext::new_error_tolerant()),
+        }
+    }
+
+    fn new_with_solver(tcx: TyCtxt<'tcx>, solver: TraitSolver) -> Box<Self> {
+        assert!(
+            solver == TraitSolver::Classic || tcx.sess.opts.unstable_opts.chalk,
+            "chalk can only be used with the param envs built for `-Zchalk`",
+        );
+        match solver {
+            TraitSolver::Chalk => Box::new(ChalkFulfillmentContext::new()),
+            TraitSolver::Classic => Box::new(FulfillmentContext::new()),
         }
     }
 }
@@ -57,14 +95,58 @@ pub fn new(infcx: &'a InferCtxt<'tcx>) -> Self {
         Self { infcx, engine: RefCell::new(<dyn TraitEngine<'_>>::new(infcx.tcx)) }
     }
 
+    /// Like [`ObligationCtxt::new`], but proves the obligations with `solver` instead of
+    /// the solver selected for the whole compilation. See [`TraitEngineExt::new_with_solver`].
+    pub fn new_with_solver(infcx: &'a InferCtxt<'tcx>, solver: TraitSolver) -> Self {
+        Self {
+            infcx,
+            engine: RefCell::new(<dyn TraitEngine<'_>>::new_with_solver(infcx.tcx, solver)),
+        }
+    }
+
     pub fn new_in_snapshot(infcx: &'a InferCtxt<'tcx>) -> Self {
         Self { infcx, engine: RefCell::new(<dyn TraitEngine<'_>>::new_in_snapshot(infcx.tcx)) }
     }
 
+    /// Like [`ObligationCtxt::new`], but [`ObligationCtxt::select_all_or_error`] also
+    /// returns the remaining ambiguities when there are errors, for consumers that want
+    /// as much of inference done as possible despite unsatisfied bounds.
+    pub fn new_error_tolerant(infcx: &'a InferCtxt<'tcx>) -> Self {
+        Self { infcx, engine: RefCell::new(<dyn TraitEngine<'_>>::new_error_tolerant(infcx.tcx)) }
+    }
+
+    /// Creates an obligation context whose obligations overflow once they are nested
+    /// more than `limit` levels deep, or at the crate's recursion limit if that is lower.
+    /// Meant for obligations that are synthesized rather than written by the user, and
+    /// should give up on runaway recursion sooner.
+    ///
+    /// Chalk does not track how deeply obligations are nested, so this must not be
+    /// used with `-Zchalk`.
+    pub fn with_recursion_limit(infcx: &'a InferCtxt<'tcx>, limit: Limit) -> Self {
+        assert_eq!(
+            TraitSolver::from_session(infcx.tcx),
+            TraitSolver::Classic,
+            "`ObligationCtxt::with_recursion_limit` is not supported by chalk",
+        );
+        let engine = Box::new(FulfillmentContext::with_recursion_limit(limit));
+        Self { infcx, engine: RefCell::new(engine) }
+    }
+
     pub fn register_obligation(&self, obligation: PredicateObligation<'tcx>) {
         self.engine.borrow_mut().register_predicate_obligation(self.infcx, obligation);
     }
 
+    /// Registers `obligation` as if it was already nested `depth` levels deep, so that
+    /// it reaches the recursion limit sooner.
+    pub fn register_obligation_at_depth(
+        &self,
+        mut obligation: PredicateObligation<'tcx>,
+        depth: usize,
+    ) {
+        obligation.recursion_depth = depth;
+        self.register_obligation(obligation);
+    }
+
     pub fn register_obligations(
         &self,
         obligations: impl IntoIterator<Item = PredicateObligation<'tcx>>,
@@ -1,14 +1,25 @@
 use rustc_hir::def_id::DefId;
-use rustc_infer::infer::InferCtxt;
+use rustc_infer::infer::{InferCtxt, LateBoundRegionConversionTime};
+use rustc_infer::traits::util::elaborate_predicates_with_span;
 use rustc_infer::traits::{Obligation, ObligationCause, TraitObligation};
-use rustc_span::DUMMY_SP;
+use rustc_middle::ty;
+use rustc_span::{Span, DUMMY_SP};
 
 use crate::traits::ObligationCtxt;
 
+/// A candidate that may apply to an ambiguous obligation.
+#[derive(Debug)]
+pub enum Ambiguity {
+    /// An impl, identified by its `DefId`.
+    DefId(DefId),
+    /// A where-clause in scope, pointing at the bound that may apply.
+    ParamEnv(Span),
+}
+
 pub fn recompute_applicable_impls<'tcx>(
     infcx: &InferCtxt<'tcx>,
     obligation: &TraitObligation<'tcx>,
-) -> Vec<DefId> {
+) -> Vec<Ambiguity> {
     let tcx = infcx.tcx;
     let param_env = obligation.param_env;
     let dummy_cause = ObligationCause::dummy();
@@ -38,15 +49,52 @@ pub fn recompute_applicable_impls<'tcx>(
         ocx.select_where_possible().is_empty()
     };
 
-    let mut impls = Vec::new();
+    let param_env_candidate_may_apply = |poly_trait_predicate: ty::PolyTraitPredicate<'tcx>| {
+        let ocx = ObligationCtxt::new_in_snapshot(infcx);
+        let placeholder_obligation =
+            infcx.replace_bound_vars_with_placeholders(obligation.predicate);
+        let obligation_trait_ref =
+            ocx.normalize(dummy_cause.clone(), param_env, placeholder_obligation.trait_ref);
+
+        let param_env_predicate = infcx.replace_bound_vars_with_fresh_vars(
+            DUMMY_SP,
+            LateBoundRegionConversionTime::HigherRankedType,
+            poly_trait_predicate,
+        );
+        let param_env_trait_ref =
+            ocx.normalize(dummy_cause.clone(), param_env, param_env_predicate.trait_ref);
+
+        ocx.eq(&dummy_cause, param_env, obligation_trait_ref, param_env_trait_ref).is_ok()
+    };
+
+    let mut ambiguities = Vec::new();
+
     tcx.for_each_relevant_impl(
         obligation.predicate.def_id(),
         obligation.predicate.skip_binder().trait_ref.self_ty(),
         |impl_def_id| {
-            if infcx.probe(move |_snapshot| impl_may_apply(impl_def_id)) {
-                impls.push(impl_def_id)
+            if infcx.probe(|_snapshot| impl_may_apply(impl_def_id)) {
+                ambiguities.push(Ambiguity::DefId(impl_def_id))
             }
         },
     );
-    impls
+
+    // Where-clauses don't carry their span in the `ParamEnv`, so look them up
+    // in the predicates of the item the obligation originates from.
+    let obligation_def_id = obligation.predicate.def_id();
+    let predicates =
+        tcx.predicates_of(obligation.cause.body_id.owner.to_def_id()).instantiate_identity(tcx);
+    for where_clause in
+        elaborate_predicates_with_span(tcx, std::iter::zip(predicates.predicates, predicates.spans))
+    {
+        let kind = where_clause.predicate.kind();
+        if let ty::PredicateKind::Trait(trait_pred) = kind.skip_binder()
+            && trait_pred.def_id() == obligation_def_id
+            && infcx.probe(|_snapshot| param_env_candidate_may_apply(kind.rebind(trait_pred)))
+        {
+            ambiguities.push(Ambiguity::ParamEnv(where_clause.cause.span))
+        }
+    }
+
+    ambiguities
 }
@@ -1,25 +1,29 @@
 mod ambiguity;
 pub mod on_unimplemented;
 pub mod suggestions;
+mod transmutability;
 
 use super::{
-    FulfillmentError, FulfillmentErrorCode, MismatchedProjectionTypes, Obligation, ObligationCause,
-    ObligationCauseCode, OutputTypeParameterMismatch, Overflow, PredicateObligation,
-    SelectionContext, SelectionError, TraitNotObjectSafe,
+    ConstituentAccess, FulfillmentError, FulfillmentErrorCode, ImplRejection,
+    MismatchedProjectionTypes, Obligation, ObligationCause, ObligationCauseCode,
+    OutputTypeParameterMismatch, Overflow, PredicateObligation, SelectionContext, SelectionError,
+    TraitNotObjectSafe, TraitObligation,
 };
 use crate::infer::error_reporting::{TyCategory, TypeAnnotationNeeded as ErrorCode};
 use crate::infer::type_variable::{TypeVariableOrigin, TypeVariableOriginKind};
+use crate::infer::InferCtxtExt as _;
 use crate::infer::{self, InferCtxt, TyCtxtInferExt};
 use crate::traits::engine::TraitEngineExt as _;
 use crate::traits::query::evaluate_obligation::InferCtxtExt as _;
 use crate::traits::query::normalize::AtExt as _;
 use crate::traits::specialize::to_pretty_impl_header;
+use crate::traits::ObligationCtxt;
 use on_unimplemented::OnUnimplementedNote;
 use on_unimplemented::TypeErrCtxtExt as _;
-use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap, FxIndexSet};
 use rustc_errors::{
     pluralize, struct_span_err, Applicability, Diagnostic, DiagnosticBuilder, ErrorGuaranteed,
-    MultiSpan, Style,
+    MultiSpan, ObligationCauseNode, Style,
 };
 use rustc_hir as hir;
 use rustc_hir::def::Namespace;
@@ -31,19 +35,24 @@
 use rustc_infer::infer::error_reporting::TypeErrCtxt;
 use rustc_infer::infer::TypeTrace;
 use rustc_infer::traits::TraitEngine;
-use rustc_middle::traits::select::OverflowError;
+use rustc_middle::hir::place::ProjectionKind;
+use rustc_middle::traits::select::{OverflowError, SelectionCandidate};
 use rustc_middle::ty::abstract_const::NotConstEvaluatable;
 use rustc_middle::ty::error::ExpectedFound;
+use rustc_middle::ty::fast_reject::{simplify_type, SimplifiedType, TreatParams};
 use rustc_middle::ty::fold::{TypeFolder, TypeSuperFoldable};
-use rustc_middle::ty::print::{FmtPrinter, Print};
+use rustc_middle::ty::print::{with_no_trimmed_paths, FmtPrinter, Print};
+use rustc_middle::ty::subst::GenericArgKind;
 use rustc_middle::ty::{
     self, SubtypePredicate, ToPolyTraitRef, ToPredicate, TraitRef, Ty, TyCtxt, TypeFoldable,
     TypeVisitable,
 };
 use rustc_session::Limit;
 use rustc_span::def_id::LOCAL_CRATE;
-use rustc_span::symbol::{kw, sym};
+use rustc_span::lev_distance::lev_distance;
+use rustc_span::symbol::{kw, sym, Symbol};
 use rustc_span::{ExpnKind, Span, DUMMY_SP};
+use std::collections::VecDeque;
 use std::fmt;
 use std::iter;
 use std::ops::ControlFlow;
@@ -51,14 +60,21 @@
 
 pub use rustc_infer::traits::error_reporting::*;
 
+/// Number of obligations kept at either end of the chain noted for an overflow.
+const OVERFLOW_CHAIN_EDGE: usize = 3;
+
 // When outputting impl candidates, prefer showing those that are more similar.
 //
 // We also compare candidates after skipping lifetimes, which has a lower
 // priority than exact matches.
+//
+// Finally, impls for local ADTs whose name is a likely typo of the ADT in
+// the obligation are listed after all other candidates.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CandidateSimilarity {
     Exact { ignoring_lifetimes: bool },
     Fuzzy { ignoring_lifetimes: bool },
+    SimilarName { ignoring_lifetimes: bool },
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -116,6 +132,20 @@ fn report_overflow_error<T>(
             + Print<'tcx, FmtPrinter<'tcx, 'tcx>, Output = FmtPrinter<'tcx, 'tcx>>,
         <T as Print<'tcx, FmtPrinter<'tcx, 'tcx>>>::Error: std::fmt::Debug;
 
+    /// Like `report_overflow_error`, but also renders the cycle of obligations on `stack`
+    /// that led to the overflow. `stack` lists the trait obligations being selected,
+    /// innermost first.
+    fn report_overflow_error_with_stack<T>(
+        &self,
+        obligation: &Obligation<'tcx, T>,
+        stack: &[TraitObligation<'tcx>],
+    ) -> !
+    where
+        T: fmt::Display
+            + TypeFoldable<'tcx>
+            + Print<'tcx, FmtPrinter<'tcx, 'tcx>, Output = FmtPrinter<'tcx, 'tcx>>,
+        <T as Print<'tcx, FmtPrinter<'tcx, 'tcx>>>::Error: std::fmt::Debug;
+
     fn suggest_new_overflow_limit(&self, err: &mut Diagnostic);
 
     fn report_overflow_error_cycle(&self, cycle: &[PredicateObligation<'tcx>]) -> !;
@@ -474,41 +504,25 @@ fn report_overflow_error<T>(
             + Print<'tcx, FmtPrinter<'tcx, 'tcx>, Output = FmtPrinter<'tcx, 'tcx>>,
         <T as Print<'tcx, FmtPrinter<'tcx, 'tcx>>>::Error: std::fmt::Debug,
     {
-        let predicate = self.resolve_vars_if_possible(obligation.predicate.clone());
-        let mut pred_str = predicate.to_string();
-        if pred_str.len() > 50 {
-            // We don't need to save the type to a file, we will be talking about this type already
-            // in a separate note when we explain the obligation, so it will be available that way.
-            pred_str = predicate
-                .print(FmtPrinter::new_with_limit(
-                    self.tcx,
-                    Namespace::TypeNS,
-                    rustc_session::Limit(6),
-                ))
-                .unwrap()
-                .into_buffer();
-        }
-        let mut err = struct_span_err!(
-            self.tcx.sess,
-            obligation.cause.span,
-            E0275,
-            "overflow evaluating the requirement `{}`",
-            pred_str,
-        );
-
-        if suggest_increasing_limit {
-            self.suggest_new_overflow_limit(&mut err);
-        }
-
-        self.note_obligation_cause_code(
-            &mut err,
-            &obligation.predicate,
-            obligation.param_env,
-            obligation.cause.code(),
-            &mut vec![],
-            &mut Default::default(),
-        );
+        let mut err = self.build_overflow_error(obligation, suggest_increasing_limit);
+        err.emit();
+        self.tcx.sess.abort_if_errors();
+        bug!();
+    }
 
+    fn report_overflow_error_with_stack<T>(
+        &self,
+        obligation: &Obligation<'tcx, T>,
+        stack: &[TraitObligation<'tcx>],
+    ) -> !
+    where
+        T: fmt::Display
+            + TypeFoldable<'tcx>
+            + Print<'tcx, FmtPrinter<'tcx, 'tcx>, Output = FmtPrinter<'tcx, 'tcx>>,
+        <T as Print<'tcx, FmtPrinter<'tcx, 'tcx>>>::Error: std::fmt::Debug,
+    {
+        let mut err = self.build_overflow_error(obligation, true);
+        self.note_overflow_obligation_stack(&mut err, stack);
         err.emit();
         self.tcx.sess.abort_if_errors();
         bug!();
@@ -603,6 +617,10 @@ fn report_selection_error(
                             return;
                         }
                         let trait_ref = trait_predicate.to_poly_trait_ref();
+                        // Very long types are written out to a file and only shown in a shortened
+                        // form in the diagnostic itself.
+                        let (self_ty_str, long_ty_file) =
+                            self.tcx.short_ty_string(trait_ref.skip_binder().self_ty());
                         let (post_message, pre_message, type_def) = self
                             .get_parent_trait_ref(obligation.cause.code())
                             .map(|(t, s)| {
@@ -664,10 +682,21 @@ fn report_selection_error(
                                         (true, None) => None,
                                     }
                                 })
-                                .unwrap_or_else(|| format!(
-                                    "the trait bound `{}` is not satisfied{}",
-                                    trait_predicate, post_message,
-                                ))
+                                .unwrap_or_else(|| if long_ty_file.is_some() {
+                                    format!(
+                                        "the trait bound `{}: {}` is not satisfied{}",
+                                        self_ty_str,
+                                        trait_predicate
+                                            .skip_binder()
+                                            .print_modifiers_and_trait_path(),
+                                        post_message,
+                                    )
+                                } else {
+                                    format!(
+                                        "the trait bound `{}` is not satisfied{}",
+                                        trait_predicate, post_message,
+                                    )
+                                })
                         );
 
                         if is_try_conversion && let Some(ret_span) = self.return_type_span(&obligation) {
@@ -723,13 +752,13 @@ fn report_selection_error(
                                     pre_message,
                                     trait_predicate.print_modifiers_and_trait_path(),
                                     desc,
-                                    trait_ref.skip_binder().self_ty(),
+                                    self_ty_str,
                                 ),
                                 None => format!(
                                     "{}the trait `{}` is not implemented for `{}`",
                                     pre_message,
                                     trait_predicate.print_modifiers_and_trait_path(),
-                                    trait_ref.skip_binder().self_ty(),
+                                    self_ty_str,
                                 ),
                             }
                         };
@@ -757,6 +786,35 @@ fn report_selection_error(
                         } else {
                             err.span_label(span, explanation);
                         }
+                        if let Some(file) = &long_ty_file {
+                            err.note(&format!(
+                                "the full type name has been written to '{}'",
+                                file.display(),
+                            ));
+                        }
+
+                        if Some(trait_ref.def_id()) == tcx.lang_items().transmute_trait()
+                            && let Some(explanation) = transmutability::explain_rejection(
+                                self.infcx,
+                                &obligation,
+                                trait_predicate,
+                            )
+                        {
+                            err.note(&explanation);
+                        }
+
+                        if Some(trait_ref.def_id()) == tcx.lang_items().transmute_trait()
+                            && let Some(assumption) = transmutability::missing_assumption(
+                                self.infcx,
+                                &obligation,
+                                trait_predicate,
+                            )
+                        {
+                            err.note(&format!(
+                                "the transmutation would be accepted if `Assume::{}` were set",
+                                assumption,
+                            ));
+                        }
 
                         if let ObligationCauseCode::ObjectCastObligation(concrete_ty, obj_ty) = obligation.cause.code().peel_derives() &&
                             Some(trait_ref.def_id()) == self.tcx.lang_items().sized_trait() {
@@ -812,6 +870,8 @@ fn report_selection_error(
                         suggested |= self.suggest_fn_call(&obligation, &mut err, trait_predicate);
                         suggested |=
                             self.suggest_remove_reference(&obligation, &mut err, trait_predicate);
+                        suggested |=
+                            self.suggest_clone_or_as_ref(&obligation, &mut err, trait_predicate);
                         suggested |= self.suggest_semicolon_removal(
                             &obligation,
                             &mut err,
@@ -819,6 +879,15 @@ fn report_selection_error(
                             trait_predicate,
                         );
                         self.note_version_mismatch(&mut err, &trait_ref);
+                        self.suggest_enabling_dependency_feature(&mut err, trait_predicate);
+                        self.note_rejected_impls(&mut err, &obligation, trait_predicate);
+                        self.note_object_supertrait_mismatches(
+                            &mut err,
+                            &obligation,
+                            trait_predicate,
+                        );
+                        self.note_auto_trait_access_path(&mut err, &obligation, trait_predicate);
+                        self.note_pointer_sized_layout(&mut err, &obligation, trait_predicate);
                         self.suggest_remove_await(&obligation, &mut err);
                         self.suggest_derive(&obligation, &mut err, trait_predicate);
 
@@ -936,15 +1005,23 @@ fn report_selection_error(
                                 None,
                                 obligation.cause.body_id,
                             );
+                            self.suggest_adjusting_near_miss_param_bound(
+                                &mut err,
+                                obligation.param_env,
+                                trait_predicate,
+                                obligation.cause.body_id,
+                            );
                         } else if !suggested && !unsatisfied_const {
                             // Can't show anything else useful, try to find similar impls.
                             let impl_candidates = self.find_similar_impl_candidates(trait_predicate);
-                            if !self.report_similar_impl_candidates(
-                                impl_candidates,
-                                trait_ref,
-                                obligation.cause.body_id,
-                                &mut err,
-                            ) {
+                            if !self.note_const_arg_impl_candidates(&mut err, trait_predicate)
+                                && !self.report_similar_impl_candidates(
+                                    impl_candidates,
+                                    trait_ref,
+                                    obligation.cause.body_id,
+                                    &mut err,
+                                )
+                            {
                                 // This is *almost* equivalent to
                                 // `obligation.cause.code().peel_derives()`, but it gives us the
                                 // trait predicate for that corresponding root obligation. This
@@ -1110,6 +1187,34 @@ fn report_selection_error(
                                             ty::place_to_string_for_capture(tcx, place)
                                         ),
                                     );
+                                    // Moving out a clone instead lets the closure be called
+                                    // more than once.
+                                    let is_clone = tcx.lang_items().clone_trait().map_or(
+                                        false,
+                                        |clone_trait| {
+                                            self.type_implements_trait(
+                                                clone_trait,
+                                                [place.ty()],
+                                                obligation.param_env,
+                                            )
+                                            .must_apply_modulo_regions()
+                                        },
+                                    );
+                                    if is_clone
+                                        && span.can_be_used_for_suggestions()
+                                        && !place
+                                            .projections
+                                            .iter()
+                                            .any(|proj| proj.kind == ProjectionKind::Deref)
+                                    {
+                                        err.span_suggestion_verbose(
+                                            span.shrink_to_hi(),
+                                            "consider cloning the value instead of moving it \
+                                             out of the closure's environment",
+                                            ".clone()",
+                                            Applicability::MaybeIncorrect,
+                                        );
+                                    }
                                 }
                                 (ty::ClosureKind::FnMut, Some((span, place))) => {
                                     err.span_label(
@@ -1386,6 +1491,8 @@ fn find_similar_impl_candidates(
         trait_pred: ty::PolyTraitPredicate<'tcx>,
     ) -> Vec<ImplCandidate<'tcx>>;
 
+    fn adt_names_are_similar(&self, obligation_adt: DefId, impl_adt: DefId) -> bool;
+
     fn report_similar_impl_candidates(
         &self,
         impl_candidates: Vec<ImplCandidate<'tcx>>,
@@ -1394,6 +1501,15 @@ fn report_similar_impl_candidates(
         err: &mut Diagnostic,
     ) -> bool;
 
+    /// If the `Self` type of the unsatisfied `trait_pred` only differs in one const argument
+    /// from the `Self` types of several impls of the trait, lists the values of that argument
+    /// the trait is implemented for.
+    fn note_const_arg_impl_candidates(
+        &self,
+        err: &mut Diagnostic,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) -> bool;
+
     /// Gets the parent trait chain start
     fn get_parent_trait_ref(
         &self,
@@ -1409,6 +1525,60 @@ fn note_version_mismatch(
         trait_ref: &ty::PolyTraitRef<'tcx>,
     ) -> bool;
 
+    /// If the crate of the trait or of the `Self` type of the unsatisfied `trait_pred` has an
+    /// impl for them that was removed by a `#[cfg(feature = "...")]` attribute, suggests
+    /// enabling that feature.
+    fn suggest_enabling_dependency_feature(
+        &self,
+        err: &mut Diagnostic,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    );
+
+    /// Points at the impls which selection rejected for the unsatisfied `trait_pred`
+    /// and explains why.
+    fn note_rejected_impls(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    );
+
+    /// For a local impl whose header does not match `trait_pred`, returns the types which the
+    /// generic parameters in `trait_pred` would have to be for the impl to match, if any.
+    fn generic_param_instantiations(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+        impl_def_id: DefId,
+    ) -> Option<Vec<(Ty<'tcx>, Ty<'tcx>)>>;
+
+    /// For an unsatisfied `dyn Trait: Super<Args>` obligation, notes which instantiations of
+    /// `Super` the trait object provides instead, and through which supertraits of `Trait`.
+    fn note_object_supertrait_mismatches(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    );
+
+    /// For an unsatisfied auto trait obligation on a type nested within others, notes the
+    /// path of fields and captures from the outermost type down to the offending one.
+    fn note_auto_trait_access_path(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    );
+
+    /// For an unsatisfied `PointerSized` obligation, notes how the layout of the self type
+    /// differs from that of `usize`, or that it depends on generic parameters.
+    fn note_pointer_sized_layout(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    );
+
     /// Creates a `PredicateObligation` with `new_self_ty` replacing the existing type in the
     /// `trait_ref`.
     ///
@@ -1442,8 +1612,9 @@ fn suggest_unsized_bound_if_applicable(
     fn annotate_source_of_ambiguity(
         &self,
         err: &mut Diagnostic,
-        impls: &[DefId],
+        impls: &[ambiguity::Ambiguity],
         predicate: ty::Predicate<'tcx>,
+        where_clauses_shadow_impls: bool,
     );
 
     fn maybe_suggest_unsized_generics(&self, err: &mut Diagnostic, span: Span, node: Node<'tcx>);
@@ -1460,6 +1631,23 @@ fn is_recursive_obligation(
         obligated_types: &mut Vec<Ty<'tcx>>,
         cause_code: &ObligationCauseCode<'tcx>,
     ) -> bool;
+
+    fn build_overflow_error<T>(
+        &self,
+        obligation: &Obligation<'tcx, T>,
+        suggest_increasing_limit: bool,
+    ) -> DiagnosticBuilder<'tcx, ErrorGuaranteed>
+    where
+        T: fmt::Display
+            + TypeFoldable<'tcx>
+            + Print<'tcx, FmtPrinter<'tcx, 'tcx>, Output = FmtPrinter<'tcx, 'tcx>>,
+        <T as Print<'tcx, FmtPrinter<'tcx, 'tcx>>>::Error: std::fmt::Debug;
+
+    /// Notes the chain of obligations that led to an overflow: the selection `stack`,
+    /// followed by the parents of its outermost obligation. If a segment at the top of
+    /// that chain keeps repeating, it is noted as the obligation cycle responsible for the
+    /// overflow, otherwise the middle of the chain is elided unless `-Zverbose` is set.
+    fn note_overflow_obligation_stack(&self, err: &mut Diagnostic, stack: &[TraitObligation<'tcx>]);
 }
 
 impl<'tcx> InferCtxtPrivExt<'tcx> for TypeErrCtxt<'_, 'tcx> {
@@ -1766,6 +1954,11 @@ fn type_category(tcx: TyCtxt<'_>, t: Ty<'_>) -> Option<u32> {
             Some(CandidateSimilarity::Exact { ignoring_lifetimes })
         } else if cat_a == cat_b {
             match (a.kind(), b.kind()) {
+                (ty::Adt(def_a, _), ty::Adt(def_b, _)) if def_a != def_b => {
+                    return self
+                        .adt_names_are_similar(def_a.did(), def_b.did())
+                        .then_some(CandidateSimilarity::SimilarName { ignoring_lifetimes });
+                }
                 (ty::Adt(def_a, _), ty::Adt(def_b, _)) => def_a == def_b,
                 (ty::Foreign(def_a), ty::Foreign(def_b)) => def_a == def_b,
                 // Matching on references results in a lot of unhelpful
@@ -1786,6 +1979,20 @@ fn type_category(tcx: TyCtxt<'_>, t: Ty<'_>) -> Option<u32> {
         }
     }
 
+    fn adt_names_are_similar(&self, obligation_adt: DefId, impl_adt: DefId) -> bool {
+        // Only suggest types from the local crate, mentioning every similarly
+        // named type from upstream crates would mostly be noise.
+        if !impl_adt.is_local() {
+            return false;
+        }
+        let a = self.tcx.item_name(obligation_adt);
+        let b = self.tcx.item_name(impl_adt);
+        let (a, b) = (a.as_str(), b.as_str());
+        let len = a.chars().count().max(b.chars().count());
+        // Very short names are too easily within a few edits of each other.
+        len >= 4 && lev_distance(a, b, len / 3).is_some()
+    }
+
     fn describe_generator(&self, body_id: hir::BodyId) -> Option<&'static str> {
         self.tcx.hir().body(body_id).generator_kind.map(|gen_kind| match gen_kind {
             hir::GeneratorKind::Gen => "a generator",
@@ -1799,6 +2006,15 @@ fn find_similar_impl_candidates(
         &self,
         trait_pred: ty::PolyTraitPredicate<'tcx>,
     ) -> Vec<ImplCandidate<'tcx>> {
+        // A type that explicitly opts out of the trait is not a misspelling of one of the
+        // types that implement it.
+        let opted_out = trait_pred.skip_binder().self_ty().ty_adt_def().map_or(false, |adt| {
+            self.tcx.all_impls(trait_pred.def_id()).any(|def_id| {
+                self.tcx.impl_polarity(def_id) == ty::ImplPolarity::Negative
+                    && self.tcx.type_of(def_id).ty_adt_def() == Some(adt)
+            })
+        });
+
         self.tcx
             .all_impls(trait_pred.def_id())
             .filter_map(|def_id| {
@@ -1813,6 +2029,10 @@ fn find_similar_impl_candidates(
                 let imp = self.tcx.impl_trait_ref(def_id).unwrap();
 
                 self.fuzzy_match_tys(trait_pred.skip_binder().self_ty(), imp.self_ty(), false)
+                    .filter(|similarity| {
+                        !(opted_out
+                            && matches!(similarity, CandidateSimilarity::SimilarName { .. }))
+                    })
                     .map(|similarity| ImplCandidate { trait_ref: imp, similarity })
             })
             .collect()
@@ -1884,11 +2104,16 @@ fn report_similar_impl_candidates(
         };
 
         let def_id = trait_ref.def_id();
-        if impl_candidates.is_empty() {
-            if self.tcx.trait_is_auto(def_id)
-                || self.tcx.lang_items().iter().any(|(_, id)| id == def_id)
-                || self.tcx.get_diagnostic_name(def_id).is_some()
-            {
+        // Types that are only similarly named are not worth pointing out if we
+        // are going to list all implementers anyways.
+        let only_similar_names = impl_candidates
+            .iter()
+            .all(|c| matches!(c.similarity, CandidateSimilarity::SimilarName { .. }));
+        let is_well_known_trait = self.tcx.trait_is_auto(def_id)
+            || self.tcx.lang_items().iter().any(|(_, id)| id == def_id)
+            || self.tcx.get_diagnostic_name(def_id).is_some();
+        if impl_candidates.is_empty() || (only_similar_names && !is_well_known_trait) {
+            if is_well_known_trait {
                 // Mentioning implementers of `Copy`, `Debug` and friends is not useful.
                 return false;
             }
@@ -1955,6 +2180,127 @@ fn report_similar_impl_candidates(
         report(normalized_impl_candidates, err)
     }
 
+    fn note_const_arg_impl_candidates(
+        &self,
+        err: &mut Diagnostic,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) -> bool {
+        let tcx = self.tcx;
+        let param_env = ty::ParamEnv::empty();
+        // Arrays are treated like an ADT with the element type and length as arguments.
+        let args_of = |ty: Ty<'tcx>| match *ty.kind() {
+            ty::Adt(def, substs) => Some((Some(def.did()), substs.to_vec())),
+            ty::Array(elem_ty, len) => Some((None, vec![elem_ty.into(), len.into()])),
+            _ => None,
+        };
+        let self_ty = self.resolve_vars_if_possible(trait_pred.skip_binder().self_ty());
+        let Some((adt, self_args)) = args_of(self_ty) else { return false };
+        let is_value = |ct: ty::Const<'tcx>| matches!(ct.kind(), ty::ConstKind::Value(_));
+
+        let mut position = None;
+        let mut candidates = vec![];
+        for impl_def_id in tcx.all_impls(trait_pred.def_id()) {
+            if tcx.impl_polarity(impl_def_id) == ty::ImplPolarity::Negative {
+                continue;
+            }
+            let Some(impl_trait_ref) = tcx.impl_trait_ref(impl_def_id) else { continue };
+            let Some((impl_adt, impl_args)) = args_of(impl_trait_ref.self_ty()) else { continue };
+            if impl_adt != adt {
+                continue;
+            }
+            // The single const argument in which the impl's `Self` type differs, if any.
+            let mut differing = None;
+            let unifies =
+                iter::zip(&self_args, &impl_args).enumerate().all(|(i, (arg, impl_arg))| {
+                    match (arg.unpack(), impl_arg.unpack()) {
+                        (GenericArgKind::Lifetime(_), GenericArgKind::Lifetime(_)) => true,
+                        (GenericArgKind::Type(ty), GenericArgKind::Type(impl_ty)) => {
+                            ty == impl_ty || matches!(impl_ty.kind(), ty::Param(_))
+                        }
+                        (GenericArgKind::Const(ct), GenericArgKind::Const(impl_ct)) => {
+                            let (ct, impl_ct) =
+                                (ct.eval(tcx, param_env), impl_ct.eval(tcx, param_env));
+                            if ct == impl_ct {
+                                true
+                            } else if is_value(ct) && is_value(impl_ct) && differing.is_none() {
+                                differing = Some((i, impl_ct));
+                                true
+                            } else {
+                                false
+                            }
+                        }
+                        _ => false,
+                    }
+                });
+            let (true, Some((i, impl_ct))) = (unifies, differing) else { continue };
+            if *position.get_or_insert(i) != i {
+                // The impls differ in different arguments, there is no single list to show.
+                return false;
+            }
+            candidates.push((impl_def_id, impl_ct));
+        }
+        // A single candidate is already pointed out by `report_similar_impl_candidates`.
+        let Some(position) = position.filter(|_| candidates.len() > 1) else { return false };
+
+        let bits: Option<Vec<u128>> = match candidates[0].1.ty().kind() {
+            ty::Uint(_) => {
+                candidates.iter().map(|(_, ct)| ct.try_eval_bits(tcx, param_env, ct.ty())).collect()
+            }
+            _ => None,
+        };
+        let list = |mut values: Vec<String>| {
+            let len = values.len();
+            let end = if len <= 9 { len } else { 8 };
+            values.truncate(end);
+            format!(
+                "one of {}{}",
+                values.iter().map(|value| format!("`{value}`")).collect::<Vec<_>>().join(", "),
+                if len > 9 { format!(" and {} others", len - 8) } else { String::new() }
+            )
+        };
+        let mut range = None;
+        let values = match bits {
+            Some(mut bits) => {
+                bits.sort();
+                bits.dedup();
+                let (min, max) = (bits[0], bits[bits.len() - 1]);
+                if bits.len() > 2 && max - min == bits.len() as u128 - 1 {
+                    range = Some(format!("{min}..={max}"));
+                    format!("in `{min}..={max}`")
+                } else {
+                    list(bits.iter().map(|bits| bits.to_string()).collect())
+                }
+            }
+            None => {
+                let mut values: Vec<_> = candidates.iter().map(|(_, ct)| ct.to_string()).collect();
+                values.sort();
+                values.dedup();
+                list(values)
+            }
+        };
+
+        let (subject, arg) = match adt {
+            Some(did) => (
+                format!("`{}`", tcx.def_path_str(did)),
+                format!("`{}`", tcx.generics_of(did).param_at(position, tcx).name),
+            ),
+            None => ("arrays".to_string(), "the length".to_string()),
+        };
+        err.help(&format!(
+            "the trait `{}` is implemented for {subject} only when {arg} is {values}",
+            trait_pred.skip_binder().trait_ref.print_only_trait_path(),
+        ));
+        if let Some(range) = range
+            && candidates.iter().all(|&(impl_def_id, _)| tcx.def_span(impl_def_id).from_expansion())
+        {
+            err.note(&format!(
+                "these impls are generated by a macro for each value in `{range}`, \
+                 other values are not covered"
+            ));
+        }
+        true
+    }
+
     /// Gets the parent trait chain start
     fn get_parent_trait_ref(
         &self,
@@ -2015,6 +2361,416 @@ fn note_version_mismatch(
         suggested
     }
 
+    fn suggest_enabling_dependency_feature(
+        &self,
+        err: &mut Diagnostic,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let trait_def_id = trait_pred.def_id();
+        let self_ty = trait_pred.skip_binder().self_ty();
+        let Some(simplified_self_ty) = simplify_type(tcx, self_ty, TreatParams::AsInfer) else {
+            return;
+        };
+        let self_ty_crate = match simplified_self_ty {
+            SimplifiedType::AdtSimplifiedType(def_id)
+            | SimplifiedType::ForeignSimplifiedType(def_id) => Some(def_id.krate),
+            _ => None,
+        };
+        let trait_path = Symbol::intern(&with_no_trimmed_paths!(tcx.def_path_str(trait_def_id)));
+
+        // Coherence only allows the impl to live in the crate of the trait or of the type.
+        let crates = iter::once(trait_def_id.krate).chain(self_ty_crate);
+        for krate in crates.filter(|&krate| krate != LOCAL_CRATE) {
+            let Some(stripped) = tcx.stripped_cfg_impls(krate).iter().find(|stripped| {
+                let same_trait = match stripped.trait_ {
+                    ty::StrippedCfgImplTrait::Resolved(def_id) => def_id == trait_def_id,
+                    ty::StrippedCfgImplTrait::UnloadedCrate(path) => path == trait_path,
+                };
+                same_trait && stripped.self_ty == simplified_self_ty
+            }) else {
+                continue;
+            };
+            err.help(&format!(
+                "the trait `{}` is implemented for `{}` if the `{}` feature of the `{}` crate \
+                 is enabled",
+                trait_pred.print_modifiers_and_trait_path(),
+                self_ty,
+                stripped.feature,
+                tcx.crate_name(krate),
+            ));
+            return;
+        }
+    }
+
+    fn note_object_supertrait_mismatches(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let self_ty = trait_pred.skip_binder().self_ty();
+        let ty::Dynamic(data, ..) = self_ty.kind() else { return };
+        let Some(principal) = data.principal() else { return };
+        let mismatches = self.probe(|_| {
+            let mut selcx = SelectionContext::new(self);
+            selcx.enable_tracking_object_supertrait_mismatches();
+            let _ = selcx.select(&obligation.with(tcx, trait_pred));
+            selcx.take_object_supertrait_mismatches()
+        });
+
+        let principal = principal.with_self_ty(tcx, self_ty);
+        let mut notes = vec![];
+        for mismatch in mismatches {
+            let mismatch = tcx.erase_regions(mismatch);
+            // Search the supertraits of the principal breadth-first for the chain leading to
+            // the mismatched one, along with the span of the last bound in that chain.
+            let mut queue = VecDeque::from([(principal, vec![principal], None)]);
+            let mut visited = FxHashSet::default();
+            let mut found = None;
+            while let Some((trait_ref, chain, span)) = queue.pop_front() {
+                if tcx.erase_regions(trait_ref) == mismatch {
+                    found = Some((chain, span));
+                    break;
+                }
+                for &(pred, span) in tcx.super_predicates_of(trait_ref.def_id()).predicates {
+                    let super_pred = pred.subst_supertrait(tcx, &trait_ref);
+                    let Some(super_pred) = super_pred.to_opt_poly_trait_pred() else { continue };
+                    let super_trait_ref = super_pred.to_poly_trait_ref();
+                    if visited.insert(super_trait_ref) {
+                        let mut chain = chain.clone();
+                        chain.push(super_trait_ref);
+                        queue.push_back((super_trait_ref, chain, Some(span)));
+                    }
+                }
+            }
+            let Some((chain, Some(span))) = found else { continue };
+            let chain: Vec<_> = chain
+                .iter()
+                .map(|trait_ref| trait_ref.print_only_trait_path().to_string())
+                .collect();
+            notes.push((
+                span,
+                format!(
+                    "`{}` implements `{}` instead of `{}`, through its supertraits `{}`",
+                    self_ty,
+                    mismatch.print_only_trait_path(),
+                    trait_pred.print_modifiers_and_trait_path(),
+                    chain.join(": "),
+                ),
+            ));
+        }
+        // The supertraits are elaborated in reverse, so sort the notes to follow the order in
+        // which the bounds were written.
+        notes.sort_by_key(|(span, _)| *span);
+        for (span, note) in notes {
+            err.span_note(span, &note);
+        }
+    }
+
+    fn note_auto_trait_access_path(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let trait_def_id = trait_pred.def_id();
+        if !tcx.trait_is_auto(trait_def_id) {
+            return;
+        }
+
+        // The types from the offending one up to the outermost one, together with whether
+        // each of them is a constituent type of the next one or only required by an impl.
+        let mut tys = vec![self.resolve_vars_if_possible(trait_pred.self_ty())];
+        let mut is_constituent = vec![];
+        let mut code = obligation.cause.code();
+        loop {
+            let (data, constituent) = match code {
+                ObligationCauseCode::BuiltinDerivedObligation(data) => (data, true),
+                ObligationCauseCode::ImplDerivedObligation(cause) => (&cause.derived, false),
+                _ => break,
+            };
+            let parent_trait_pred = self.resolve_vars_if_possible(data.parent_trait_pred);
+            if parent_trait_pred.def_id() != trait_def_id {
+                break;
+            }
+            tys.push(parent_trait_pred.self_ty());
+            is_constituent.push(constituent);
+            code = &data.parent_code;
+        }
+        // A single level is already explained by the "appears within the type" note.
+        if tys.len() < 3 {
+            return;
+        }
+
+        let selcx = SelectionContext::new(self);
+        let erase =
+            |ty: ty::Binder<'tcx, Ty<'tcx>>| tcx.erase_regions(tcx.erase_late_bound_regions(ty));
+        let mut segments = vec![];
+        // The suspension points of the innermost generator on the path so far are await
+        // points if it is an `async` one, and yield points otherwise.
+        let mut is_async = false;
+        for i in (1..tys.len()).rev() {
+            let (parent, child) = (tys[i], tys[i - 1]);
+            if let ty::Generator(def_id, ..) = *parent.skip_binder().kind() {
+                is_async =
+                    matches!(tcx.generator_kind(def_id), Some(hir::GeneratorKind::Async(..)));
+            }
+            let (await_or_yield, an_await_or_yield) =
+                if is_async { ("await", "an await") } else { ("yield", "a yield") };
+            let access = if is_constituent[i - 1] {
+                selcx
+                    .constituent_types_with_access(parent)
+                    .skip_binder()
+                    .into_iter()
+                    .find(|&(ty, _)| erase(parent.rebind(ty)) == erase(child))
+                    .map(|(_, access)| access)
+            } else {
+                None
+            };
+            let parent = parent.skip_binder();
+            segments.push(match access {
+                Some(ConstituentAccess::Field { variant: Some(variant), name }) => {
+                    format!("`{}::{}.{}`", parent, variant, name)
+                }
+                Some(ConstituentAccess::Field { variant: None, name }) => {
+                    format!("`{}.{}`", parent, name)
+                }
+                Some(ConstituentAccess::TupleField(idx)) => format!("`{}.{}`", parent, idx),
+                Some(ConstituentAccess::Element) => format!("the elements of `{}`", parent),
+                Some(ConstituentAccess::Pointee) => format!("the pointee of `{}`", parent),
+                Some(ConstituentAccess::PhantomData) => {
+                    format!("the type argument of `{}`", parent)
+                }
+                Some(ConstituentAccess::Upvars) => format!("the captures of `{}`", parent),
+                Some(ConstituentAccess::Witness) => {
+                    format!("the values held across {} points by `{}`", await_or_yield, parent)
+                }
+                Some(ConstituentAccess::HeldAcrossYield) => {
+                    format!("a value held across {} point", an_await_or_yield)
+                }
+                Some(ConstituentAccess::HiddenType) => {
+                    format!("the hidden type of `{}`", parent)
+                }
+                None => format!("`{}`", parent),
+            });
+        }
+        segments.push(format!("`{}`", tys[0].skip_binder()));
+
+        err.note(&format!(
+            "the type that is not `{}` is reached through {}",
+            tcx.def_path_str(trait_def_id),
+            segments.join(" \u{2192} "),
+        ));
+    }
+
+    fn note_pointer_sized_layout(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        if Some(trait_pred.def_id()) != tcx.lang_items().pointer_sized() {
+            return;
+        }
+        let self_ty = self.resolve_vars_if_possible(trait_pred.self_ty());
+        let self_ty = tcx.erase_regions(tcx.erase_late_bound_regions(self_ty));
+        if self_ty.references_error() || self_ty.has_non_region_infer() {
+            return;
+        }
+
+        match tcx.layout_of(obligation.param_env.and(self_ty)) {
+            Ok(layout) => {
+                let Ok(usize_layout) = tcx.layout_of(ty::ParamEnv::empty().and(tcx.types.usize))
+                else {
+                    return;
+                };
+                err.note(&format!(
+                    "`{}` has size {}, align {}; expected size {}, align {}, like `usize`",
+                    self_ty,
+                    layout.size.bytes(),
+                    layout.align.abi.bytes(),
+                    usize_layout.size.bytes(),
+                    usize_layout.align.abi.bytes(),
+                ));
+            }
+            Err(_) if self_ty.has_non_region_param() => {
+                err.note(&format!(
+                    "the layout of `{}` depends on generic parameters, so it is not known to \
+                     match the layout of `usize`",
+                    self_ty,
+                ));
+            }
+            Err(_) => {}
+        }
+    }
+
+    fn note_rejected_impls(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) {
+        fn listify(items: &[String]) -> String {
+            match items {
+                [] => String::new(),
+                [only] => only.clone(),
+                [init @ .., last] => format!("{} and {}", init.join(", "), last),
+            }
+        }
+
+        let tcx = self.tcx;
+        // Select the obligation again, remembering the impls which selection
+        // rejected and why.
+        let rejected_impls = self.probe(|_| {
+            let mut selcx = SelectionContext::new(self);
+            selcx.enable_tracking_rejected_impls();
+            let _ = selcx.select(&obligation.with(tcx, trait_pred));
+            selcx.take_rejected_impls()
+        });
+
+        let self_ty = trait_pred.skip_binder().self_ty();
+        let is_auto = tcx.trait_is_auto(trait_pred.def_id());
+        for (impl_def_id, rejection) in rejected_impls {
+            // The impls of the standard library are documented and there are a lot of them,
+            // so pointing at them would mostly be noise.
+            if matches!(tcx.crate_name(impl_def_id.krate), sym::std | sym::core | sym::alloc) {
+                continue;
+            }
+            let impl_span = tcx.def_span(impl_def_id);
+            match rejection {
+                ImplRejection::HeaderMismatch => {
+                    // Most impls don't match because they are for different types. Only
+                    // point at local ones which would match if the generic parameters of
+                    // the obligation were the right types.
+                    if !impl_def_id.is_local() {
+                        continue;
+                    }
+                    let Some(instantiations) =
+                        self.generic_param_instantiations(obligation, trait_pred, impl_def_id)
+                    else {
+                        continue;
+                    };
+                    let requirements: Vec<_> = instantiations
+                        .iter()
+                        .map(|(param, ty)| format!("`{}` to be `{}`", param, ty))
+                        .collect();
+                    let what = if requirements.len() == 1 {
+                        "it is a generic parameter"
+                    } else {
+                        "they are generic parameters"
+                    };
+                    err.span_note(
+                        impl_span,
+                        &format!(
+                            "this impl doesn't apply because it requires {}, but {}",
+                            listify(&requirements),
+                            what,
+                        ),
+                    );
+                }
+                // Negative impls of auto traits are already what the error is about.
+                ImplRejection::Polarity if is_auto => {}
+                ImplRejection::Polarity => {
+                    if tcx.impl_polarity(impl_def_id) != ty::ImplPolarity::Negative {
+                        continue;
+                    }
+                    err.span_note(
+                        impl_span,
+                        &format!(
+                            "the trait `{}` is explicitly not implemented for `{}` here",
+                            trait_pred.print_modifiers_and_trait_path(),
+                            self_ty,
+                        ),
+                    );
+                }
+                ImplRejection::NotConst => {
+                    err.span_note(
+                        impl_span,
+                        "this implementation is not `impl const`, so it can't satisfy `~const` \
+                         bounds",
+                    );
+                }
+                ImplRejection::Unsatisfied(unsatisfied) => {
+                    let mut spans: MultiSpan = impl_span.into();
+                    let mut predicates = vec![];
+                    for nested in unsatisfied {
+                        let predicate = format!("`{}`", nested.predicate);
+                        if predicates.contains(&predicate) {
+                            continue;
+                        }
+                        predicates.push(predicate);
+                        if let ObligationCauseCode::ImplDerivedObligation(cause) =
+                            nested.cause.code()
+                        {
+                            spans.push_span_label(cause.span, "unsatisfied bound introduced here");
+                        }
+                    }
+                    if predicates.is_empty() {
+                        continue;
+                    }
+                    err.span_note(
+                        spans,
+                        &format!(
+                            "this impl doesn't apply because {} {} not satisfied",
+                            listify(&predicates),
+                            if predicates.len() == 1 { "is" } else { "are" },
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn generic_param_instantiations(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+        impl_def_id: DefId,
+    ) -> Option<Vec<(Ty<'tcx>, Ty<'tcx>)>> {
+        let tcx = self.tcx;
+        // Every impl would match a bare type parameter, which is not worth pointing out.
+        if trait_pred.skip_binder().self_ty().is_ty_param() || !trait_pred.has_non_region_param()
+        {
+            return None;
+        }
+        self.probe(|_| {
+            let placeholder_pred = self.replace_bound_vars_with_placeholders(trait_pred);
+            let mut params = FxIndexMap::default();
+            let trait_ref = placeholder_pred.trait_ref.fold_with(&mut ty::fold::BottomUpFolder {
+                tcx,
+                ty_op: |ty| match ty.kind() {
+                    ty::Param(_) => *params.entry(ty).or_insert_with(|| {
+                        self.next_ty_var(TypeVariableOrigin {
+                            kind: TypeVariableOriginKind::MiscVariable,
+                            span: DUMMY_SP,
+                        })
+                    }),
+                    _ => ty,
+                },
+                lt_op: |lt| lt,
+                ct_op: |ct| ct,
+            });
+            let impl_substs = self.fresh_substs_for_item(DUMMY_SP, impl_def_id);
+            let impl_trait_ref =
+                tcx.bound_impl_trait_ref(impl_def_id).unwrap().subst(tcx, impl_substs);
+            let ocx = ObligationCtxt::new_in_snapshot(self);
+            ocx.eq(&ObligationCause::dummy(), obligation.param_env, trait_ref, impl_trait_ref)
+                .ok()?;
+            let instantiations: Vec<_> = params
+                .into_iter()
+                .filter_map(|(param, var)| {
+                    let ty = self.resolve_vars_if_possible(var);
+                    (!ty.is_ty_var()).then_some((param, ty))
+                })
+                .collect();
+            (!instantiations.is_empty()).then_some(instantiations)
+        })
+    }
+
     fn mk_trait_obligation_with_new_self_ty(
         &self,
         param_env: ty::ParamEnv<'tcx>,
@@ -2099,6 +2855,16 @@ fn maybe_report_ambiguity(
                 // a more general error.
                 let subst = data.trait_ref.substs.iter().find(|s| s.has_non_region_infer());
 
+                // Very long self types are written out to a file and only shown in a shortened
+                // form in the diagnostic itself.
+                let (predicate_str, long_ty_file) = match self.tcx.short_ty_string(data.self_ty()) {
+                    (self_ty, Some(file)) => (
+                        format!("{}: {}", self_ty, data.print_modifiers_and_trait_path()),
+                        Some(file),
+                    ),
+                    (_, None) => (predicate.to_string(), None),
+                };
+
                 let mut err = if let Some(subst) = subst {
                     self.emit_inference_failure_err(body_id, span, subst, ErrorCode::E0283, true)
                 } else {
@@ -2107,7 +2873,7 @@ fn maybe_report_ambiguity(
                         span,
                         E0283,
                         "type annotations needed: cannot satisfy `{}`",
-                        predicate,
+                        predicate_str,
                     )
                 };
 
@@ -2121,16 +2887,43 @@ fn maybe_report_ambiguity(
                         let impls = ambiguity::recompute_applicable_impls(self.infcx, &obligation);
                         let has_non_region_infer =
                             trait_ref.skip_binder().substs.types().any(|t| !t.is_ty_infer());
+                        // Ambiguities which don't involve several `impl`s are often caused by
+                        // earlier errors, so they are not reported after those.
+                        let impl_count = impls
+                            .iter()
+                            .filter(|a| matches!(a, ambiguity::Ambiguity::DefId(_)))
+                            .count();
                         // It doesn't make sense to talk about applicable impls if there are more
                         // than a handful of them.
-                        if impls.len() > 1 && impls.len() < 5 && has_non_region_infer {
-                            self.annotate_source_of_ambiguity(&mut err, &impls, predicate);
+                        if impls.len() > 1
+                            && impls.len() < 5
+                            && has_non_region_infer
+                            && (impl_count > 1 || self.tainted_by_errors().is_none())
+                        {
+                            // Selection gives up on the `where` clauses left after winnowing,
+                            // which already dropped every `impl` in their favor.
+                            let winnowed = selcx
+                                .winnowed_candidates_for_ambiguity(&obligation)
+                                .unwrap_or_default();
+                            let where_clauses_shadow_impls = winnowed.len() > 1
+                                && winnowed.iter().all(|candidate| {
+                                    matches!(candidate, SelectionCandidate::ParamCandidate(_))
+                                })
+                                && impls
+                                    .iter()
+                                    .any(|a| matches!(a, ambiguity::Ambiguity::DefId(_)));
+                            self.annotate_source_of_ambiguity(
+                                &mut err,
+                                &impls,
+                                predicate,
+                                where_clauses_shadow_impls,
+                            );
                         } else {
                             if self.tainted_by_errors().is_some() {
                                 err.cancel();
                                 return;
                             }
-                            err.note(&format!("cannot satisfy `{}`", predicate));
+                            err.note(&format!("cannot satisfy `{}`", predicate_str));
                         }
                     }
                     _ => {
@@ -2138,9 +2931,15 @@ fn maybe_report_ambiguity(
                             err.cancel();
                             return;
                         }
-                        err.note(&format!("cannot satisfy `{}`", predicate));
+                        err.note(&format!("cannot satisfy `{}`", predicate_str));
                     }
                 }
+                if let Some(file) = &long_ty_file {
+                    err.note(&format!(
+                        "the full type name has been written to '{}'",
+                        file.display(),
+                    ));
+                }
 
                 if let ObligationCauseCode::ItemObligation(def_id) | ObligationCauseCode::ExprItemObligation(def_id, ..) = *obligation.cause.code() {
                     self.suggest_fully_qualified_path(&mut err, def_id, span, trait_ref.def_id());
@@ -2410,21 +3209,31 @@ fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
     fn annotate_source_of_ambiguity(
         &self,
         err: &mut Diagnostic,
-        impls: &[DefId],
+        impls: &[ambiguity::Ambiguity],
         predicate: ty::Predicate<'tcx>,
+        where_clauses_shadow_impls: bool,
     ) {
         let mut spans = vec![];
         let mut crates = vec![];
         let mut post = vec![];
-        for def_id in impls {
-            match self.tcx.span_of_impl(*def_id) {
-                Ok(span) => spans.push(span),
-                Err(name) => {
-                    crates.push(name);
-                    if let Some(header) = to_pretty_impl_header(self.tcx, *def_id) {
-                        post.push(header);
+        let mut has_param_env = false;
+        for ambiguity in impls {
+            match ambiguity {
+                ambiguity::Ambiguity::DefId(impl_def_id) => {
+                    match self.tcx.span_of_impl(*impl_def_id) {
+                        Ok(span) => spans.push(span),
+                        Err(name) => {
+                            crates.push(name);
+                            if let Some(header) = to_pretty_impl_header(self.tcx, *impl_def_id) {
+                                post.push(header);
+                            }
+                        }
                     }
                 }
+                ambiguity::Ambiguity::ParamEnv(span) => {
+                    has_param_env = true;
+                    spans.push(*span);
+                }
             }
         }
         let mut crate_names: Vec<_> = crates.iter().map(|n| format!("`{}`", n)).collect();
@@ -2448,7 +3257,11 @@ fn annotate_source_of_ambiguity(
             return;
         }
 
-        let msg = format!("multiple `impl`s satisfying `{}` found", predicate);
+        let msg = format!(
+            "multiple `impl`s{} satisfying `{}` found",
+            if has_param_env { " or `where` clauses" } else { "" },
+            predicate
+        );
         let post = if post.len() > 1 || (post.len() == 1 && post[0].contains('\n')) {
             format!(":\n{}", post.iter().map(|p| format!("- {}", p)).collect::<Vec<_>>().join("\n"),)
         } else if post.len() == 1 {
@@ -2493,6 +3306,17 @@ fn annotate_source_of_ambiguity(
                 ));
             }
         }
+
+        if where_clauses_shadow_impls {
+            err.note(
+                "`where` clauses take precedence over `impl`s, so the `impl`s are not \
+                 considered and the ambiguity is between the `where` clauses",
+            );
+            err.help(
+                "use a fully qualified path to pick one of the `where` clauses, or remove \
+                 the ones which are already satisfied by an `impl`",
+            );
+        }
     }
 
     /// Returns `true` if the trait predicate may apply for *some* assignment
@@ -2562,6 +3386,11 @@ fn note_obligation_cause(&self, err: &mut Diagnostic, obligation: &PredicateObli
             );
             self.suggest_unsized_bound_if_applicable(err, obligation);
         }
+        err.set_cause_chain(obligation_cause_node(
+            Some(obligation.predicate.to_string()),
+            Some(obligation.cause.span),
+            obligation.cause.code(),
+        ));
     }
 
     #[instrument(level = "debug", skip_all)]
@@ -2695,6 +3524,170 @@ fn is_recursive_obligation(
         }
         false
     }
+
+    fn build_overflow_error<T>(
+        &self,
+        obligation: &Obligation<'tcx, T>,
+        suggest_increasing_limit: bool,
+    ) -> DiagnosticBuilder<'tcx, ErrorGuaranteed>
+    where
+        T: fmt::Display
+            + TypeFoldable<'tcx>
+            + Print<'tcx, FmtPrinter<'tcx, 'tcx>, Output = FmtPrinter<'tcx, 'tcx>>,
+        <T as Print<'tcx, FmtPrinter<'tcx, 'tcx>>>::Error: std::fmt::Debug,
+    {
+        let predicate = self.resolve_vars_if_possible(obligation.predicate.clone());
+        let mut pred_str = predicate.to_string();
+        if pred_str.len() > 50 && !self.tcx.sess.verbose() {
+            // We don't need to save the type to a file, we will be talking about this type already
+            // in a separate note when we explain the obligation, so it will be available that way.
+            pred_str = predicate
+                .print(FmtPrinter::new_with_limit(
+                    self.tcx,
+                    Namespace::TypeNS,
+                    rustc_session::Limit(6),
+                ))
+                .unwrap()
+                .into_buffer();
+        }
+        let mut err = struct_span_err!(
+            self.tcx.sess,
+            obligation.cause.span,
+            E0275,
+            "overflow evaluating the requirement `{}`",
+            pred_str,
+        );
+
+        if suggest_increasing_limit {
+            self.suggest_new_overflow_limit(&mut err);
+        }
+
+        self.note_obligation_cause_code(
+            &mut err,
+            &obligation.predicate,
+            obligation.param_env,
+            obligation.cause.code(),
+            &mut vec![],
+            &mut Default::default(),
+        );
+
+        err
+    }
+
+    fn note_overflow_obligation_stack(
+        &self,
+        err: &mut Diagnostic,
+        stack: &[TraitObligation<'tcx>],
+    ) {
+        let tcx = self.tcx;
+        let verbose = tcx.sess.verbose();
+        let Some(root) = stack.last() else {
+            return;
+        };
+        // Nested obligations registered with the fulfillment context are selected on a fresh
+        // stack, so continue with the parents recorded in the cause of the outermost one.
+        let mut levels: Vec<_> = stack
+            .iter()
+            .map(|obligation| (obligation.predicate, obligation.cause.code()))
+            .collect();
+        let mut code = root.cause.code();
+        while let Some((parent_code, parent_trait_pred)) = code.parent() {
+            if let Some(parent_trait_pred) = parent_trait_pred {
+                levels.push((parent_trait_pred, parent_code));
+            }
+            code = parent_code;
+        }
+        let preds: Vec<_> =
+            levels.iter().map(|&(pred, _)| self.resolve_vars_if_possible(pred)).collect();
+
+        let print = |pred: ty::PolyTraitPredicate<'tcx>| {
+            if verbose {
+                return pred.to_string();
+            }
+            pred.print(FmtPrinter::new_with_limit(tcx, Namespace::TypeNS, Limit(6)))
+                .unwrap()
+                .into_buffer()
+        };
+
+        // The types involved usually grow with every level of recursion, so only compare
+        // the trait and the outermost type constructor of the self type.
+        let keys: Vec<_> = preds
+            .iter()
+            .map(|pred| {
+                let self_ty = pred.skip_binder().self_ty();
+                (pred.def_id(), simplify_type(tcx, self_ty, TreatParams::AsPlaceholder))
+            })
+            .collect();
+        let cycle_len = (1..=keys.len() / 2).find(|&len| keys[..len] == keys[len..2 * len]);
+
+        if preds.len() > 1 && (cycle_len.is_none() || verbose) {
+            // Print the chain from the root obligation down to the one that overflowed, only
+            // keeping a few obligations at either end unless `-Zverbose` is set.
+            let elide = !verbose && preds.len() > 2 * OVERFLOW_CHAIN_EDGE;
+            let mut msg = String::from("the overflow happened in this chain of obligations:");
+            for (i, &pred) in preds.iter().rev().enumerate() {
+                if elide && i == OVERFLOW_CHAIN_EDGE {
+                    let elided = preds.len() - 2 * OVERFLOW_CHAIN_EDGE;
+                    msg.push_str(&format!(
+                        "\n\u{2026} {} obligation{} elided \u{2026}",
+                        elided,
+                        pluralize!(elided),
+                    ));
+                }
+                if elide && (OVERFLOW_CHAIN_EDGE..preds.len() - OVERFLOW_CHAIN_EDGE).contains(&i) {
+                    continue;
+                }
+                let prefix = if i == 0 { "" } else { "requires " };
+                msg.push_str(&format!("\n{}`{}`", prefix, print(pred)));
+            }
+            err.note(&msg);
+        }
+
+        let Some(cycle_len) = cycle_len else {
+            return;
+        };
+        let repetitions =
+            keys.chunks_exact(cycle_len).take_while(|levels| *levels == &keys[..cycle_len]).count();
+
+        let mut msg = format!(
+            "the overflow is caused by a cycle of obligations repeating {} times:\n`{}`",
+            repetitions,
+            print(preds[cycle_len]),
+        );
+        for &pred in preds[..cycle_len].iter().rev() {
+            msg.push_str(&format!("\nrequires `{}`", print(pred)));
+        }
+        let elided = preds.len() - cycle_len - 1;
+        if elided > 0 && !verbose {
+            msg.push_str(&format!(
+                "\n(required by `{}` through {} elided level{})",
+                print(preds[preds.len() - 1]),
+                elided,
+                pluralize!(elided),
+            ));
+        }
+        err.note(&msg);
+
+        // Nested obligations remember the impl that required them, point at the impls
+        // that keep the cycle going.
+        let impl_def_ids: FxIndexSet<DefId> = levels[..cycle_len]
+            .iter()
+            .filter_map(|&(_, code)| match code {
+                ObligationCauseCode::ImplDerivedObligation(data) => Some(data.impl_def_id),
+                _ => None,
+            })
+            .collect();
+        if !impl_def_ids.is_empty() {
+            let spans: Vec<_> = impl_def_ids.iter().map(|&def_id| tcx.def_span(def_id)).collect();
+            err.span_note(
+                spans,
+                &format!(
+                    "the cycle goes through {}",
+                    if impl_def_ids.len() == 1 { "this impl" } else { "these impls" },
+                ),
+            );
+        }
+    }
 }
 
 /// Look for type `param` in an ADT being used only through a reference to confirm that suggesting
@@ -2740,6 +3733,88 @@ fn visit_ty(&mut self, ty: &hir::Ty<'_>) {
     }
 }
 
+/// Builds the structured cause chain emitted with JSON diagnostics, following
+/// the same parent links as `note_obligation_cause_code`.
+fn obligation_cause_node<'tcx>(
+    predicate: Option<String>,
+    span: Option<Span>,
+    code: &ObligationCauseCode<'tcx>,
+) -> ObligationCauseNode {
+    let kind = obligation_cause_kind(code).to_string();
+    let span = span.or_else(|| match code {
+        ObligationCauseCode::BindingObligation(_, span)
+        | ObligationCauseCode::ExprBindingObligation(_, span, ..) => Some(*span),
+        ObligationCauseCode::ImplDerivedObligation(cause) => Some(cause.span),
+        _ => None,
+    });
+    let required_by = code.parent().map(|(parent_code, parent_trait_pred)| {
+        Box::new(obligation_cause_node(
+            parent_trait_pred.map(|pred| pred.to_string()),
+            None,
+            parent_code,
+        ))
+    });
+    ObligationCauseNode { kind, predicate, span, required_by }
+}
+
+/// The name of the kind of `code` in JSON diagnostics. These names are part of the JSON output,
+/// so they must not change along with the variants of `ObligationCauseCode`.
+fn obligation_cause_kind(code: &ObligationCauseCode<'_>) -> &'static str {
+    use ObligationCauseCode::*;
+    match code {
+        MiscObligation => "misc",
+        SliceOrArrayElem => "slice_or_array_element",
+        TupleElem => "tuple_element",
+        ProjectionWf(..) => "projection_well_formed",
+        ItemObligation(..) | ExprItemObligation(..) => "item",
+        BindingObligation(..) | ExprBindingObligation(..) => "bound",
+        ReferenceOutlivesReferent(..) => "reference_outlives_referent",
+        ObjectTypeBound(..) => "object_type_bound",
+        ObjectCastObligation(..) => "object_cast",
+        Coercion { .. } => "coercion",
+        AssignmentLhsSized
+        | TupleInitializerSized
+        | StructInitializerSized
+        | VariableType(..)
+        | SizedArgumentType(..)
+        | SizedReturnType
+        | SizedYieldType
+        | SizedBoxType
+        | InlineAsmSized
+        | FieldSized { .. }
+        | ConstSized => "sized",
+        RepeatElementCopy { .. } => "repeat_element_copy",
+        SharedStatic => "shared_static",
+        BuiltinDerivedObligation(..) => "builtin_derived",
+        ImplDerivedObligation(..) => "impl_derived",
+        DerivedObligation(..) => "derived",
+        FunctionArgumentObligation { .. } => "function_argument",
+        CompareImplItemObligation { .. } | CheckAssociatedTypeBounds { .. } => "compare_impl_item",
+        ExprAssignable => "assignment",
+        MatchExpressionArm(..) => "match_arm",
+        Pattern { .. } => "pattern",
+        ConstPatternStructural => "const_pattern",
+        IfExpression(..) | IfExpressionWithNoElse => "if_expression",
+        MainFunctionType => "main_function",
+        StartFunctionType => "start_function",
+        IntrinsicType => "intrinsic",
+        LetElse => "let_else",
+        MethodReceiver | UnifyReceiver(..) => "method_receiver",
+        ReturnNoExpression | ReturnValue(..) | ReturnType | OpaqueReturnType(..) => "return",
+        BlockTailExpression(..) => "block_tail",
+        TrivialBound => "trivial_bound",
+        OpaqueType => "opaque_type",
+        AwaitableExpr(..) => "await",
+        ForLoopIterator => "for_loop",
+        QuestionMark => "question_mark",
+        WellFormed(..) => "well_formed",
+        MatchImpl(..) => "match_impl",
+        BinOp { .. } => "binary_operator",
+        AscribeUserTypeProvePredicate(..) => "type_ascription",
+        RustCall => "rust_call",
+    }
+}
+
 /// Summarizes information
 #[derive(Clone)]
 pub enum ArgKind {
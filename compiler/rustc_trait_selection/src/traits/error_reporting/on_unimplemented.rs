@@ -1,21 +1,26 @@
 use super::{ObligationCauseCode, PredicateObligation};
 use crate::infer::error_reporting::TypeErrCtxt;
-use rustc_ast::{MetaItem, NestedMetaItem};
+use rustc_ast::{AttrKind, Attribute, MetaItem, NestedMetaItem};
 use rustc_attr as attr;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_errors::{struct_span_err, ErrorGuaranteed};
 use rustc_hir as hir;
+use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
-use rustc_middle::ty::SubstsRef;
+use rustc_lint_defs::builtin::UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES;
 use rustc_middle::ty::{self, GenericParamDefKind, TyCtxt};
+use rustc_middle::ty::{InternalSubsts, SubstsRef};
 use rustc_parse_format::{ParseMode, Parser, Piece, Position};
 use rustc_span::symbol::{kw, sym, Symbol};
 use rustc_span::{Span, DUMMY_SP};
 use std::iter;
 
 use crate::errors::{
-    EmptyOnClauseInOnUnimplemented, InvalidOnClauseInOnUnimplemented, NoValueInOnUnimplemented,
+    EmptyOnClauseInOnUnimplemented, InvalidImplementsConditionInOnUnimplemented,
+    InvalidOnClauseInOnUnimplemented, NoValueInOnUnimplemented,
 };
+use crate::traits::query::evaluate_obligation::InferCtxtExt as _;
+use crate::traits::{Obligation, ObligationCause};
 
 use super::InferCtxtPrivExt;
 
@@ -35,6 +40,16 @@ fn on_unimplemented_note(
         trait_ref: ty::PolyTraitRef<'tcx>,
         obligation: &PredicateObligation<'tcx>,
     ) -> OnUnimplementedNote;
+
+    /*private*/
+    fn implements_condition_holds(
+        &self,
+        condition: &str,
+        def_id: DefId,
+        trait_ref: ty::TraitRef<'tcx>,
+        substs: SubstsRef<'tcx>,
+        obligation: &PredicateObligation<'tcx>,
+    ) -> bool;
 }
 
 impl<'tcx> TypeErrCtxtExt<'tcx> for TypeErrCtxt<'_, 'tcx> {
@@ -278,11 +293,70 @@ fn on_unimplemented_note(
         });
 
         if let Ok(Some(command)) = OnUnimplementedDirective::of_item(self.tcx, def_id) {
+            // Whether a generic argument implements some trait can only be known by probing
+            // selection, so flag the `implements` conditions that hold ahead of evaluation.
+            for condition in command.implements_conditions() {
+                if self.implements_condition_holds(
+                    condition.as_str(),
+                    def_id,
+                    trait_ref,
+                    substs,
+                    obligation,
+                ) {
+                    flags.push((sym::implements, Some(condition.to_string())));
+                }
+            }
             command.evaluate(self.tcx, trait_ref, &flags)
         } else {
             OnUnimplementedNote::default()
         }
     }
+
+    /// Checks whether the generic argument named by an `implements = "Param: Trait"` condition
+    /// of the `#[rustc_on_unimplemented]` attribute on `def_id` implements that trait.
+    fn implements_condition_holds(
+        &self,
+        condition: &str,
+        def_id: DefId,
+        trait_ref: ty::TraitRef<'tcx>,
+        substs: SubstsRef<'tcx>,
+        obligation: &PredicateObligation<'tcx>,
+    ) -> bool {
+        let tcx = self.tcx;
+        let Some((param, name)) = split_implements_condition(condition) else { return false };
+        let Some(trait_def_id) = find_trait(tcx, name) else { return false };
+        let ty = if param == kw::SelfUpper.as_str() {
+            trait_ref.self_ty()
+        } else {
+            let Some(param) = tcx.generics_of(def_id).params.iter().find(|p| {
+                p.name.as_str() == param && matches!(p.kind, GenericParamDefKind::Type { .. })
+            }) else {
+                return false;
+            };
+            substs[param.index as usize].expect_ty()
+        };
+        let ty = self.resolve_vars_if_possible(ty);
+        if ty.is_ty_var() {
+            return false;
+        }
+
+        self.probe(|_| {
+            let trait_substs = InternalSubsts::for_item(tcx, trait_def_id, |param, _| {
+                if param.index == 0 {
+                    ty.into()
+                } else {
+                    self.var_for_def(obligation.cause.span, param)
+                }
+            });
+            let trait_ref = ty::Binder::dummy(ty::TraitRef::new(trait_def_id, trait_substs));
+            self.predicate_must_hold_modulo_regions(&Obligation::new(
+                tcx,
+                ObligationCause::dummy(),
+                obligation.param_env,
+                trait_ref.without_const(),
+            ))
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -340,6 +414,14 @@ fn parse(
                 if let Some(value) = cfg.value && let Err(guar) = parse_value(value) {
                     errored = Some(guar);
                 }
+                if cfg.name == sym::implements
+                    && !Self::is_valid_implements_condition(tcx, item_def_id, cfg.value)
+                {
+                    errored =
+                        Some(tcx.sess.emit_err(InvalidImplementsConditionInOnUnimplemented {
+                            span: cfg.span,
+                        }));
+                }
                 true
             });
             Some(cond.clone())
@@ -415,9 +497,58 @@ fn parse(
         }
     }
 
+    /// Checks that an `implements = "Param: Trait"` condition names `Self` or a type parameter
+    /// of `item_def_id` and the diagnostic item of a trait.
+    fn is_valid_implements_condition(
+        tcx: TyCtxt<'tcx>,
+        item_def_id: DefId,
+        value: Option<Symbol>,
+    ) -> bool {
+        let Some(value) = value else { return false };
+        let Some((param, name)) = split_implements_condition(value.as_str()) else {
+            return false;
+        };
+        let known_param = param == kw::SelfUpper.as_str()
+            || tcx.generics_of(item_def_id).params.iter().any(|p| {
+                p.name.as_str() == param && matches!(p.kind, GenericParamDefKind::Type { .. })
+            });
+        known_param && find_trait(tcx, name).is_some()
+    }
+
+    /// Returns the values of the `implements = "Param: Trait"` conditions of this directive and
+    /// its subcommands. These can't be decided from the flags alone, so they are probed when
+    /// reporting an error and passed to `evaluate` as flags if they hold.
+    pub fn implements_conditions(&self) -> Vec<Symbol> {
+        let mut conditions = vec![];
+        for command in self.subcommands.iter().chain(Some(self)) {
+            if let Some(condition) = &command.condition {
+                collect_implements_conditions(condition, &mut conditions);
+            }
+        }
+        conditions
+    }
+
     pub fn of_item(tcx: TyCtxt<'tcx>, item_def_id: DefId) -> Result<Option<Self>, ErrorGuaranteed> {
+        Self::of_item_inner(tcx, item_def_id, false)
+    }
+
+    /// Like `of_item`, but also lints against malformed `#[diagnostic::on_unimplemented]`
+    /// attributes. This is done once when checking the item itself, rather than every time
+    /// the directive is looked up while reporting an error.
+    pub fn check_item(
+        tcx: TyCtxt<'tcx>,
+        item_def_id: DefId,
+    ) -> Result<Option<Self>, ErrorGuaranteed> {
+        Self::of_item_inner(tcx, item_def_id, true)
+    }
+
+    fn of_item_inner(
+        tcx: TyCtxt<'tcx>,
+        item_def_id: DefId,
+        emit_lints: bool,
+    ) -> Result<Option<Self>, ErrorGuaranteed> {
         let Some(attr) = tcx.get_attr(item_def_id, sym::rustc_on_unimplemented) else {
-            return Ok(None);
+            return Ok(Self::of_diagnostic_attribute(tcx, item_def_id, emit_lints));
         };
 
         let result = if let Some(items) = attr.meta_item_list() {
@@ -446,6 +577,96 @@ pub fn of_item(tcx: TyCtxt<'tcx>, item_def_id: DefId) -> Result<Option<Self>, Er
         result
     }
 
+    /// Parses `#[diagnostic::on_unimplemented]`, the counterpart of `#[rustc_on_unimplemented]`
+    /// that is available outside of the standard library. It only supports the `message`,
+    /// `label` and `note` options, and malformed uses of it are linted against and ignored
+    /// rather than rejected.
+    fn of_diagnostic_attribute(
+        tcx: TyCtxt<'tcx>,
+        item_def_id: DefId,
+        emit_lints: bool,
+    ) -> Option<Self> {
+        let Some(attr) = tcx
+            .get_attrs_unchecked(item_def_id)
+            .iter()
+            .find(|attr| is_diagnostic_on_unimplemented(attr))
+        else {
+            return None;
+        };
+
+        let lint = |span: Span, msg: &str, help: Option<&str>| {
+            if emit_lints {
+                tcx.struct_span_lint_hir(
+                    UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES,
+                    tcx.hir().local_def_id_to_hir_id(item_def_id.expect_local()),
+                    span,
+                    msg,
+                    |lint| match help {
+                        Some(help) => lint.help(help),
+                        None => lint,
+                    },
+                );
+            }
+        };
+        let malformed = "malformed `on_unimplemented` attribute";
+        let options_help = Some("only `message`, `note` and `label` are allowed as options");
+
+        // Uses on other items are linted against when checking attributes.
+        if !tcx.is_trait(item_def_id) {
+            return None;
+        }
+        let Some(items) = attr.meta_item_list() else {
+            lint(attr.span, malformed, options_help);
+            return None;
+        };
+
+        let mut directive = OnUnimplementedDirective {
+            condition: None,
+            subcommands: vec![],
+            message: None,
+            label: None,
+            note: None,
+            parent_label: None,
+            append_const_msg: None,
+        };
+        for item in &items {
+            let option = if item.has_name(sym::message) {
+                Some(&mut directive.message)
+            } else if item.has_name(sym::label) {
+                Some(&mut directive.label)
+            } else if item.has_name(sym::note) {
+                Some(&mut directive.note)
+            } else {
+                None
+            };
+            match (option, item.value_str()) {
+                (Some(option), Some(value)) if option.is_none() => {
+                    let format_string = OnUnimplementedFormatString(value);
+                    let errors = format_string.parameter_errors(tcx, item_def_id, item_def_id);
+                    for error in &errors {
+                        let msg = match error {
+                            FormatParameterError::Unknown(s) => format!(
+                                "there is no parameter `{}` on trait `{}`",
+                                s,
+                                tcx.item_name(item_def_id)
+                            ),
+                            FormatParameterError::Positional => {
+                                "only named substitution parameters are allowed".to_string()
+                            }
+                        };
+                        lint(item.span(), &msg, None);
+                    }
+                    if errors.is_empty() {
+                        *option = Some(format_string);
+                    }
+                }
+                _ => lint(item.span(), malformed, options_help),
+            }
+        }
+        debug!("of_diagnostic_attribute({:?}) = {:?}", item_def_id, directive);
+        Some(directive)
+    }
+
     pub fn evaluate(
         &self,
         tcx: TyCtxt<'tcx>,
@@ -508,6 +729,14 @@ pub fn evaluate(
     }
 }
 
+/// A parameter of an `on_unimplemented` format string which can't be substituted.
+enum FormatParameterError {
+    /// `{name}` names neither a generic parameter nor one of the predefined parameters.
+    Unknown(Symbol),
+    /// `{}` or `{0}` is used instead of a named parameter.
+    Positional,
+}
+
 impl<'tcx> OnUnimplementedFormatString {
     fn try_parse(
         tcx: TyCtxt<'tcx>,
@@ -532,11 +761,50 @@ fn verify(
             tcx.trait_id_of_impl(item_def_id)
                 .expect("expected `on_unimplemented` to correspond to a trait")
         };
+        let trait_name = tcx.item_name(trait_def_id);
+        let mut result = Ok(());
+        for error in self.parameter_errors(tcx, trait_def_id, item_def_id) {
+            let reported = match error {
+                FormatParameterError::Unknown(s) => struct_span_err!(
+                    tcx.sess,
+                    span,
+                    E0230,
+                    "there is no parameter `{}` on {}",
+                    s,
+                    if trait_def_id == item_def_id {
+                        format!("trait `{}`", trait_name)
+                    } else {
+                        "impl".to_string()
+                    }
+                )
+                .emit(),
+                FormatParameterError::Positional => struct_span_err!(
+                    tcx.sess,
+                    span,
+                    E0231,
+                    "only named substitution parameters are allowed"
+                )
+                .emit(),
+            };
+            result = Err(reported);
+        }
+
+        result
+    }
+
+    /// Returns the parameters of this format string which can't be substituted when it is used
+    /// for the trait `trait_def_id` in the attribute on `item_def_id`.
+    fn parameter_errors(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        trait_def_id: DefId,
+        item_def_id: DefId,
+    ) -> Vec<FormatParameterError> {
         let trait_name = tcx.item_name(trait_def_id);
         let generics = tcx.generics_of(item_def_id);
         let s = self.0.as_str();
         let parser = Parser::new(s, None, None, false, ParseMode::Format);
-        let mut result = Ok(());
+        let mut errors = vec![];
         for token in parser {
             match token {
                 Piece::String(_) => (), // Normal string, no need to check it
@@ -558,41 +826,19 @@ fn verify(
                             // So is `{A}` if A is a type parameter
                             s => match generics.params.iter().find(|param| param.name == s) {
                                 Some(_) => (),
-                                None => {
-                                    let reported = struct_span_err!(
-                                        tcx.sess,
-                                        span,
-                                        E0230,
-                                        "there is no parameter `{}` on {}",
-                                        s,
-                                        if trait_def_id == item_def_id {
-                                            format!("trait `{}`", trait_name)
-                                        } else {
-                                            "impl".to_string()
-                                        }
-                                    )
-                                    .emit();
-                                    result = Err(reported);
-                                }
+                                None => errors.push(FormatParameterError::Unknown(s)),
                             },
                         }
                     }
                     // `{:1}` and `{}` are not to be used
                     Position::ArgumentIs(..) | Position::ArgumentImplicitlyIs(_) => {
-                        let reported = struct_span_err!(
-                            tcx.sess,
-                            span,
-                            E0231,
-                            "only named substitution parameters are allowed"
-                        )
-                        .emit();
-                        result = Err(reported);
+                        errors.push(FormatParameterError::Positional);
                     }
                 },
             }
         }
 
-        result
+        errors
     }
 
     pub fn format(
@@ -664,3 +910,38 @@ pub fn format(
             .collect()
     }
 }
+
+fn is_diagnostic_on_unimplemented(attr: &Attribute) -> bool {
+    match &attr.kind {
+        AttrKind::Normal(normal) => matches!(
+            &*normal.item.path.segments,
+            [namespace, name]
+                if namespace.ident.name == sym::diagnostic
+                    && name.ident.name == sym::on_unimplemented
+        ),
+        AttrKind::DocComment(..) => false,
+    }
+}
+
+fn collect_implements_conditions(condition: &MetaItem, conditions: &mut Vec<Symbol>) {
+    if let Some(items) = condition.meta_item_list() {
+        for item in items.iter().filter_map(|item| item.meta_item()) {
+            collect_implements_conditions(item, conditions);
+        }
+    } else if condition.has_name(sym::implements) && let Some(value) = condition.value_str() {
+        conditions.push(value);
+    }
+}
+
+/// Splits the value of an `implements = "Param: Trait"` condition into its parts.
+fn split_implements_condition(value: &str) -> Option<(&str, &str)> {
+    let (param, name) = value.split_once(':')?;
+    let (param, name) = (param.trim(), name.trim());
+    (!param.is_empty() && !name.is_empty() && !name.starts_with(':')).then_some((param, name))
+}
+
+/// Looks up the trait of an `implements` condition, which is named by its diagnostic item.
+fn find_trait(tcx: TyCtxt<'_>, name: &str) -> Option<DefId> {
+    let def_id = tcx.get_diagnostic_item(Symbol::intern(name))?;
+    (tcx.def_kind(def_id) == DefKind::Trait).then_some(def_id)
+}
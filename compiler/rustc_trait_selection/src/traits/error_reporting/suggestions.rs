@@ -9,6 +9,7 @@
 
 use hir::def::CtorOf;
 use hir::HirId;
+use rustc_ast::util::parser::{PREC_POSTFIX, PREC_PREFIX};
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::stack::ensure_sufficient_stack;
 use rustc_errors::{
@@ -178,6 +179,14 @@ fn suggest_restricting_param_bound(
         body_id: hir::HirId,
     );
 
+    fn suggest_adjusting_near_miss_param_bound(
+        &self,
+        err: &mut Diagnostic,
+        param_env: ty::ParamEnv<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+        body_id: hir::HirId,
+    );
+
     fn suggest_dereferences(
         &self,
         obligation: &PredicateObligation<'tcx>,
@@ -217,6 +226,13 @@ fn suggest_remove_reference(
         trait_pred: ty::PolyTraitPredicate<'tcx>,
     ) -> bool;
 
+    fn suggest_clone_or_as_ref(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut Diagnostic,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) -> bool;
+
     fn suggest_remove_await(&self, obligation: &PredicateObligation<'tcx>, err: &mut Diagnostic);
 
     fn suggest_change_mut(
@@ -290,6 +306,7 @@ fn note_obligation_cause_for_async_await(
         typeck_results: Option<&ty::TypeckResults<'tcx>>,
         obligation: &PredicateObligation<'tcx>,
         next_code: Option<&ObligationCauseCode<'tcx>>,
+        binding: Option<Ident>,
     );
 
     fn note_obligation_cause_code<T>(
@@ -341,6 +358,83 @@ fn predicate_constraint(generics: &hir::Generics<'_>, pred: ty::Predicate<'_>) -
     )
 }
 
+/// Suggests bounding the associated type of a type parameter with the `T::Assoc: Bound`
+/// shorthand, or with `T: Trait<Assoc: Bound>` under `associated_type_bounds`. Returns `None`
+/// if the shorthand can't be used, because it would be ambiguous or can't name the generic
+/// arguments of the projection.
+fn projection_bound_suggestion<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    item_def_id: DefId,
+    hir_generics: &hir::Generics<'tcx>,
+    projection: &ty::ProjectionTy<'tcx>,
+    trait_pred: ty::PolyTraitPredicate<'tcx>,
+) -> Option<(Span, String)> {
+    let ty::Param(param) = *projection.self_ty().kind() else { return None };
+    if projection.substs.len() != 1 {
+        return None;
+    }
+    let trait_def_id = projection.trait_def_id(tcx);
+    let assoc_name = tcx.item_name(projection.item_def_id);
+
+    // Another bound on `T` with an associated type of the same name makes `T::Assoc` ambiguous.
+    let predicates = tcx.predicates_of(item_def_id).instantiate_identity(tcx);
+    let is_ambiguous =
+        predicates.predicates.iter().filter_map(|pred| pred.to_opt_poly_trait_pred()).any(
+            |bound| {
+                bound.def_id() != trait_def_id
+                    && bound.skip_binder().self_ty().kind() == &ty::Param(param)
+                    && tcx
+                        .associated_items(bound.def_id())
+                        .filter_by_name_unhygienic(assoc_name)
+                        .any(|item| item.kind == ty::AssocKind::Type)
+            },
+        );
+    if is_ambiguous {
+        return None;
+    }
+
+    let constraint = trait_pred.print_modifiers_and_trait_path().to_string();
+    if tcx.features().associated_type_bounds {
+        let param_def_id = tcx.generics_of(item_def_id).type_param(&param, tcx).def_id;
+        let trait_path = hir_generics
+            .predicates
+            .iter()
+            .filter_map(|pred| match pred {
+                hir::WherePredicate::BoundPredicate(pred) if pred.is_param_bound(param_def_id) => {
+                    Some(pred.bounds)
+                }
+                _ => None,
+            })
+            .flatten()
+            .find_map(|bound| match bound {
+                hir::GenericBound::Trait(poly, hir::TraitBoundModifier::None)
+                    if poly.trait_ref.trait_def_id() == Some(trait_def_id) =>
+                {
+                    Some(poly.trait_ref.path)
+                }
+                _ => None,
+            });
+        // Only `T: Trait` is handled, adding to existing generic arguments is left to the
+        // `where` clause.
+        if let Some(path) = trait_path
+            && path.segments.last().map_or(false, |segment| segment.args.is_none())
+        {
+            return Some((path.span.shrink_to_hi(), format!("<{}: {}>", assoc_name, constraint)));
+        }
+    }
+
+    Some((
+        hir_generics.tail_span_for_predicate_suggestion(),
+        format!(
+            "{} {}::{}: {}",
+            hir_generics.add_where_or_trailing_comma(),
+            param.name,
+            assoc_name,
+            constraint,
+        ),
+    ))
+}
+
 /// Type parameter needs more bounds. The trivial case is `T` `where T: Bound`, but
 /// it can also be an `impl Trait` param that needs to be decomposed to a type
 /// param for cleaner code.
@@ -351,7 +445,7 @@ fn suggest_restriction<'tcx>(
     msg: &str,
     err: &mut Diagnostic,
     fn_sig: Option<&hir::FnSig<'_>>,
-    projection: Option<&ty::ProjectionTy<'_>>,
+    projection: Option<&ty::ProjectionTy<'tcx>>,
     trait_pred: ty::PolyTraitPredicate<'tcx>,
     // When we are dealing with a trait, `super_traits` will be `Some`:
     // Given `trait T: A + B + C {}`
@@ -440,7 +534,19 @@ fn suggest_restriction<'tcx>(
                 .find(|p| !matches!(p.kind, hir::GenericParamKind::Type { synthetic: true, .. })),
             super_traits,
         ) {
-            (_, None) => predicate_constraint(hir_generics, trait_pred.to_predicate(tcx)),
+            (_, None) => projection
+                .and_then(|projection| {
+                    projection_bound_suggestion(
+                        tcx,
+                        item_id.to_def_id(),
+                        hir_generics,
+                        projection,
+                        trait_pred,
+                    )
+                })
+                .unwrap_or_else(|| {
+                    predicate_constraint(hir_generics, trait_pred.to_predicate(tcx))
+                }),
             (None, Some((ident, []))) => (
                 ident.span.shrink_to_hi(),
                 format!(": {}", trait_pred.print_modifiers_and_trait_path()),
@@ -465,6 +571,78 @@ fn suggest_restriction<'tcx>(
 }
 
 impl<'tcx> TypeErrCtxtExt<'tcx> for TypeErrCtxt<'_, 'tcx> {
+    /// If the where-clauses in scope have a bound for the same trait and type parameter
+    /// that only differs in its generic arguments (`T: From<u32>` when `T: From<u64>` is
+    /// required), suggest adjusting that bound instead of only adding a new one.
+    fn suggest_adjusting_near_miss_param_bound(
+        &self,
+        err: &mut Diagnostic,
+        param_env: ty::ParamEnv<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+        body_id: hir::HirId,
+    ) {
+        let self_ty = trait_pred.skip_binder().self_ty();
+        let ty::Param(param) = *self_ty.kind() else { return };
+        if !trait_pred.is_suggestable(self.tcx, false) {
+            return;
+        }
+
+        // Bounds that only differ in their lifetimes are better explained by
+        // region errors and higher-ranked bounds can't simply be swapped out.
+        let substs = self.tcx.erase_regions(trait_pred.skip_binder().trait_ref.substs);
+        let mut near_misses = param_env.caller_bounds().iter().filter_map(|pred| {
+            let bound = pred.to_opt_poly_trait_pred()?;
+            (bound.def_id() == trait_pred.def_id()
+                && bound.bound_vars().is_empty()
+                && bound.skip_binder().self_ty() == self_ty
+                && self.tcx.erase_regions(bound.skip_binder().trait_ref.substs) != substs)
+                .then_some(bound)
+        });
+        // With several bounds to pick from we can't tell which one was meant.
+        let (Some(_), None) = (near_misses.next(), near_misses.next()) else { return };
+
+        let generics = self.tcx.generics_of(body_id.owner.def_id);
+        let Some(param_def_id) = generics.type_param(&param, self.tcx).def_id.as_local() else {
+            return;
+        };
+        let Some(hir_generics) = self.tcx.hir().get_generics(self.tcx.local_parent(param_def_id))
+        else {
+            return;
+        };
+        let trait_refs = hir_generics
+            .predicates
+            .iter()
+            .filter_map(|pred| match pred {
+                hir::WherePredicate::BoundPredicate(pred)
+                    if pred.is_param_bound(param_def_id.to_def_id()) =>
+                {
+                    Some(pred.bounds)
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|bound| match bound {
+                hir::GenericBound::Trait(poly, hir::TraitBoundModifier::None)
+                    if poly.trait_ref.trait_def_id() == Some(trait_pred.def_id()) =>
+                {
+                    Some(&poly.trait_ref)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let [trait_ref] = &trait_refs[..] else { return };
+
+        err.span_suggestion_verbose(
+            trait_ref.path.span,
+            &format!(
+                "if the existing bound was meant to require `{}`, consider changing it",
+                trait_pred.skip_binder(),
+            ),
+            trait_pred.print_modifiers_and_trait_path().to_string(),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
     fn suggest_restricting_param_bound(
         &self,
         mut err: &mut Diagnostic,
@@ -551,7 +729,10 @@ fn suggest_restricting_param_bound(
                 hir::Node::Item(hir::Item {
                     kind:
                         hir::ItemKind::Trait(_, _, generics, ..)
-                        | hir::ItemKind::Impl(hir::Impl { generics, .. }),
+                        | hir::ItemKind::Impl(hir::Impl { generics, .. })
+                        | hir::ItemKind::Struct(_, generics)
+                        | hir::ItemKind::Enum(_, generics)
+                        | hir::ItemKind::Union(_, generics),
                     ..
                 }) if projection.is_some() => {
                     // Missing restriction on associated type of type parameter (unmet projection).
@@ -1237,6 +1418,114 @@ fn suggest_remove_reference(
         suggested
     }
 
+    /// When an argument of type `&T` does not satisfy the trait binding but `T` does, suggest
+    /// dereferencing it (if `T: Copy`) or cloning it (if `T: Clone`). Likewise, when an
+    /// `Option<T>` or `Result<T, E>` argument does not satisfy it but `Option<&T>` or
+    /// `Result<&T, E>` does, suggest calling `.as_ref()` on it.
+    fn suggest_clone_or_as_ref(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        err: &mut Diagnostic,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) -> bool {
+        let ObligationCauseCode::FunctionArgumentObligation { arg_hir_id, .. } = obligation.cause.code()
+            else { return false; };
+        let Some(typeck_results) = &self.typeck_results
+            else { return false; };
+        let Some(hir::Node::Expr(expr)) = self.tcx.hir().find(*arg_hir_id)
+            else { return false; };
+        // Explicit borrows are handled by `suggest_dereferences` and
+        // `suggest_remove_reference`.
+        if let hir::ExprKind::AddrOf(..) = expr.kind {
+            return false;
+        }
+        let Some(arg_ty) = typeck_results.expr_ty_adjusted_opt(expr)
+            else { return false; };
+        let self_ty = self.tcx.erase_late_bound_regions(trait_pred.self_ty());
+        if self.can_eq(obligation.param_env, self_ty, arg_ty).is_err() {
+            return false;
+        }
+
+        let param_env = obligation.param_env;
+        let holds_for = |new_self_ty: Ty<'tcx>| {
+            // Remapping bound vars here
+            let trait_pred_and_ty = trait_pred.map_bound(|trait_pred| (trait_pred, new_self_ty));
+            let obligation =
+                self.mk_trait_obligation_with_new_self_ty(param_env, trait_pred_and_ty);
+            self.predicate_must_hold_modulo_regions(&obligation)
+        };
+        let implements = |trait_def_id: Option<DefId>, ty: Ty<'tcx>| {
+            trait_def_id.map_or(false, |trait_def_id| {
+                self.type_implements_trait(trait_def_id, [ty], param_env)
+                    .must_apply_modulo_regions()
+            })
+        };
+
+        let postfix_sugg = |method: &str| {
+            if expr.precedence().order() < PREC_POSTFIX {
+                vec![
+                    (expr.span.shrink_to_lo(), "(".to_string()),
+                    (expr.span.shrink_to_hi(), format!(").{method}()")),
+                ]
+            } else {
+                vec![(expr.span.shrink_to_hi(), format!(".{method}()"))]
+            }
+        };
+
+        match *self_ty.kind() {
+            ty::Ref(_, inner_ty, hir::Mutability::Not) if holds_for(inner_ty) => {
+                if implements(self.tcx.lang_items().copy_trait(), inner_ty) {
+                    let sugg = if expr.precedence().order() < PREC_PREFIX {
+                        vec![
+                            (expr.span.shrink_to_lo(), "*(".to_string()),
+                            (expr.span.shrink_to_hi(), ")".to_string()),
+                        ]
+                    } else {
+                        vec![(expr.span.shrink_to_lo(), "*".to_string())]
+                    };
+                    err.multipart_suggestion_verbose(
+                        "consider dereferencing here",
+                        sugg,
+                        Applicability::MachineApplicable,
+                    );
+                } else if implements(self.tcx.lang_items().clone_trait(), inner_ty) {
+                    err.multipart_suggestion_verbose(
+                        "consider cloning the value",
+                        postfix_sugg("clone"),
+                        Applicability::MaybeIncorrect,
+                    );
+                } else {
+                    return false;
+                }
+                true
+            }
+            ty::Adt(def, substs)
+                if (self.tcx.is_diagnostic_item(sym::Option, def.did())
+                    || self.tcx.is_diagnostic_item(sym::Result, def.did()))
+                    && !substs.type_at(0).is_ref() =>
+            {
+                let inner_ref_ty =
+                    self.tcx.mk_imm_ref(self.tcx.lifetimes.re_static, substs.type_at(0));
+                let as_ref_substs = self
+                    .tcx
+                    .mk_substs(std::iter::once(inner_ref_ty.into()).chain(substs.iter().skip(1)));
+                if !holds_for(self.tcx.mk_adt(def, as_ref_substs)) {
+                    return false;
+                }
+                err.multipart_suggestion_verbose(
+                    &format!(
+                        "consider borrowing the contents of the `{}` with `.as_ref()`",
+                        self.tcx.item_name(def.did())
+                    ),
+                    postfix_sugg("as_ref"),
+                    Applicability::MaybeIncorrect,
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn suggest_remove_await(&self, obligation: &PredicateObligation<'tcx>, err: &mut Diagnostic) {
         let span = obligation.cause.span;
 
@@ -2050,6 +2339,7 @@ fn maybe_note_obligation_cause_for_async_await(
         };
 
         let mut interior_or_upvar_span = None;
+        let mut binding = None;
 
         let from_awaited_ty = generator_data.get_from_await_ty(visitor, hir, ty_matches);
         debug!(?from_awaited_ty);
@@ -2064,6 +2354,22 @@ fn maybe_note_obligation_cause_for_async_await(
         {
             let ty::GeneratorInteriorTypeCause { span, scope_span, yield_span, expr, .. } = cause;
 
+            if let Some(body) = generator_body {
+                let mut finder = BindingFinder { span: *span, binding: None };
+                finder.visit_body(body);
+                // Dropping the binding earlier doesn't help if it is still used after the
+                // suspension point.
+                binding = finder
+                    .binding
+                    .filter(|&(_, hir_id)| {
+                        let mut finder =
+                            LocalUseFinder { hir_id, after: *yield_span, used_after: false };
+                        finder.visit_body(body);
+                        !finder.used_after
+                    })
+                    .map(|(ident, _)| ident);
+            }
+
             interior_or_upvar_span = Some(GeneratorInteriorOrUpvar::Interior(
                 *span,
                 Some((*scope_span, *yield_span, *expr, from_awaited_ty)),
@@ -2095,6 +2401,7 @@ fn maybe_note_obligation_cause_for_async_await(
                 typeck_results,
                 obligation,
                 next_code,
+                binding,
             );
             true
         } else {
@@ -2116,6 +2423,7 @@ fn note_obligation_cause_for_async_await(
         typeck_results: Option<&ty::TypeckResults<'tcx>>,
         obligation: &PredicateObligation<'tcx>,
         next_code: Option<&ObligationCauseCode<'tcx>>,
+        binding: Option<Ident>,
     ) {
         let source_map = self.tcx.sess.source_map();
 
@@ -2227,6 +2535,37 @@ fn note_obligation_cause_for_async_await(
                 if let Some((span, msg)) = scope_note {
                     err.span_note(span, &msg);
                 }
+
+                // For a local binding that is only dropped at the end of its scope, suggest
+                // getting rid of it before the suspension point. If both are on the same
+                // line, there is no statement in between to do that in.
+                if let Some(name) = binding
+                    && scope_span.is_some()
+                    && !interior_span.from_expansion()
+                    && source_map.is_multiline(interior_span.between(yield_span))
+                {
+                    if self.tcx.sess.opts.unstable_opts.drop_tracking
+                        && let Some(indent) = source_map.indentation_before(yield_span)
+                    {
+                        // Only values that are still live at the suspension point are part
+                        // of the generator interior, so dropping it explicitly is enough.
+                        let line = source_map.span_extend_to_prev_char(yield_span, '\n', true);
+                        let insert_at =
+                            line.with_lo(line.lo() + BytePos(indent.len() as u32)).shrink_to_lo();
+                        err.span_suggestion_verbose(
+                            insert_at,
+                            &format!("consider dropping `{}` before the {}", name, await_or_yield),
+                            format!("drop({});\n{}", name, indent),
+                            Applicability::MaybeIncorrect,
+                        );
+                    } else {
+                        err.help(&format!(
+                            "consider moving `{}` into a block that ends before the {}, \
+                             so that it is dropped earlier",
+                            name, await_or_yield,
+                        ));
+                    }
+                }
             }
         };
         match interior_or_upvar_span {
@@ -2760,6 +3099,19 @@ fn note_obligation_cause_code<T>(
                             spans.push(trait_ref.path.span);
                         }
                         spans.push(self_ty.span);
+                        let mut spans = MultiSpan::from_spans(spans);
+                        // Point at the where-clause of the impl that introduced the unmet
+                        // obligation, unless it was written by a macro (like `derive`) or is
+                        // already covered by the impl header.
+                        if !data.span.is_dummy()
+                            && !data.span.from_expansion()
+                            && !spans.primary_spans().iter().any(|sp| sp.overlaps(data.span))
+                        {
+                            spans.push_span_label(
+                                data.span,
+                                "unsatisfied trait bound introduced here",
+                            );
+                        }
                         err.span_note(spans, &msg)
                     }
                     _ => err.note(&msg),
@@ -3046,61 +3398,95 @@ fn suggest_derive(
         err: &mut Diagnostic,
         trait_pred: ty::PolyTraitPredicate<'tcx>,
     ) {
-        let Some(diagnostic_name) = self.tcx.get_diagnostic_name(trait_pred.def_id()) else {
+        let tcx = self.tcx;
+        let Some(diagnostic_name) = tcx.get_diagnostic_name(trait_pred.def_id()) else {
             return;
         };
-        let (adt, substs) = match trait_pred.skip_binder().self_ty().kind() {
+        let self_ty = trait_pred.skip_binder().self_ty();
+        let (adt, substs) = match self_ty.kind() {
             ty::Adt(adt, substs) if adt.did().is_local() => (adt, substs),
             _ => return,
         };
-        let can_derive = {
-            let is_derivable_trait = match diagnostic_name {
-                sym::Default => !adt.is_enum(),
-                sym::PartialEq | sym::PartialOrd => {
-                    let rhs_ty = trait_pred.skip_binder().trait_ref.substs.type_at(1);
-                    trait_pred.skip_binder().self_ty() == rhs_ty
-                }
-                sym::Eq | sym::Ord | sym::Clone | sym::Copy | sym::Hash | sym::Debug => true,
-                _ => false,
+        let is_derivable_trait = match diagnostic_name {
+            // Only `Clone` and `Copy` can be derived for unions.
+            _ if adt.is_union() => matches!(diagnostic_name, sym::Clone | sym::Copy),
+            sym::Default => !adt.is_enum(),
+            sym::PartialEq | sym::PartialOrd => {
+                let rhs_ty = trait_pred.skip_binder().trait_ref.substs.type_at(1);
+                self_ty == rhs_ty
+            }
+            sym::Eq | sym::Ord | sym::Clone | sym::Copy | sym::Hash | sym::Debug => true,
+            _ => false,
+        };
+        let span = tcx.def_span(adt.did());
+        if !is_derivable_trait || span.from_expansion() {
+            return;
+        }
+
+        // Whether `ty` implements the derivable trait `name`, comparing `ty` with itself for
+        // `PartialEq` and `PartialOrd`.
+        let implements = |name: Symbol, ty: Ty<'tcx>| {
+            let Some(def_id) = tcx.get_diagnostic_item(name) else {
+                return false;
             };
-            is_derivable_trait &&
-                // Ensure all fields impl the trait.
-                adt.all_fields().all(|field| {
-                    let field_ty = field.ty(self.tcx, substs);
-                    let trait_substs = match diagnostic_name {
-                        sym::PartialEq | sym::PartialOrd => {
-                            Some(field_ty)
-                        }
-                        _ => None,
-                    };
-                    let trait_pred = trait_pred.map_bound_ref(|tr| ty::TraitPredicate {
-                        trait_ref: self.tcx.mk_trait_ref(
-                            trait_pred.def_id(),
-                            [field_ty].into_iter().chain(trait_substs),
-                        ),
-                        ..*tr
-                    });
-                    let field_obl = Obligation::new(
-                        self.tcx,
-                        obligation.cause.clone(),
-                        obligation.param_env,
-                        trait_pred,
-                    );
-                    self.predicate_must_hold_modulo_regions(&field_obl)
-                })
+            let rhs_ty = match name {
+                sym::PartialEq | sym::PartialOrd => Some(ty),
+                _ => None,
+            };
+            let trait_pred = trait_pred.map_bound_ref(|tr| ty::TraitPredicate {
+                trait_ref: tcx.mk_trait_ref(def_id, [ty].into_iter().chain(rhs_ty)),
+                ..*tr
+            });
+            let obligation =
+                Obligation::new(tcx, obligation.cause.clone(), obligation.param_env, trait_pred);
+            self.predicate_must_hold_modulo_regions(&obligation)
         };
-        if can_derive {
-            err.span_suggestion_verbose(
-                self.tcx.def_span(adt.did()).shrink_to_lo(),
-                &format!(
-                    "consider annotating `{}` with `#[derive({})]`",
-                    trait_pred.skip_binder().self_ty(),
-                    diagnostic_name,
-                ),
-                format!("#[derive({})]\n", diagnostic_name),
-                Applicability::MaybeIncorrect,
-            );
+
+        // A derived impl requires the supertraits of the derived trait, so the ones `Self`
+        // doesn't implement yet have to be derived alongside it.
+        let mut derives = vec![diagnostic_name];
+        let mut pending = vec![diagnostic_name];
+        while let Some(name) = pending.pop() {
+            let supertraits: &[Symbol] = match name {
+                sym::Copy => &[sym::Clone],
+                sym::Eq | sym::PartialOrd => &[sym::PartialEq],
+                sym::Ord => &[sym::Eq, sym::PartialOrd],
+                _ => &[],
+            };
+            for &supertrait in supertraits {
+                if !derives.contains(&supertrait) && !implements(supertrait, self_ty) {
+                    derives.push(supertrait);
+                    pending.push(supertrait);
+                }
+            }
+        }
+
+        // `Copy` can't be derived for a type with a destructor.
+        if derives.contains(&sym::Copy) && adt.has_dtor(tcx) {
+            return;
         }
+
+        // The derives only compile if every field implements the derived traits, as well as
+        // every type parameter, since the derived impls are bounded on them.
+        let can_derive = derives.iter().all(|&name| {
+            adt.all_fields()
+                .map(|field| field.ty(tcx, substs))
+                .chain(substs.types())
+                .all(|ty| implements(name, ty))
+        });
+        if !can_derive {
+            return;
+        }
+
+        derives.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        let derives = derives.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(", ");
+        err.span_suggestion_verbose(
+            span.shrink_to_lo(),
+            &format!("consider annotating `{}` with `#[derive({})]`", self_ty, derives),
+            format!("#[derive({})]\n", derives),
+            // Separate errors about the same type each suggest their own derive.
+            Applicability::MaybeIncorrect,
+        );
     }
 
     fn suggest_dereferencing_index(
@@ -3183,6 +3569,42 @@ fn visit_body(&mut self, body: &'v hir::Body<'v>) {
     }
 }
 
+/// Finds the binding introduced by the pattern with the given span, unless the pattern
+/// also destructures its value.
+struct BindingFinder {
+    span: Span,
+    binding: Option<(Ident, HirId)>,
+}
+
+impl<'v> Visitor<'v> for BindingFinder {
+    fn visit_pat(&mut self, pat: &'v hir::Pat<'v>) {
+        if let hir::PatKind::Binding(_, hir_id, ident, None) = pat.kind && pat.span == self.span {
+            self.binding = Some((ident, hir_id));
+        }
+        hir::intravisit::walk_pat(self, pat)
+    }
+}
+
+/// Checks whether the local with the given `HirId` is used after the given span.
+struct LocalUseFinder {
+    hir_id: HirId,
+    after: Span,
+    used_after: bool,
+}
+
+impl<'v> Visitor<'v> for LocalUseFinder {
+    fn visit_expr(&mut self, ex: &'v hir::Expr<'v>) {
+        if let hir::ExprKind::Path(hir::QPath::Resolved(None, path)) = ex.kind
+            && let hir::def::Res::Local(hir_id) = path.res
+            && hir_id == self.hir_id
+            && ex.span.lo() >= self.after.hi()
+        {
+            self.used_after = true;
+        }
+        hir::intravisit::walk_expr(self, ex)
+    }
+}
+
 /// Collect all the awaited expressions within the input expression.
 #[derive(Default)]
 struct AwaitsVisitor {
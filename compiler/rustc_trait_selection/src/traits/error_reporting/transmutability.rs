@@ -0,0 +1,262 @@
+use rustc_infer::infer::InferCtxt;
+use rustc_infer::traits::PredicateObligation;
+use rustc_middle::ty::{self, AdtKind, Ty, TyCtxt, TypeVisitable};
+use rustc_target::abi::{FieldsShape, Size, Variants};
+use std::ops::Range;
+
+/// Explains why the transmutability obligation `trait_pred` does not hold, by locating the
+/// first incompatible byte in the layouts of the source and destination types and describing
+/// which field (or padding) it belongs to in each of them.
+///
+/// Returns `None` if the transmutation was rejected for a reason other than a byte-level
+/// incompatibility, or if the offending byte is not part of any field or padding in either
+/// type, in which case the primary error message already says everything there is to say.
+pub fn explain_rejection<'tcx>(
+    infcx: &InferCtxt<'tcx>,
+    obligation: &PredicateObligation<'tcx>,
+    trait_pred: ty::PolyTraitPredicate<'tcx>,
+) -> Option<String> {
+    let tcx = infcx.tcx;
+    let Transmutation { src, dst, src_and_dst, scope, assume } =
+        Transmutation::of(infcx, obligation, trait_pred)?;
+
+    let mut transmute_env = rustc_transmute::TransmuteTypeEnv::new(infcx);
+    let answer =
+        transmute_env.is_transmutable(obligation.cause.clone(), src_and_dst, scope, assume);
+    let offset = Size::from_bytes(answer.first_rejection()?.offset()?);
+
+    let src_desc = ByteDesc::of(tcx, src, offset)?;
+    let dst_desc = ByteDesc::of(tcx, dst, offset)?;
+    if !src_desc.is_structural && !dst_desc.is_structural {
+        return None;
+    }
+    Some(format!(
+        "at byte offset {}, `{}` has {}, but `{}` has {}",
+        offset.bytes(),
+        src,
+        src_desc.text,
+        dst,
+        dst_desc.text,
+    ))
+}
+
+/// Finds an option of `Assume` that is not set in the transmutability obligation `trait_pred`,
+/// but under which the transmutation would be accepted outright, and returns the name of the
+/// corresponding `Assume` constant.
+pub fn missing_assumption<'tcx>(
+    infcx: &InferCtxt<'tcx>,
+    obligation: &PredicateObligation<'tcx>,
+    trait_pred: ty::PolyTraitPredicate<'tcx>,
+) -> Option<&'static str> {
+    let Transmutation { src_and_dst, scope, assume, .. } =
+        Transmutation::of(infcx, obligation, trait_pred)?;
+
+    let candidates = [
+        ("ALIGNMENT", !assume.alignment, rustc_transmute::Assume { alignment: true, ..assume }),
+        ("LIFETIMES", !assume.lifetimes, rustc_transmute::Assume { lifetimes: true, ..assume }),
+        ("SAFETY", !assume.safety, rustc_transmute::Assume { safety: true, ..assume }),
+        ("VALIDITY", !assume.validity, rustc_transmute::Assume { validity: true, ..assume }),
+    ];
+    let mut transmute_env = rustc_transmute::TransmuteTypeEnv::new(infcx);
+    candidates.into_iter().filter(|&(_, unset, _)| unset).find_map(|(name, _, assume)| {
+        let answer =
+            transmute_env.is_transmutable(obligation.cause.clone(), src_and_dst, scope, assume);
+        matches!(answer, rustc_transmute::Answer::Yes).then_some(name)
+    })
+}
+
+/// The arguments of a transmutability obligation, as passed to the transmutability analysis.
+struct Transmutation<'tcx> {
+    src: Ty<'tcx>,
+    dst: Ty<'tcx>,
+    src_and_dst: ty::Binder<'tcx, rustc_transmute::Types<'tcx>>,
+    scope: Ty<'tcx>,
+    assume: rustc_transmute::Assume,
+}
+
+impl<'tcx> Transmutation<'tcx> {
+    /// Extracts the arguments of the transmutability obligation `trait_pred`. Returns `None`
+    /// if the source or destination type is not fully known, or the `Assume` argument cannot
+    /// be evaluated, in which case there is nothing to analyze.
+    fn of(
+        infcx: &InferCtxt<'tcx>,
+        obligation: &PredicateObligation<'tcx>,
+        trait_pred: ty::PolyTraitPredicate<'tcx>,
+    ) -> Option<Self> {
+        let trait_pred = infcx.resolve_vars_if_possible(trait_pred);
+        let substs = trait_pred.skip_binder().trait_ref.substs;
+        let (dst, src) = (substs.type_at(0), substs.type_at(1));
+        if (src, dst).needs_infer() || (src, dst).has_non_region_param() {
+            return None;
+        }
+
+        let src_and_dst = trait_pred.map_bound(|p| rustc_transmute::Types {
+            dst: p.trait_ref.substs.type_at(0),
+            src: p.trait_ref.substs.type_at(1),
+        });
+        let scope = substs.type_at(2);
+        let assume = rustc_transmute::Assume::from_const(
+            infcx.tcx,
+            obligation.param_env,
+            substs.const_at(3),
+        )?;
+        Some(Transmutation { src, dst, src_and_dst, scope, assume })
+    }
+}
+
+/// A description of the part of a type's layout that a byte belongs to.
+struct ByteDesc {
+    text: String,
+    /// Whether the description names a field, variant, or padding, rather than just the type.
+    is_structural: bool,
+}
+
+impl ByteDesc {
+    fn of<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, offset: Size) -> Option<Self> {
+        let size = layout_size(tcx, ty)?;
+        if offset >= size {
+            return Some(ByteDesc {
+                text: format!("no byte (it is {} bytes long)", size.bytes()),
+                is_structural: false,
+            });
+        }
+        Self::within(tcx, ty, offset, Size::ZERO, &ty.to_string(), None)
+    }
+
+    /// Describes the byte at `offset` of `ty`, which starts at `start` and is reached through
+    /// `path`. `kind` is `Some("field")` or `Some("element")` if `ty` is not the outermost type.
+    fn within<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        ty: Ty<'tcx>,
+        offset: Size,
+        start: Size,
+        path: &str,
+        kind: Option<&str>,
+    ) -> Option<Self> {
+        let layout = tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)).ok()?;
+        let size = layout.size;
+        let structural = |text: String| Some(ByteDesc { text, is_structural: true });
+
+        match *ty.kind() {
+            ty::Adt(adt_def, substs) if adt_def.adt_kind() == AdtKind::Struct => {
+                let variant = adt_def.non_enum_variant();
+                let mut next_field_start = size;
+                let mut prev_field_end = Size::ZERO;
+                for (idx, field) in variant.fields.iter().enumerate() {
+                    let field_ty = field.ty(tcx, substs);
+                    let field_start = start + layout.fields.offset(idx);
+                    let field_end = field_start + layout_size(tcx, field_ty)?;
+                    if (field_start..field_end).contains(&offset) {
+                        let path = format!("{}.{}", path, field.name);
+                        return Self::within(
+                            tcx,
+                            field_ty,
+                            offset,
+                            field_start,
+                            &path,
+                            Some("field"),
+                        );
+                    } else if field_end <= offset {
+                        prev_field_end = prev_field_end.max(field_end);
+                    } else {
+                        next_field_start = next_field_start.min(field_start);
+                    }
+                }
+                structural(format!(
+                    "padding ({})",
+                    bytes(prev_field_end.max(start)..next_field_start.min(start + size))
+                ))
+            }
+            ty::Adt(adt_def, substs) if adt_def.adt_kind() == AdtKind::Union => {
+                let mut covering = vec![];
+                let mut prev_field_end = start;
+                for field in adt_def.all_fields() {
+                    let field_ty = field.ty(tcx, substs);
+                    let field_end = start + layout_size(tcx, field_ty)?;
+                    if offset < field_end {
+                        covering.push((field, field_ty));
+                    } else {
+                        prev_field_end = prev_field_end.max(field_end);
+                    }
+                }
+                match &covering[..] {
+                    [] => structural(format!("padding ({})", bytes(prev_field_end..start + size))),
+                    &[(field, field_ty)] => {
+                        let path = format!("{}.{}", path, field.name);
+                        Self::within(tcx, field_ty, offset, start, &path, Some("field"))
+                    }
+                    covering => structural(format!(
+                        "part of the overlapping fields {} ({})",
+                        covering
+                            .iter()
+                            .map(|(field, _)| format!("`{}.{}`", path, field.name))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        bytes(start..start + size),
+                    )),
+                }
+            }
+            ty::Adt(adt_def, _)
+                if adt_def.is_enum()
+                    && adt_def.variants().iter().any(|variant| !variant.fields.is_empty()) =>
+            {
+                let (tag_start, tag_end) = match layout.variants {
+                    Variants::Multiple { tag, tag_field, .. } => {
+                        let tag_start = start + layout.fields.offset(tag_field);
+                        (tag_start, tag_start + tag.size(&tcx))
+                    }
+                    Variants::Single { .. } => (start, start),
+                };
+                if (tag_start..tag_end).contains(&offset) {
+                    structural(format!(
+                        "part of the discriminant of `{}` ({})",
+                        path,
+                        bytes(tag_start..tag_end),
+                    ))
+                } else {
+                    structural(format!(
+                        "part of a variant of `{}` ({})",
+                        path,
+                        bytes(tag_end..start + size),
+                    ))
+                }
+            }
+            ty::Array(elem_ty, _) => {
+                let FieldsShape::Array { stride, .. } = layout.fields else { return None };
+                let idx = (offset - start).bytes() / stride.bytes();
+                let elem_start = start + stride * idx;
+                let path = format!("{}[{}]", path, idx);
+                let kind = if kind.is_some() { Some("element") } else { None };
+                Self::within(tcx, elem_ty, offset, elem_start, &path, kind)
+            }
+            _ => Some(match kind {
+                Some(kind) => ByteDesc {
+                    text: format!(
+                        "part of the `{}` {} `{}` ({})",
+                        ty,
+                        kind,
+                        path,
+                        bytes(start..start + size),
+                    ),
+                    is_structural: true,
+                },
+                None => ByteDesc {
+                    text: format!("part of its value ({})", bytes(start..start + size)),
+                    is_structural: false,
+                },
+            }),
+        }
+    }
+}
+
+fn layout_size<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Size> {
+    tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)).ok().map(|layout| layout.size)
+}
+
+fn bytes(range: Range<Size>) -> String {
+    if range.end.bytes() == range.start.bytes() + 1 {
+        format!("byte {}", range.start.bytes())
+    } else {
+        format!("bytes {}..{}", range.start.bytes(), range.end.bytes())
+    }
+}
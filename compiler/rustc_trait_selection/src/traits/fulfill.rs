@@ -8,9 +8,13 @@
 use rustc_middle::mir::interpret::ErrorHandled;
 use rustc_middle::ty::abstract_const::NotConstEvaluatable;
 use rustc_middle::ty::error::{ExpectedFound, TypeError};
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::subst::SubstsRef;
 use rustc_middle::ty::{self, Binder, Const, Ty, TypeVisitable};
+use rustc_session::Limit;
+use std::io;
 use std::marker::PhantomData;
+use std::path::Path;
 
 use super::const_evaluatable;
 use super::project::{self, ProjectAndUnifyResult};
@@ -37,6 +41,14 @@ impl<'tcx> ForestObligation for PendingPredicateObligation<'tcx> {
     fn as_cache_key(&self) -> Self::CacheKey {
         self.obligation.param_env.and(self.obligation.predicate)
     }
+
+    fn dump_note(&self) -> Option<String> {
+        if self.stalled_on.is_empty() {
+            None
+        } else {
+            Some(format!("stalled on {:?}", self.stalled_on))
+        }
+    }
 }
 
 /// The fulfillment context is used to drive trait resolution. It
@@ -66,6 +78,20 @@ pub struct FulfillmentContext<'tcx> {
     // a snapshot (they don't *straddle* a snapshot, so there
     // is no trouble there).
     usable_in_snapshot: bool,
+
+    // Should `select_all_or_error` keep going after `select_where_possible`
+    // reported errors?
+    //
+    // Normally the remaining ambiguities are not reported once there are
+    // errors, as they are usually caused by them. Consumers that only care
+    // about getting as much inference done as possible, like IDEs, want every
+    // error and every ambiguity in one go instead.
+    error_tolerant: bool,
+
+    // A recursion limit below the crate's one to select obligations with, for
+    // obligations that should give up on runaway recursion sooner than
+    // hand-written code does.
+    recursion_limit: Option<Limit>,
 }
 
 #[derive(Clone, Debug)]
@@ -89,6 +115,8 @@ pub(super) fn new() -> FulfillmentContext<'tcx> {
             predicates: ObligationForest::new(),
             relationships: FxHashMap::default(),
             usable_in_snapshot: false,
+            error_tolerant: false,
+            recursion_limit: None,
         }
     }
 
@@ -97,9 +125,29 @@ pub(super) fn new_in_snapshot() -> FulfillmentContext<'tcx> {
             predicates: ObligationForest::new(),
             relationships: FxHashMap::default(),
             usable_in_snapshot: true,
+            error_tolerant: false,
+            recursion_limit: None,
         }
     }
 
+    /// Creates a fulfillment context whose `select_all_or_error` reports the
+    /// remaining ambiguities even if selection already produced errors.
+    pub(super) fn new_error_tolerant() -> FulfillmentContext<'tcx> {
+        FulfillmentContext {
+            predicates: ObligationForest::new(),
+            relationships: FxHashMap::default(),
+            usable_in_snapshot: false,
+            error_tolerant: true,
+            recursion_limit: None,
+        }
+    }
+
+    /// Creates a fulfillment context that selects its obligations with at most
+    /// `limit` as the recursion limit, see [`SelectionContext::with_recursion_limit`].
+    pub(super) fn with_recursion_limit(limit: Limit) -> FulfillmentContext<'tcx> {
+        FulfillmentContext { recursion_limit: Some(limit), ..FulfillmentContext::new() }
+    }
+
     /// Attempts to select obligations using `selcx`.
     fn select(&mut self, selcx: SelectionContext<'a, 'tcx>) -> Vec<FulfillmentError<'tcx>> {
         let span = debug_span!("select", obligation_forest_size = ?self.predicates.len());
@@ -182,18 +230,21 @@ fn register_predicate_obligation(
     }
 
     fn select_all_or_error(&mut self, infcx: &InferCtxt<'tcx>) -> Vec<FulfillmentError<'tcx>> {
-        {
-            let errors = self.select_where_possible(infcx);
-            if !errors.is_empty() {
-                return errors;
-            }
+        let mut errors = self.select_where_possible(infcx);
+        if !errors.is_empty() && !self.error_tolerant {
+            return errors;
         }
 
-        self.predicates.to_errors(CodeAmbiguity).into_iter().map(to_fulfillment_error).collect()
+        errors
+            .extend(self.predicates.to_errors(CodeAmbiguity).into_iter().map(to_fulfillment_error));
+        errors
     }
 
     fn select_where_possible(&mut self, infcx: &InferCtxt<'tcx>) -> Vec<FulfillmentError<'tcx>> {
-        let selcx = SelectionContext::new(infcx);
+        let selcx = match self.recursion_limit {
+            Some(limit) => SelectionContext::with_recursion_limit(infcx, limit),
+            None => SelectionContext::new(infcx),
+        };
         self.select(selcx)
     }
 
@@ -256,6 +307,48 @@ fn needs_process_obligation(&self, pending_obligation: &Self::Obligation) -> boo
         }
     }
 
+    fn has_early_pass(&self) -> bool {
+        self.selcx.tcx().sess.opts.unstable_opts.prioritize_cheap_obligations
+    }
+
+    /// Obligations that only relate types to each other, trait obligations for builtin
+    /// bounds and trait obligations that a where clause may prove are processed first.
+    /// They are cheap to select and their results constrain the inference variables
+    /// that impl-heavy obligations would otherwise have to be winnowed against.
+    fn process_early(&self, pending_obligation: &Self::Obligation) -> bool {
+        let obligation = &pending_obligation.obligation;
+        match obligation.predicate.kind().skip_binder() {
+            ty::PredicateKind::Trait(data) => {
+                let def_id = data.def_id();
+                let lang_items = self.selcx.tcx().lang_items();
+                lang_items.sized_trait() == Some(def_id)
+                    || lang_items.copy_trait() == Some(def_id)
+                    || obligation.param_env.caller_bounds().iter().any(|bound| {
+                        bound.to_opt_poly_trait_pred().map_or(false, |p| p.def_id() == def_id)
+                    })
+            }
+            ty::PredicateKind::Subtype(_)
+            | ty::PredicateKind::Coerce(_)
+            | ty::PredicateKind::RegionOutlives(_)
+            | ty::PredicateKind::TypeOutlives(_) => true,
+            _ => false,
+        }
+    }
+
+    fn dump_dir(&self) -> Option<&Path> {
+        self.selcx.tcx().sess.opts.unstable_opts.dump_obligation_forest.as_deref().map(Path::new)
+    }
+
+    fn dump_failed(&self, dir: &Path, error: io::Error) {
+        // The message does not mention the snapshot, so that a directory that cannot be
+        // written to is only reported once rather than after every round.
+        self.selcx.tcx().sess.warn(&format!(
+            "failed to dump the obligation forest to `{}`: {}",
+            dir.display(),
+            error
+        ));
+    }
+
     /// Processes a predicate obligation and returns either:
     /// - `Changed(v)` if the predicate is true, presuming that `v` are also true
     /// - `Unchanged` if we don't have enough info to be sure
@@ -472,9 +565,9 @@ fn process_obligation(
                         Ok(()) => ProcessResult::Changed(vec![]),
                         Err(NotConstEvaluatable::MentionsInfer) => {
                             pending_obligation.stalled_on.clear();
-                            pending_obligation.stalled_on.extend(
-                                uv.walk().filter_map(TyOrConstInferVar::maybe_from_generic_arg),
-                            );
+                            pending_obligation
+                                .stalled_on
+                                .extend(generic_arg_infer_vars(&self.selcx, uv.into()));
                             ProcessResult::Unchanged
                         }
                         Err(
@@ -505,10 +598,11 @@ fn process_obligation(
                     }
 
                     let stalled_on = &mut pending_obligation.stalled_on;
+                    let selcx = &self.selcx;
 
                     let mut evaluate = |c: Const<'tcx>| {
                         if let ty::ConstKind::Unevaluated(unevaluated) = c.kind() {
-                            match self.selcx.infcx().try_const_eval_resolve(
+                            match selcx.infcx().try_const_eval_resolve(
                                 obligation.param_env,
                                 unevaluated,
                                 c.ty(),
@@ -517,11 +611,7 @@ fn process_obligation(
                                 Ok(val) => Ok(val),
                                 Err(e) => match e {
                                     ErrorHandled::TooGeneric => {
-                                        stalled_on.extend(
-                                            unevaluated.substs.iter().filter_map(
-                                                TyOrConstInferVar::maybe_from_generic_arg,
-                                            ),
-                                        );
+                                        stalled_on.extend(generic_arg_infer_vars(selcx, c.into()));
                                         Err(ErrorHandled::TooGeneric)
                                     }
                                     _ => Err(e),
@@ -604,7 +694,11 @@ fn process_trait_obligation(
         stalled_on: &mut Vec<TyOrConstInferVar<'tcx>>,
     ) -> ProcessResult<PendingPredicateObligation<'tcx>, FulfillmentErrorCode<'tcx>> {
         let infcx = self.selcx.infcx();
-        if obligation.predicate.is_global() {
+        if let Some(filter) = &infcx.tcx.sess.opts.unstable_opts.dump_proof_tree {
+            with_no_trimmed_paths!(self.record_proof_tree(obligation, filter));
+        }
+
+        if obligation.predicate.is_global() && self.can_evaluate_global_obligations() {
             // no type variables present, can use evaluation for better caching.
             // FIXME: consider caching errors too.
             if infcx.predicate_must_hold_considering_regions(obligation) {
@@ -650,6 +744,31 @@ fn process_trait_obligation(
         }
     }
 
+    /// Whether global obligations may be proven by evaluating them instead of selecting
+    /// them. Evaluation goes through the `evaluate_obligation` query, which is shared by
+    /// the whole crate and so always checks against the crate's recursion limit, which
+    /// would bypass a lower limit given to [`FulfillmentContext::with_recursion_limit`].
+    fn can_evaluate_global_obligations(&self) -> bool {
+        self.selcx.recursion_limit().0 == self.selcx.tcx().recursion_limit().0
+    }
+
+    /// Records the proof tree of `obligation` for `-Zdump-proof-tree` if it is
+    /// fully inferred and its printed form contains `filter`.
+    fn record_proof_tree(&mut self, obligation: &PredicateObligation<'tcx>, filter: &str) {
+        let tcx = self.selcx.tcx();
+        let predicate = self.selcx.infcx().resolve_vars_if_possible(obligation.predicate);
+        if predicate.needs_infer() {
+            return;
+        }
+        let goal = predicate.to_string();
+        let proof_trees = &tcx.sess.trait_dumps.proof_trees;
+        if !goal.contains(filter) || proof_trees.lock().contains_key(&goal) {
+            return;
+        }
+        let tree = self.selcx.proof_tree(obligation);
+        proof_trees.lock().insert(goal, tree);
+    }
+
     fn process_projection_obligation(
         &mut self,
         obligation: &PredicateObligation<'tcx>,
@@ -658,7 +777,7 @@ fn process_projection_obligation(
     ) -> ProcessResult<PendingPredicateObligation<'tcx>, FulfillmentErrorCode<'tcx>> {
         let tcx = self.selcx.tcx();
 
-        if obligation.predicate.is_global() {
+        if obligation.predicate.is_global() && self.can_evaluate_global_obligations() {
             // no type variables present, can use evaluation for better caching.
             // FIXME: consider caching errors too.
             if self.selcx.infcx().predicate_must_hold_considering_regions(obligation) {
@@ -727,6 +846,20 @@ fn substs_infer_vars<'a, 'tcx>(
         .filter_map(TyOrConstInferVar::maybe_from_generic_arg)
 }
 
+/// Returns the inference variables that are still unresolved in `arg`, including ones
+/// nested inside of it. Variables that were already resolved are left out, as an
+/// obligation stalled on them would be processed again in every round.
+fn generic_arg_infer_vars<'a, 'tcx>(
+    selcx: &SelectionContext<'a, 'tcx>,
+    arg: ty::GenericArg<'tcx>,
+) -> impl Iterator<Item = TyOrConstInferVar<'tcx>> {
+    selcx
+        .infcx()
+        .resolve_vars_if_possible(arg)
+        .walk()
+        .filter_map(TyOrConstInferVar::maybe_from_generic_arg)
+}
+
 fn to_fulfillment_error<'tcx>(
     error: Error<PendingPredicateObligation<'tcx>, FulfillmentErrorCode<'tcx>>,
 ) -> FulfillmentError<'tcx> {
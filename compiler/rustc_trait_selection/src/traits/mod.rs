@@ -49,17 +49,23 @@
 pub use self::ObligationCauseCode::*;
 pub use self::SelectionError::*;
 
-pub use self::coherence::{add_placeholder_note, orphan_check, overlapping_impls};
-pub use self::coherence::{OrphanCheckErr, OverlapResult};
-pub use self::engine::{ObligationCtxt, TraitEngineExt};
+pub use self::coherence::{
+    add_placeholder_note, orphan_check, orphan_check_unstable_fundamental_ty, overlapping_impls,
+};
+pub use self::coherence::{Disjoint, OrphanCheckErr, OverlapResult};
+pub use self::engine::{ObligationCtxt, TraitEngineExt, TraitSolver};
 pub use self::fulfill::{FulfillmentContext, PendingPredicateObligation};
 pub use self::object_safety::astconv_object_safety_violations;
+pub use self::object_safety::gat_is_sized_confined;
 pub use self::object_safety::is_vtable_safe_method;
 pub use self::object_safety::MethodViolationCode;
 pub use self::object_safety::ObjectSafetyViolation;
+pub use self::object_safety::ObjectSafetyViolationItem;
 pub use self::project::{normalize, normalize_projection_type, normalize_to};
-pub use self::select::{EvaluationCache, SelectionCache, SelectionContext};
-pub use self::select::{EvaluationResult, IntercrateAmbiguityCause, OverflowError};
+pub use self::select::ConstituentAccess;
+pub use self::select::{dump_proof_trees, dump_selection_tree};
+pub use self::select::{CoherenceMode, EvaluationCache, SelectionCache, SelectionContext};
+pub use self::select::{EvaluationResult, ImplRejection, IntercrateAmbiguityCause, OverflowError};
 pub use self::specialize::specialization_graph::FutureCompatOverlapError;
 pub use self::specialize::specialization_graph::FutureCompatOverlapErrorKind;
 pub use self::specialize::{specialization_graph, translate_substs, OverlapError};
@@ -918,7 +924,9 @@ pub fn provide(providers: &mut ty::query::Providers) {
     structural_match::provide(providers);
     *providers = ty::query::Providers {
         specialization_graph_of: specialize::specialization_graph_provider,
+        specialization_dag: specialize::specialization_dag_provider,
         specializes: specialize::specializes,
+        reveals_defaults_in_const: specialize::reveals_defaults_in_const,
         codegen_select_candidate: codegen::codegen_select_candidate,
         own_existential_vtable_entries,
         vtable_entries,
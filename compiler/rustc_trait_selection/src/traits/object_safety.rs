@@ -13,10 +13,11 @@
 use crate::infer::TyCtxtInferExt;
 use crate::traits::query::evaluate_obligation::InferCtxtExt;
 use crate::traits::{self, Obligation, ObligationCause};
-use hir::def::DefKind;
+use hir::def::{DefKind, Res};
 use rustc_errors::{DelayDm, FatalError, MultiSpan};
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit::{self, Visitor};
 use rustc_middle::ty::abstract_const::{walk_abstract_const, AbstractConst};
 use rustc_middle::ty::{
     self, EarlyBinder, Ty, TyCtxt, TypeSuperVisitable, TypeVisitable, TypeVisitor,
@@ -31,7 +32,7 @@
 use std::iter;
 use std::ops::ControlFlow;
 
-pub use crate::traits::{MethodViolationCode, ObjectSafetyViolation};
+pub use crate::traits::{MethodViolationCode, ObjectSafetyViolation, ObjectSafetyViolationItem};
 
 /// Returns the object safety violations that affect
 /// astconv -- currently, `Self` in supertraits. This is needed
@@ -57,9 +58,21 @@ fn object_safety_violations(tcx: TyCtxt<'_>, trait_def_id: DefId) -> &'_ [Object
     debug_assert!(tcx.generics_of(trait_def_id).has_self);
     debug!("object_safety_violations: {:?}", trait_def_id);
 
+    tcx.arena.alloc_from_iter(
+        tcx.object_safety_violation_items(trait_def_id).iter().map(|item| item.violation.clone()),
+    )
+}
+
+fn object_safety_violation_items(
+    tcx: TyCtxt<'_>,
+    trait_def_id: DefId,
+) -> &'_ [ObjectSafetyViolationItem] {
+    debug_assert!(tcx.generics_of(trait_def_id).has_self);
+    debug!("object_safety_violation_items: {:?}", trait_def_id);
+
     tcx.arena.alloc_from_iter(
         traits::supertrait_def_ids(tcx, trait_def_id)
-            .flat_map(|def_id| object_safety_violations_for_trait(tcx, def_id)),
+            .flat_map(|def_id| object_safety_violations_for_trait(tcx, def_id, trait_def_id)),
     )
 }
 
@@ -81,27 +94,44 @@ pub fn is_vtable_safe_method(tcx: TyCtxt<'_>, trait_def_id: DefId, method: &ty::
     }
 }
 
+/// Returns the object safety violations of `trait_def_id`, which is `object_def_id` or one of
+/// its supertraits.
 fn object_safety_violations_for_trait(
     tcx: TyCtxt<'_>,
     trait_def_id: DefId,
-) -> Vec<ObjectSafetyViolation> {
+    object_def_id: DefId,
+) -> Vec<ObjectSafetyViolationItem> {
+    let violation_item = |item_def_id, violation| ObjectSafetyViolationItem {
+        trait_def_id,
+        item_def_id,
+        violation,
+        self_spans: vec![],
+    };
+
     // Check methods for violations.
     let mut violations: Vec<_> = tcx
         .associated_items(trait_def_id)
         .in_definition_order()
         .filter(|item| item.kind == ty::AssocKind::Fn)
         .filter_map(|item| {
-            object_safety_violation_for_method(tcx, trait_def_id, &item)
-                .map(|(code, span)| ObjectSafetyViolation::Method(item.name, code, span))
+            object_safety_violation_for_method(tcx, trait_def_id, &item).map(|(code, span)| {
+                ObjectSafetyViolationItem {
+                    self_spans: self_ty_spans_in_signature(tcx, item, &code),
+                    ..violation_item(
+                        Some(item.def_id),
+                        ObjectSafetyViolation::Method(item.name, code, span),
+                    )
+                }
+            })
         })
-        .filter(|violation| {
+        .filter(|item| {
             if let ObjectSafetyViolation::Method(
                 _,
                 MethodViolationCode::WhereClauseReferencesSelf,
                 span,
-            ) = violation
+            ) = &item.violation
             {
-                lint_object_unsafe_trait(tcx, *span, trait_def_id, &violation);
+                lint_object_unsafe_trait(tcx, *span, trait_def_id, &item.violation);
                 false
             } else {
                 true
@@ -113,24 +143,30 @@ fn object_safety_violations_for_trait(
     if trait_has_sized_self(tcx, trait_def_id) {
         // We don't want to include the requirement from `Sized` itself to be `Sized` in the list.
         let spans = get_sized_bounds(tcx, trait_def_id);
-        violations.push(ObjectSafetyViolation::SizedSelf(spans));
+        violations.push(violation_item(None, ObjectSafetyViolation::SizedSelf(spans)));
     }
     let spans = predicates_reference_self(tcx, trait_def_id, false);
     if !spans.is_empty() {
-        violations.push(ObjectSafetyViolation::SupertraitSelf(spans));
+        violations.push(violation_item(None, ObjectSafetyViolation::SupertraitSelf(spans)));
     }
     let spans = bounds_reference_self(tcx, trait_def_id);
     if !spans.is_empty() {
-        violations.push(ObjectSafetyViolation::SupertraitSelf(spans));
+        violations.push(violation_item(None, ObjectSafetyViolation::SupertraitSelf(spans)));
     }
 
     violations.extend(
         tcx.associated_items(trait_def_id)
             .in_definition_order()
             .filter(|item| item.kind == ty::AssocKind::Const)
+            // With `object_safe_assoc_consts`, consts with a default value require
+            // `Self: Sized`, so they can't be used through a trait object.
+            .filter(|item| !generics_require_sized_self(tcx, item.def_id))
             .map(|item| {
                 let ident = item.ident(tcx);
-                ObjectSafetyViolation::AssocConst(ident.name, ident.span)
+                violation_item(
+                    Some(item.def_id),
+                    ObjectSafetyViolation::AssocConst(ident.name, ident.span),
+                )
             }),
     );
 
@@ -140,9 +176,16 @@ fn object_safety_violations_for_trait(
                 .in_definition_order()
                 .filter(|item| item.kind == ty::AssocKind::Type)
                 .filter(|item| !tcx.generics_of(item.def_id).params.is_empty())
+                .filter(|item| {
+                    !(tcx.features().object_safe_sized_gats
+                        && gat_is_sized_confined(tcx, object_def_id, item.def_id))
+                })
                 .map(|item| {
                     let ident = item.ident(tcx);
-                    ObjectSafetyViolation::GAT(ident.name, ident.span)
+                    violation_item(
+                        Some(item.def_id),
+                        ObjectSafetyViolation::GAT(ident.name, ident.span),
+                    )
                 }),
         );
     }
@@ -155,6 +198,52 @@ fn object_safety_violations_for_trait(
     violations
 }
 
+/// Returns the spans of the uses of `Self` in the parameter or return type that makes `method`
+/// violate object safety with `code`. Uses of `Self` as the self type of an associated type
+/// projection, like `Self::Item`, are object safe and are not included.
+///
+/// Returns an empty list for other violations and for methods of foreign traits.
+fn self_ty_spans_in_signature(
+    tcx: TyCtxt<'_>,
+    method: &ty::AssocItem,
+    code: &MethodViolationCode,
+) -> Vec<Span> {
+    struct SelfTyVisitor(Vec<Span>);
+
+    impl<'v> Visitor<'v> for SelfTyVisitor {
+        fn visit_ty(&mut self, ty: &'v hir::Ty<'v>) {
+            match ty.kind {
+                hir::TyKind::Path(hir::QPath::Resolved(None, path))
+                    if matches!(path.res, Res::SelfTyParam { .. }) =>
+                {
+                    self.0.push(ty.span);
+                }
+                hir::TyKind::Path(
+                    hir::QPath::TypeRelative(..) | hir::QPath::Resolved(Some(_), _),
+                ) => {}
+                _ => intravisit::walk_ty(self, ty),
+            }
+        }
+    }
+
+    let Some(decl) = tcx.hir().get_if_local(method.def_id).and_then(|node| node.fn_decl()) else {
+        return vec![];
+    };
+    let ty = match (code, &decl.output) {
+        (MethodViolationCode::ReferencesSelfInput(Some(span)), _) => {
+            decl.inputs.iter().find(|ty| ty.span == *span)
+        }
+        (MethodViolationCode::ReferencesSelfOutput, hir::FnRetTy::Return(ty)) => Some(*ty),
+        _ => None,
+    };
+
+    let mut visitor = SelfTyVisitor(vec![]);
+    if let Some(ty) = ty {
+        visitor.visit_ty(ty);
+    }
+    visitor.0
+}
+
 /// Lint object-unsafe trait.
 fn lint_object_unsafe_trait(
     tcx: TyCtxt<'_>,
@@ -327,6 +416,66 @@ fn trait_has_sized_self(tcx: TyCtxt<'_>, trait_def_id: DefId) -> bool {
     generics_require_sized_self(tcx, trait_def_id)
 }
 
+/// Returns `true` if the generic associated type `gat_def_id` is only mentioned by methods that
+/// require `Self: Sized`, both in `object_def_id` and in all of its supertraits. Such a GAT can
+/// never be used through a `dyn` object of `object_def_id`, so it doesn't have to be specified in
+/// the object type and doesn't make the trait object unsafe.
+pub fn gat_is_sized_confined(tcx: TyCtxt<'_>, object_def_id: DefId, gat_def_id: DefId) -> bool {
+    struct MentionsAssocItem(DefId);
+
+    impl<'tcx> TypeVisitor<'tcx> for MentionsAssocItem {
+        type BreakTy = ();
+
+        fn visit_ty(&mut self, t: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
+            match t.kind() {
+                ty::Projection(data) if data.item_def_id == self.0 => ControlFlow::BREAK,
+                _ => t.super_visit_with(self),
+            }
+        }
+    }
+
+    if tcx.generics_of(gat_def_id).params.is_empty() {
+        return false;
+    }
+
+    let mentions_gat = |def_id: DefId| {
+        let mut visitor = MentionsAssocItem(gat_def_id);
+        tcx.predicates_of(def_id)
+            .predicates
+            .iter()
+            .any(|(pred, _)| pred.visit_with(&mut visitor).is_break())
+    };
+
+    let item_mentions_gat = |item: &ty::AssocItem| match item.kind {
+        ty::AssocKind::Fn => {
+            !generics_require_sized_self(tcx, item.def_id)
+                && (mentions_gat(item.def_id)
+                    || tcx
+                        .fn_sig(item.def_id)
+                        .visit_with(&mut MentionsAssocItem(gat_def_id))
+                        .is_break())
+        }
+        ty::AssocKind::Type => {
+            item.def_id != gat_def_id
+                && (mentions_gat(item.def_id)
+                    || tcx.explicit_item_bounds(item.def_id).iter().any(|(pred, _)| {
+                        pred.visit_with(&mut MentionsAssocItem(gat_def_id)).is_break()
+                    }))
+        }
+        ty::AssocKind::Const => {
+            tcx.type_of(item.def_id).visit_with(&mut MentionsAssocItem(gat_def_id)).is_break()
+        }
+    };
+
+    // A subtrait can mention the GAT of its supertrait as well, e.g. in the signature of one
+    // of its own methods, so every trait that a `dyn` object of `object_def_id` implements
+    // has to be checked.
+    traits::supertrait_def_ids(tcx, object_def_id).all(|trait_def_id| {
+        !mentions_gat(trait_def_id)
+            && !tcx.associated_items(trait_def_id).in_definition_order().any(&item_mentions_gat)
+    })
+}
+
 fn generics_require_sized_self(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
     let Some(sized_def_id) = tcx.lang_items().sized_trait() else {
         return false; /* No Sized trait, can't require it! */
@@ -886,5 +1035,9 @@ pub fn contains_illegal_impl_trait_in_trait<'tcx>(
 }
 
 pub fn provide(providers: &mut ty::query::Providers) {
-    *providers = ty::query::Providers { object_safety_violations, ..*providers };
+    *providers = ty::query::Providers {
+        object_safety_violations,
+        object_safety_violation_items,
+        ..*providers
+    };
 }
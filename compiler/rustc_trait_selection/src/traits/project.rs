@@ -1260,7 +1260,7 @@ fn project<'cx, 'tcx>(
     selcx: &mut SelectionContext<'cx, 'tcx>,
     obligation: &ProjectionTyObligation<'tcx>,
 ) -> Result<Projected<'tcx>, ProjectionError<'tcx>> {
-    if !selcx.tcx().recursion_limit().value_within_limit(obligation.recursion_depth) {
+    if !selcx.recursion_limit().value_within_limit(obligation.recursion_depth) {
         // This should really be an immediate error, but some existing code
         // relies on being able to recover from this.
         return Err(ProjectionError::TraitSelectionError(SelectionError::Overflow(
@@ -1602,6 +1602,25 @@ fn assemble_candidates_from_impls<'cx, 'tcx>(
                         // assume `poly_trait_ref` isn't monomorphic, if it contains any.
                         let poly_trait_ref = selcx.infcx().resolve_vars_if_possible(poly_trait_ref);
                         !poly_trait_ref.still_further_specializable()
+                    } else if obligation.param_env.is_const()
+                        && let specialization_graph::Node::Impl(defining_impl) =
+                            node_item.defining_node
+                        && selcx.tcx().reveals_defaults_in_const(impl_data.impl_def_id)
+                        && selcx.tcx().reveals_defaults_in_const(defining_impl)
+                    {
+                        // In const contexts, a monomorphic trait ref can reveal the default
+                        // if both the selected impl and the impl defining the item apply for
+                        // all lifetimes. Const evaluation, which runs with `Reveal::All` and
+                        // erased regions, then ends up with the same impl and the same type.
+                        let poly_trait_ref = selcx.infcx().resolve_vars_if_possible(poly_trait_ref);
+                        let eligible = !poly_trait_ref.still_further_specializable();
+                        debug!(
+                            assoc_ty = ?selcx.tcx().def_path_str(node_item.item.def_id),
+                            ?obligation.predicate,
+                            eligible,
+                            "assemble_candidates_from_impls: default in const context",
+                        );
+                        eligible
                     } else {
                         debug!(
                             assoc_ty = ?selcx.tcx().def_path_str(node_item.item.def_id),
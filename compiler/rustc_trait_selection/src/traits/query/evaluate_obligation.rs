@@ -1,4 +1,4 @@
-use rustc_middle::ty;
+use rustc_middle::ty::{self, TypeVisitable};
 
 use crate::infer::canonical::OriginalQueryValues;
 use crate::infer::InferCtxt;
@@ -65,6 +65,15 @@ fn evaluate_obligation(
         &self,
         obligation: &PredicateObligation<'tcx>,
     ) -> Result<EvaluationResult, OverflowError> {
+        // The canonical query doesn't know the hidden types of the opaque types
+        // in this inference context, so it can't reveal them.
+        if self.tcx.sess.opts.unstable_opts.reveal_opaque_types_in_defining_scope
+            && obligation.predicate.has_opaque_types()
+        {
+            let mut selcx = SelectionContext::with_query_mode(&self, TraitQueryMode::Canonical);
+            return selcx.evaluate_root_obligation(obligation);
+        }
+
         let mut _orig_values = OriginalQueryValues::default();
 
         let param_env = match obligation.predicate.kind().skip_binder() {
@@ -79,6 +88,24 @@ fn evaluate_obligation(
             _ => obligation.param_env.without_const(),
         };
 
+        // Like in `evaluate_trait_predicate_recursively`, a global trait predicate
+        // does not depend on a param env without global bounds. Dropping the caller
+        // bounds means that a goal like `String: Display` has the same canonical
+        // key in every body, so it is only evaluated once per session instead of
+        // once for each distinct param env it comes up in. Erased regions may
+        // stand for early-bound regions of the body, so those goals keep them.
+        let is_trait_predicate =
+            matches!(obligation.predicate.kind().skip_binder(), ty::PredicateKind::Trait(_));
+        let param_env = if is_trait_predicate
+            && obligation.predicate.is_global()
+            && !obligation.predicate.has_erased_regions()
+            && param_env.caller_bounds().iter().all(|bound| bound.needs_subst())
+        {
+            param_env.without_caller_bounds()
+        } else {
+            param_env
+        };
+
         let c_pred = self
             .canonicalize_query_keep_static(param_env.and(obligation.predicate), &mut _orig_values);
         // Run canonical query. If overflow occurs, rerun from scratch but this time
@@ -6,26 +6,28 @@
 //!
 //! [rustc dev guide]:https://rustc-dev-guide.rust-lang.org/traits/resolution.html#candidate-assembly
 use hir::LangItem;
-use rustc_errors::DelayDm;
+use rustc_errors::{Applicability, DelayDm};
 use rustc_hir as hir;
-use rustc_infer::traits::ObligationCause;
-use rustc_infer::traits::{Obligation, SelectionError, TraitObligation};
+use rustc_hir::def_id::DefId;
+use rustc_infer::traits::{Obligation, PredicateObligation, SelectionError, TraitObligation};
+use rustc_infer::traits::{ObligationCause, ObligationCauseCode};
 use rustc_lint_defs::builtin::DEREF_INTO_DYN_SUPERTRAIT;
-use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::fast_reject::{self, TreatParams};
 use rustc_middle::ty::{self, Ty, TypeVisitable};
+use rustc_span::symbol::sym;
 use rustc_target::spec::abi::Abi;
 
 use crate::traits;
 use crate::traits::coherence::Conflict;
 use crate::traits::query::evaluate_obligation::InferCtxtExt;
+use crate::traits::Unimplemented;
 use crate::traits::{util, SelectionResult};
-use crate::traits::{ErrorReporting, Overflow, Unimplemented};
 
 use super::BuiltinImplConditions;
+use super::ImplRejection;
 use super::IntercrateAmbiguityCause;
-use super::OverflowError;
 use super::SelectionCandidate::{self, *};
-use super::{EvaluatedCandidate, SelectionCandidateSet, SelectionContext, TraitObligationStack};
+use super::{SelectionCandidateSet, SelectionContext, TraitObligationStack};
 
 impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     #[instrument(level = "debug", skip(self), ret)]
@@ -35,7 +37,7 @@ pub(super) fn candidate_from_obligation<'o>(
     ) -> SelectionResult<'tcx, SelectionCandidate<'tcx>> {
         // Watch out for overflow. This intentionally bypasses (and does
         // not update) the cache.
-        self.check_recursion_limit(&stack.obligation, &stack.obligation)?;
+        self.check_recursion_limit(&stack.obligation, &stack.obligation, stack.list())?;
 
         // Check the cache. Note that we freshen the trait-ref
         // separately rather than using `stack.fresh_trait_ref` --
@@ -49,6 +51,10 @@ pub(super) fn candidate_from_obligation<'o>(
             self.check_candidate_cache(stack.obligation.param_env, cache_fresh_trait_pred)
         {
             debug!("CACHE HIT");
+            if stack.depth == 1 && self.tcx().sess.opts.unstable_opts.dump_selection_tree.is_some()
+            {
+                self.dumped_candidates = None;
+            }
             return c;
         }
 
@@ -77,38 +83,41 @@ fn candidate_from_obligation_no_cache<'o>(
     ) -> SelectionResult<'tcx, SelectionCandidate<'tcx>> {
         if let Err(conflict) = self.is_knowable(stack) {
             debug!("coherence stage: not knowable");
-            if self.intercrate_ambiguity_causes.is_some() {
-                debug!("evaluate_stack: intercrate_ambiguity_causes is some");
-                // Heuristics: show the diagnostics when there are no candidates in crate.
+            if self.collects_intercrate_ambiguity_causes() {
+                debug!("evaluate_stack: collecting intercrate ambiguity causes");
+                // Heuristics: show the diagnostics when there are no candidates in crate,
+                // unless `-Zalways-explain-intercrate-ambiguity` asks for them regardless.
+                let always_explain =
+                    self.tcx().sess.opts.unstable_opts.always_explain_intercrate_ambiguity;
                 if let Ok(candidate_set) = self.assemble_candidates(stack) {
                     let mut no_candidates_apply = true;
 
-                    for c in candidate_set.vec.iter() {
-                        if self.evaluate_candidate(stack, &c)?.may_apply() {
-                            no_candidates_apply = false;
-                            break;
+                    if !always_explain {
+                        for c in candidate_set.vec.iter() {
+                            if self.evaluate_candidate(stack, &c)?.may_apply() {
+                                no_candidates_apply = false;
+                                break;
+                            }
                         }
                     }
 
                     if !candidate_set.ambiguous && no_candidates_apply {
-                        let trait_ref = stack.obligation.predicate.skip_binder().trait_ref;
+                        let trait_ref = self.infcx.resolve_vars_if_possible(
+                            stack.obligation.predicate.skip_binder().trait_ref,
+                        );
                         let self_ty = trait_ref.self_ty();
-                        let (trait_desc, self_desc) = with_no_trimmed_paths!({
-                            let trait_desc = trait_ref.print_only_trait_path().to_string();
-                            let self_desc = if self_ty.has_concrete_skeleton() {
-                                Some(self_ty.to_string())
-                            } else {
-                                None
-                            };
-                            (trait_desc, self_desc)
-                        });
+                        let self_ty = self_ty.has_concrete_skeleton().then_some(self_ty);
+                        let span = stack.obligation.cause.span;
                         let cause = if let Conflict::Upstream = conflict {
-                            IntercrateAmbiguityCause::UpstreamCrateUpdate { trait_desc, self_desc }
+                            IntercrateAmbiguityCause::UpstreamCrateUpdate {
+                                trait_ref,
+                                self_ty,
+                                span,
+                            }
                         } else {
-                            IntercrateAmbiguityCause::DownstreamCrate { trait_desc, self_desc }
+                            IntercrateAmbiguityCause::DownstreamCrate { trait_ref, self_ty, span }
                         };
-                        debug!(?cause, "evaluate_stack: pushing cause");
-                        self.intercrate_ambiguity_causes.as_mut().unwrap().insert(cause);
+                        self.add_intercrate_ambiguity_cause(cause);
                     }
                 }
             }
@@ -122,10 +131,25 @@ fn candidate_from_obligation_no_cache<'o>(
             return Ok(None);
         }
 
+        if self.tracks_rejected_impls(stack) {
+            for &impl_def_id in &candidate_set.mismatched_impls {
+                self.record_rejected_impl(stack, impl_def_id, |_| ImplRejection::HeaderMismatch);
+            }
+            for &impl_def_id in &candidate_set.rejected_blanket_impls {
+                self.record_rejected_impl(stack, impl_def_id, |this| {
+                    this.impl_rejection(stack, impl_def_id)
+                });
+            }
+        }
+
         let candidates = candidate_set.vec;
 
         debug!(?stack, ?candidates, "assembled {} candidates", candidates.len());
 
+        if stack.depth == 1 && self.tcx().sess.opts.unstable_opts.dump_selection_tree.is_some() {
+            self.dumped_candidates = Some(candidates.iter().map(|c| format!("{c:?}")).collect());
+        }
+
         // At this point, we know that each of the entries in the
         // candidate set is *individually* applicable. Now we have to
         // figure out if they contain mutual incompatibilities. This
@@ -135,7 +159,7 @@ fn candidate_from_obligation_no_cache<'o>(
         // candidate which assumes $0 == int, one that assumes `$0 ==
         // usize`, etc. This spells an ambiguity.
 
-        let mut candidates = self.filter_impls(candidates, stack.obligation);
+        let mut candidates = self.filter_impls(candidates, stack);
 
         // If there is more than one candidate, first winnow them down
         // by considering extra conditions (nested obligations and so
@@ -157,52 +181,13 @@ fn candidate_from_obligation_no_cache<'o>(
 
         // Winnow, but record the exact outcome of evaluation, which
         // is needed for specialization. Propagate overflow if it occurs.
-        let mut candidates = candidates
-            .into_iter()
-            .map(|c| match self.evaluate_candidate(stack, &c) {
-                Ok(eval) if eval.may_apply() => {
-                    Ok(Some(EvaluatedCandidate { candidate: c, evaluation: eval }))
-                }
-                Ok(_) => Ok(None),
-                Err(OverflowError::Canonical) => Err(Overflow(OverflowError::Canonical)),
-                Err(OverflowError::ErrorReporting) => Err(ErrorReporting),
-                Err(OverflowError::Error(e)) => Err(Overflow(OverflowError::Error(e))),
-            })
-            .flat_map(Result::transpose)
-            .collect::<Result<Vec<_>, _>>()?;
-
-        debug!(?stack, ?candidates, "winnowed to {} candidates", candidates.len());
+        let mut candidates = self.winnow_candidates(stack, candidates, true)?;
 
-        let needs_infer = stack.obligation.predicate.has_non_region_infer();
-
-        // If there are STILL multiple candidates, we can further
-        // reduce the list by dropping duplicates -- including
-        // resolving specializations.
+        // If there are *STILL* multiple candidates, give up and report
+        // ambiguity.
         if candidates.len() > 1 {
-            let mut i = 0;
-            while i < candidates.len() {
-                let is_dup = (0..candidates.len()).filter(|&j| i != j).any(|j| {
-                    self.candidate_should_be_dropped_in_favor_of(
-                        &candidates[i],
-                        &candidates[j],
-                        needs_infer,
-                    )
-                });
-                if is_dup {
-                    debug!(candidate = ?candidates[i], "Dropping candidate #{}/{}", i, candidates.len());
-                    candidates.swap_remove(i);
-                } else {
-                    debug!(candidate = ?candidates[i], "Retaining candidate #{}/{}", i, candidates.len());
-                    i += 1;
-
-                    // If there are *STILL* multiple candidates, give up
-                    // and report ambiguity.
-                    if i > 1 {
-                        debug!("multiple matches, ambig");
-                        return Ok(None);
-                    }
-                }
-            }
+            debug!("multiple matches, ambig");
+            return Ok(None);
         }
 
         // If there are *NO* candidates, then there are no impls --
@@ -254,16 +239,27 @@ pub(super) fn assemble_candidates<'o>(
             // Take the fast path out - this also improves
             // performance by preventing assemble_candidates_from_impls from
             // matching every impl for this trait.
-            return Ok(SelectionCandidateSet { vec: vec![], ambiguous: true });
+            return Ok(SelectionCandidateSet {
+                vec: vec![],
+                ambiguous: true,
+                rejected_blanket_impls: vec![],
+                mismatched_impls: vec![],
+            });
         }
 
-        let mut candidates = SelectionCandidateSet { vec: Vec::new(), ambiguous: false };
+        let mut candidates = SelectionCandidateSet {
+            vec: Vec::new(),
+            ambiguous: false,
+            rejected_blanket_impls: Vec::new(),
+            mismatched_impls: Vec::new(),
+        };
 
-        // The only way to prove a NotImplemented(T: Foo) predicate is via a negative impl.
-        // There are no compiler built-in rules for this.
+        // The only way to prove a NotImplemented(T: Foo) predicate is via a negative impl
+        // or a negative where clause. There are no compiler built-in rules for this.
         if obligation.polarity() == ty::ImplPolarity::Negative {
             self.assemble_candidates_for_trait_alias(obligation, &mut candidates);
             self.assemble_candidates_from_impls(obligation, &mut candidates);
+            self.assemble_candidates_from_caller_bounds(stack, &mut candidates)?;
         } else {
             self.assemble_candidates_for_trait_alias(obligation, &mut candidates);
 
@@ -323,6 +319,24 @@ pub(super) fn assemble_candidates<'o>(
 
             self.assemble_candidates_from_projected_tys(obligation, &mut candidates);
             self.assemble_candidates_from_caller_bounds(stack, &mut candidates)?;
+            // Blanket impls rejected by a cheap check on their self type bounds
+            // are only interesting if they are the only thing that could apply:
+            // their unsatisfied bound is then what the error should blame. They
+            // still have to match the obligation like any other impl candidate.
+            if candidates.vec.is_empty() {
+                let rejected_blanket_impls = std::mem::take(&mut candidates.rejected_blanket_impls);
+                for impl_def_id in rejected_blanket_impls {
+                    self.assemble_candidate_from_impl(obligation, impl_def_id, &mut candidates);
+                }
+            }
+            // An opaque type in its defining scope is only revealed to its hidden
+            // type if nothing else applies, which also avoids computing its type
+            // for auto traits while it is still being inferred.
+            if candidates.vec.is_empty()
+                && self.tcx().sess.opts.unstable_opts.reveal_opaque_types_in_defining_scope
+            {
+                self.assemble_candidate_from_opaque_hidden_type(stack, &mut candidates)?;
+            }
             // Auto implementations have lower priority, so we only
             // consider triggering a default if there is no other impl that can apply.
             if candidates.vec.is_empty() {
@@ -381,13 +395,15 @@ fn assemble_candidates_from_caller_bounds<'o>(
             .filter_map(|o| o.to_opt_poly_trait_pred());
 
         // Micro-optimization: filter out predicates relating to different traits.
-        let matching_bounds =
-            all_bounds.filter(|p| p.def_id() == stack.obligation.predicate.def_id());
+        // A bound can only prove obligations of the same polarity.
+        let matching_bounds = all_bounds.filter(|p| {
+            p.def_id() == stack.obligation.predicate.def_id()
+                && p.skip_binder().polarity == stack.obligation.polarity()
+        });
 
         // Keep only those bounds which may apply, and propagate overflow if it occurs.
         for bound in matching_bounds {
-            // FIXME(oli-obk): it is suspicious that we are dropping the constness and
-            // polarity here.
+            // FIXME(oli-obk): it is suspicious that we are dropping the constness here.
             let wc = self.where_clause_may_apply(stack, bound.map_bound(|t| t.trait_ref))?;
             if wc.may_apply() {
                 candidates.vec.push(ParamCandidate(bound));
@@ -537,27 +553,141 @@ fn assemble_candidates_from_impls(
             return;
         }
 
-        self.tcx().for_each_relevant_impl(
+        self.tcx().for_each_relevant_impl_with_self_bounds(
             obligation.predicate.def_id(),
             obligation.predicate.skip_binder().trait_ref.self_ty(),
-            |impl_def_id| {
+            |impl_def_id, self_bounds| {
                 // Before we create the substitutions and everything, first
                 // consider a "quick reject". This avoids creating more types
                 // and so forth that we need to.
                 let impl_trait_ref = self.tcx().bound_impl_trait_ref(impl_def_id).unwrap();
                 if self.fast_reject_trait_refs(obligation, &impl_trait_ref.0) {
+                    if self.rejected_impls.is_some() {
+                        candidates.mismatched_impls.push(impl_def_id);
+                    }
                     return;
                 }
 
-                self.infcx.probe(|_| {
-                    if let Ok(_substs) = self.match_impl(impl_def_id, impl_trait_ref, obligation) {
-                        candidates.vec.push(ImplCandidate(impl_def_id));
-                    }
-                });
+                // For blanket impls like `impl<T: Foo> Bar for T`, check whether
+                // `Foo` can possibly be implemented by the self type before doing
+                // the comparatively expensive matching of the impl header.
+                if self_bounds
+                    .iter()
+                    .any(|&bound_def_id| self.fast_reject_self_bound(obligation, bound_def_id))
+                {
+                    candidates.rejected_blanket_impls.push(impl_def_id);
+                    return;
+                }
+
+                self.assemble_candidate_from_impl(obligation, impl_def_id, candidates);
             },
         );
     }
 
+    /// Adds `impl_def_id` as a candidate if its header matches `obligation`.
+    fn assemble_candidate_from_impl(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
+        impl_def_id: DefId,
+        candidates: &mut SelectionCandidateSet<'tcx>,
+    ) {
+        let impl_trait_ref = self.tcx().bound_impl_trait_ref(impl_def_id).unwrap();
+        self.infcx.probe(|_| {
+            if let Ok(_substs) = self.match_impl(impl_def_id, impl_trait_ref, obligation) {
+                candidates.vec.push(ImplCandidate(impl_def_id));
+            } else if self.rejected_impls.is_some() {
+                candidates.mismatched_impls.push(impl_def_id);
+            }
+        });
+    }
+
+    /// Returns `true` if the self type of `obligation` can never implement the
+    /// trait `bound_def_id`, which bounds the self type parameter of a blanket impl.
+    ///
+    /// This only looks at the impls of `bound_def_id` and the caller bounds, so we
+    /// have to be conservative: self types which may get a candidate from anywhere
+    /// else (e.g. from an object type or a projection bound) are never rejected.
+    /// Auto traits, trait aliases and lang items were already filtered out by the
+    /// `blanket_impl_self_bounds` query.
+    fn fast_reject_self_bound(
+        &self,
+        obligation: &TraitObligation<'tcx>,
+        bound_def_id: DefId,
+    ) -> bool {
+        // During coherence, downstream and upstream crates may add impls for
+        // the bound, so we can't say anything about it.
+        if self.is_intercrate() || obligation.polarity() != ty::ImplPolarity::Positive {
+            return false;
+        }
+
+        let tcx = self.tcx();
+        let self_ty = obligation.predicate.skip_binder().self_ty();
+        if self_ty.has_non_region_infer() || self_ty.references_error() {
+            return false;
+        }
+
+        match self_ty.kind() {
+            ty::Bool
+            | ty::Char
+            | ty::Int(_)
+            | ty::Uint(_)
+            | ty::Float(_)
+            | ty::Str
+            | ty::Adt(..)
+            | ty::Foreign(_)
+            | ty::Array(..)
+            | ty::Slice(_)
+            | ty::RawPtr(_)
+            | ty::Ref(..)
+            | ty::Tuple(_)
+            | ty::Never => {}
+            _ => return false,
+        }
+
+        let bound_impls = tcx.trait_impls_of(bound_def_id);
+        if !bound_impls.blanket_impls().is_empty() {
+            return false;
+        }
+
+        let Some(simp) = fast_reject::simplify_type(tcx, self_ty, TreatParams::AsPlaceholder) else {
+            return false;
+        };
+        if bound_impls.non_blanket_impls().contains_key(&simp) {
+            return false;
+        }
+
+        // A where-clause like `where MyType: Foo` may still make the bound hold.
+        !obligation.param_env.caller_bounds().iter().any(|bound| {
+            bound.to_opt_poly_trait_pred().map_or(false, |bound| bound.def_id() == bound_def_id)
+        })
+    }
+
+    /// Assembles a candidate that proves the obligation for the hidden type of its
+    /// self type, if that is an opaque type in its defining scope which already has
+    /// a hidden type. The hidden type is only revealed in a probe here, to check
+    /// that the obligation may hold for it.
+    fn assemble_candidate_from_opaque_hidden_type<'o>(
+        &mut self,
+        stack: &TraitObligationStack<'o, 'tcx>,
+        candidates: &mut SelectionCandidateSet<'tcx>,
+    ) -> Result<(), SelectionError<'tcx>> {
+        let obligation = stack.obligation;
+        let self_ty = obligation.predicate.skip_binder().self_ty();
+        let Some(hidden_ty) = self.opaque_hidden_type(self_ty) else { return Ok(()) };
+
+        let tcx = self.tcx();
+        let hidden_obligation: PredicateObligation<'tcx> = obligation
+            .with(tcx, obligation.predicate.map_bound(|pred| pred.with_self_type(tcx, hidden_ty)));
+        let result = self.evaluation_probe(|this| {
+            this.evaluate_predicate_recursively(stack.list(), hidden_obligation)
+        })?;
+        if result.may_apply() {
+            candidates.vec.push(OpaqueHiddenTypeCandidate);
+        }
+
+        Ok(())
+    }
+
     fn assemble_candidates_from_auto_impls(
         &mut self,
         obligation: &TraitObligation<'tcx>,
@@ -583,6 +713,13 @@ fn assemble_candidates_from_auto_impls(
                     // still be provided by a manual implementation for
                     // this trait and type.
                 }
+                ty::Projection(..)
+                    if self.infcx.probe(|_| {
+                        self.normalize_impl_trait_in_trait(obligation, self_ty).is_some()
+                    }) =>
+                {
+                    candidates.vec.push(RevealedImplTraitInTraitCandidate);
+                }
                 ty::Param(..) | ty::Projection(..) => {
                     // In these cases, we don't know what the actual
                     // type is.  Therefore, we cannot break it down
@@ -602,6 +739,14 @@ fn assemble_candidates_from_auto_impls(
                     // The auto impl might apply; we don't know.
                     candidates.ambiguous = true;
                 }
+                ty::Opaque(..)
+                    if self.tcx().sess.opts.unstable_opts.reveal_opaque_types_in_defining_scope
+                        && self.opaque_hidden_type(self_ty).is_some() =>
+                {
+                    // Leaking the auto traits of an opaque type in its defining scope
+                    // would compute its type while it is still being inferred. Leave
+                    // it to the candidate for its hidden type instead.
+                }
                 ty::Generator(_, _, movability)
                     if self.tcx().lang_items().unpin_trait() == Some(def_id) =>
                 {
@@ -681,18 +826,26 @@ fn assemble_candidates_from_object_ty(
             // we are looking for. Specifically, do not only check for the
             // correct trait, but also the correct type parameters.
             // For example, we may be trying to upcast `Foo` to `Bar<i32>`,
-            // but `Foo` is declared as `trait Foo: Bar<u32>`.
+            // but `Foo` is declared as `trait Foo: Bar<u32>`. We remember
+            // these mismatches for error reporting if asked to.
             let candidate_supertraits = util::supertraits(self.tcx(), poly_trait_ref)
                 .enumerate()
                 .filter(|&(_, upcast_trait_ref)| {
-                    self.infcx.probe(|_| {
+                    let matches = self.infcx.probe(|_| {
                         self.match_normalize_trait_ref(
                             obligation,
                             upcast_trait_ref,
                             placeholder_trait_predicate.trait_ref,
                         )
                         .is_ok()
-                    })
+                    });
+                    if !matches
+                        && upcast_trait_ref.def_id() == obligation.predicate.def_id()
+                        && let Some(mismatches) = &mut self.object_supertrait_mismatches
+                    {
+                        mismatches.push(self.infcx.resolve_vars_if_possible(upcast_trait_ref));
+                    }
+                    matches
                 })
                 .map(|(idx, _)| ObjectCandidate(idx));
 
@@ -746,6 +899,89 @@ fn need_migrate_deref_output_trait_object(
         })
     }
 
+    /// Emits `DEREF_INTO_DYN_SUPERTRAIT` for a coercion from `source` that currently goes
+    /// through its `Deref` impl, but would become a trait upcasting coercion to
+    /// `deref_trait_ref` once `trait_upcasting` is stabilized.
+    fn lint_deref_into_dyn_supertrait(
+        &self,
+        obligation: &TraitObligation<'tcx>,
+        source: Ty<'tcx>,
+        deref_trait_ref: ty::PolyExistentialTraitRef<'tcx>,
+    ) {
+        let tcx = self.tcx();
+        let span = obligation.cause.span;
+        tcx.struct_span_lint_hir(
+            DEREF_INTO_DYN_SUPERTRAIT,
+            obligation.cause.body_id,
+            span,
+            DelayDm(|| {
+                format!(
+                    "`{}` implements `Deref` with supertrait `{}` as output",
+                    source, deref_trait_ref
+                )
+            }),
+            |lint| {
+                lint.span_label(
+                    span,
+                    format!("`{}` is coerced to `dyn {}` here", source, deref_trait_ref),
+                );
+                // Only offer the rewrites for a reference coerced by a plain expression, not
+                // for coercion sites that were produced by macro expansion.
+                let ObligationCauseCode::Coercion { source: pointer_ty, .. } =
+                    *obligation.cause.code().peel_derives()
+                else {
+                    return lint;
+                };
+                let ty::Ref(_, _, mutbl) = *pointer_ty.kind() else {
+                    return lint;
+                };
+                if span.from_expansion() {
+                    return lint;
+                }
+                let Ok(snippet) = tcx.sess.source_map().span_to_snippet(span) else {
+                    return lint;
+                };
+                // `Deref` isn't in the prelude, so call it by its full path. `std` can only be
+                // named like that if the crate links to it.
+                let krate_attrs = tcx.hir().krate_attrs();
+                if !tcx.sess.contains_name(krate_attrs, sym::no_std)
+                    && !tcx.sess.contains_name(krate_attrs, sym::no_core)
+                {
+                    let deref = match mutbl {
+                        hir::Mutability::Not => "std::ops::Deref::deref",
+                        hir::Mutability::Mut => "std::ops::DerefMut::deref_mut",
+                    };
+                    lint.multipart_suggestion_verbose(
+                        "call `deref` explicitly to keep the current behavior",
+                        vec![
+                            (span.shrink_to_lo(), format!("{deref}(")),
+                            (span.shrink_to_hi(), ")".to_string()),
+                        ],
+                        Applicability::MachineApplicable,
+                    );
+                }
+                let is_simple_path =
+                    snippet.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+                let cast = format!(" as &{}dyn {}", mutbl.prefix_str(), deref_trait_ref);
+                let sugg = if is_simple_path {
+                    vec![(span.shrink_to_hi(), cast)]
+                } else {
+                    vec![
+                        (span.shrink_to_lo(), "(".to_string()),
+                        (span.shrink_to_hi(), format!("){cast}")),
+                    ]
+                };
+                lint.multipart_suggestion_verbose(
+                    "or, with trait upcasting, cast to the supertrait directly instead of calling \
+                     `Deref::deref`",
+                    sugg,
+                    Applicability::MaybeIncorrect,
+                );
+                lint
+            },
+        );
+    }
+
     /// Searches for unsizing that might apply to `obligation`.
     fn assemble_candidates_for_unsizing(
         &mut self,
@@ -811,15 +1047,10 @@ fn assemble_candidates_for_unsizing(
                             &obligation.cause,
                         ) {
                             if deref_trait_ref.def_id() == target_trait_did {
-                                self.tcx().struct_span_lint_hir(
-                                    DEREF_INTO_DYN_SUPERTRAIT,
-                                    obligation.cause.body_id,
-                                    obligation.cause.span,
-                                    DelayDm(|| format!(
-                                        "`{}` implements `Deref` with supertrait `{}` as output",
-                                        source, deref_trait_ref
-                                    )),
-                                    |lint| lint,
+                                self.lint_deref_into_dyn_supertrait(
+                                    obligation,
+                                    source,
+                                    deref_trait_ref,
                                 );
                                 return;
                             }
@@ -1060,6 +1291,21 @@ fn assemble_candidate_for_ptr_sized(
             return;
         }
 
+        // Thin pointers are always pointer-sized, so avoid querying their layout.
+        match *self_ty.kind() {
+            ty::Ref(_, pointee, _) | ty::RawPtr(ty::TypeAndMut { ty: pointee, .. })
+                if pointee.is_trivially_sized(self.tcx()) =>
+            {
+                candidates.vec.push(BuiltinCandidate { has_nested: false });
+                return;
+            }
+            ty::FnPtr(_) => {
+                candidates.vec.push(BuiltinCandidate { has_nested: false });
+                return;
+            }
+            _ => {}
+        }
+
         let usize_layout =
             self.tcx().layout_of(ty::ParamEnv::empty().and(self.tcx().types.usize)).unwrap().layout;
         if let Ok(layout) = self.tcx().layout_of(obligation.param_env.and(self_ty))
@@ -7,6 +7,7 @@
 //! [rustc dev guide]:
 //! https://rustc-dev-guide.rust-lang.org/traits/resolution.html#confirmation
 use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_hir as hir;
 use rustc_hir::lang_items::LangItem;
 use rustc_index::bit_set::GrowableBitSet;
 use rustc_infer::infer::InferOk;
@@ -20,10 +21,10 @@
 use crate::traits::project::{normalize_with_depth, normalize_with_depth_to};
 use crate::traits::util::{self, closure_trait_ref_and_return_type, predicate_for_trait_def};
 use crate::traits::{
-    BuiltinDerivedObligation, ImplDerivedObligation, ImplDerivedObligationCause, ImplSource,
-    ImplSourceAutoImplData, ImplSourceBuiltinData, ImplSourceClosureData,
-    ImplSourceConstDestructData, ImplSourceDiscriminantKindData, ImplSourceFnPointerData,
-    ImplSourceGeneratorData, ImplSourceObjectData, ImplSourcePointeeData, ImplSourceTraitAliasData,
+    gat_is_sized_confined, BuiltinDerivedObligation, ImplSource, ImplSourceAutoImplData,
+    ImplSourceBuiltinData, ImplSourceClosureData, ImplSourceConstDestructData,
+    ImplSourceDiscriminantKindData, ImplSourceFnPointerData, ImplSourceGeneratorData,
+    ImplSourceObjectData, ImplSourcePointeeData, ImplSourceTraitAliasData,
     ImplSourceTraitUpcastingData, ImplSourceUserDefinedData, Normalized, ObjectCastObligation,
     Obligation, ObligationCause, OutputTypeParameterMismatch, PredicateObligation, Selection,
     SelectionError, TraitNotObjectSafe, TraitObligation, Unimplemented, VtblSegment,
@@ -32,6 +33,7 @@
 use super::BuiltinImplConditions;
 use super::SelectionCandidate::{self, *};
 use super::SelectionContext;
+use super::{ProvisionalEvaluationCache, TraitObligationStackList};
 
 use std::iter;
 use std::ops::ControlFlow;
@@ -126,6 +128,16 @@ pub(super) fn confirm_candidate(
                 let data = self.confirm_const_destruct_candidate(obligation, def_id)?;
                 ImplSource::ConstDestruct(data)
             }
+
+            OpaqueHiddenTypeCandidate => {
+                let data = self.confirm_opaque_hidden_type_candidate(obligation);
+                ImplSource::Builtin(data)
+            }
+
+            RevealedImplTraitInTraitCandidate => {
+                let data = self.confirm_revealed_impl_trait_in_trait_candidate(obligation);
+                ImplSource::Builtin(data)
+            }
         };
 
         if !obligation.predicate.is_const_if_const() {
@@ -298,13 +310,113 @@ fn confirm_transmutability_candidate(
 
         let mut transmute_env = rustc_transmute::TransmuteTypeEnv::new(self.infcx);
 
+        let assume_lifetimes = assume.lifetimes;
         let maybe_transmutable = transmute_env.is_transmutable(cause, src_and_dst, scope, assume);
 
+        let mut nested = vec![];
+        self.flatten_transmutability_answer(
+            obligation,
+            predicate,
+            assume_lifetimes,
+            maybe_transmutable,
+            &mut nested,
+        )?;
+        Ok(ImplSourceBuiltinData { nested })
+    }
+
+    /// Turns the conditions of an `Answer` into nested obligations: for each pair of
+    /// references, the referents must be transmutable (in both directions, if the
+    /// destination can be written through) and, unless lifetimes are assumed to be fine,
+    /// the source lifetime must outlive the destination lifetime.
+    ///
+    /// A disjunction of conditions can't be expressed as nested obligations. Each of its
+    /// alternatives is evaluated in a probe instead, and the first one that may hold is
+    /// committed to.
+    fn flatten_transmutability_answer(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
+        predicate: ty::PolyTraitPredicate<'tcx>,
+        assume_lifetimes: bool,
+        answer: rustc_transmute::Answer<rustc_transmute::Ref<'tcx>>,
+        nested: &mut Vec<PredicateObligation<'tcx>>,
+    ) -> Result<(), SelectionError<'tcx>> {
         use rustc_transmute::Answer;
 
-        match maybe_transmutable {
-            Answer::Yes => Ok(ImplSourceBuiltinData { nested: vec![] }),
-            _ => Err(Unimplemented),
+        let tcx = self.tcx();
+        match answer {
+            Answer::Yes => Ok(()),
+            Answer::No(_) => Err(Unimplemented),
+            Answer::IfAll(answers) => answers.into_iter().try_for_each(|answer| {
+                self.flatten_transmutability_answer(
+                    obligation,
+                    predicate,
+                    assume_lifetimes,
+                    answer,
+                    nested,
+                )
+            }),
+            Answer::IfAny(answers) => {
+                for answer in answers {
+                    let mut alternative = vec![];
+                    if self
+                        .flatten_transmutability_answer(
+                            obligation,
+                            predicate,
+                            assume_lifetimes,
+                            answer,
+                            &mut alternative,
+                        )
+                        .is_err()
+                    {
+                        continue;
+                    }
+                    let result = self.evaluation_probe(|this| {
+                        this.evaluate_predicates_recursively(
+                            TraitObligationStackList::empty(&ProvisionalEvaluationCache::default()),
+                            alternative.clone(),
+                        )
+                    })?;
+                    if result.may_apply() {
+                        nested.extend(alternative);
+                        return Ok(());
+                    }
+                }
+                Err(Unimplemented)
+            }
+            Answer::IfTransmutable { src, dst } => {
+                let referents_transmutable = |src: Ty<'tcx>, dst: Ty<'tcx>| {
+                    let trait_pred = predicate.map_bound(|mut pred| {
+                        let substs = pred.trait_ref.substs;
+                        pred.trait_ref.substs = tcx.mk_substs(
+                            [dst.into(), src.into()].into_iter().chain(substs.iter().skip(2)),
+                        );
+                        pred
+                    });
+                    Obligation::with_depth(
+                        tcx,
+                        obligation.cause.clone(),
+                        obligation.recursion_depth + 1,
+                        obligation.param_env,
+                        trait_pred,
+                    )
+                };
+                nested.push(referents_transmutable(src.ty, dst.ty));
+                if dst.mutability == hir::Mutability::Mut {
+                    nested.push(referents_transmutable(dst.ty, src.ty));
+                }
+                if !assume_lifetimes {
+                    nested.push(Obligation::with_depth(
+                        tcx,
+                        obligation.cause.clone(),
+                        obligation.recursion_depth + 1,
+                        obligation.param_env,
+                        ty::Binder::dummy(ty::PredicateKind::RegionOutlives(
+                            ty::OutlivesPredicate(src.lifetime, dst.lifetime),
+                        )),
+                    ));
+                }
+                Ok(())
+            }
         }
     }
 
@@ -387,7 +499,7 @@ fn confirm_impl_candidate(
         })
     }
 
-    fn vtable_impl(
+    pub(super) fn vtable_impl(
         &mut self,
         impl_def_id: DefId,
         substs: Normalized<'tcx, SubstsRef<'tcx>>,
@@ -497,6 +609,14 @@ fn confirm_object_candidate(
         for assoc_type in assoc_types {
             let defs: &ty::Generics = tcx.generics_of(assoc_type);
 
+            // A GAT which is only used by `Self: Sized` methods can't be used
+            // through the trait object, so its bounds don't have to hold.
+            if tcx.features().object_safe_sized_gats
+                && gat_is_sized_confined(tcx, trait_predicate.def_id(), assoc_type)
+            {
+                continue;
+            }
+
             if !defs.params.is_empty() && !tcx.features().generic_associated_types_extended {
                 tcx.sess.delay_span_bug(
                     obligation.cause.span,
@@ -893,6 +1013,49 @@ fn confirm_trait_upcasting_unsize_candidate(
         Ok(ImplSourceTraitUpcastingData { upcast_trait_ref, vtable_vptr_slot, nested })
     }
 
+    /// The opaque self type is already constrained to be equal to its hidden type
+    /// by the hidden type registered for it, so all that is left is to prove the
+    /// obligation for the hidden type.
+    fn confirm_opaque_hidden_type_candidate(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
+    ) -> ImplSourceBuiltinData<PredicateObligation<'tcx>> {
+        let tcx = self.tcx();
+        let self_ty = obligation.predicate.skip_binder().self_ty();
+        let Some(hidden_ty) = self.opaque_hidden_type(self_ty) else {
+            bug!("opaque hidden type candidate for `{self_ty}` without a hidden type")
+        };
+        let nested = Obligation::with_depth(
+            tcx,
+            obligation.cause.clone(),
+            obligation.recursion_depth + 1,
+            obligation.param_env,
+            obligation.predicate.map_bound(|pred| pred.with_self_type(tcx, hidden_ty)),
+        );
+        ImplSourceBuiltinData { nested: vec![nested] }
+    }
+
+    fn confirm_revealed_impl_trait_in_trait_candidate(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
+    ) -> ImplSourceBuiltinData<PredicateObligation<'tcx>> {
+        let tcx = self.tcx();
+        let self_ty = obligation.predicate.skip_binder().self_ty();
+        let Some(Normalized { value: revealed_ty, obligations: mut nested }) =
+            self.normalize_impl_trait_in_trait(obligation, self_ty)
+        else {
+            bug!("revealed `impl Trait` in trait candidate for `{self_ty}` without an impl")
+        };
+        nested.push(Obligation::with_depth(
+            tcx,
+            obligation.cause.clone(),
+            obligation.recursion_depth + 1,
+            obligation.param_env,
+            obligation.predicate.map_bound(|pred| pred.with_self_type(tcx, revealed_ty)),
+        ));
+        ImplSourceBuiltinData { nested }
+    }
+
     fn confirm_builtin_unsize_candidate(
         &mut self,
         obligation: &TraitObligation<'tcx>,
@@ -1172,18 +1335,13 @@ fn confirm_const_destruct_candidate(
             let substs = self.rematch_impl(impl_def_id, &new_obligation);
             debug!(?substs, "impl substs");
 
-            let cause = obligation.derived_cause(|derived| {
-                ImplDerivedObligation(Box::new(ImplDerivedObligationCause {
-                    derived,
-                    impl_def_id,
-                    span: obligation.cause.span,
-                }))
-            });
+            // The nested obligations are attributed to the where-clauses of the impl that
+            // introduced them by `impl_or_trait_obligations`, just like for a regular impl.
             let obligations = ensure_sufficient_stack(|| {
                 self.vtable_impl(
                     impl_def_id,
                     substs,
-                    &cause,
+                    &obligation.cause,
                     new_obligation.recursion_depth + 1,
                     new_obligation.param_env,
                     obligation.predicate,
@@ -0,0 +1,112 @@
+//! Writes the selections recorded with `-Zdump-selection-tree` as a graphviz file,
+//! and prints the proof trees recorded with `-Zdump-proof-tree`.
+//!
+//! Every selected obligation becomes a node listing the assembled candidates
+//! and the selection result, with edges to the nested obligations of the
+//! chosen candidate. Nodes are keyed by the printed obligation, so nested
+//! obligations that were selected themselves link up into a tree. Selections
+//! taken from the selection cache are marked as cached instead of listing
+//! candidates.
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::trait_dumps::{ProofTree, ProofTreeCertainty};
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Escapes `s` to be used inside of a quoted graphviz label.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\l")
+}
+
+fn write_selection_tree(tcx: TyCtxt<'_>, w: &mut impl Write) -> io::Result<()> {
+    let nodes = tcx.sess.trait_dumps.selection_tree.lock();
+
+    // The same obligation may be selected many times, only the first
+    // selection which was not taken from the cache is shown.
+    let mut ids = FxIndexMap::default();
+    for node in nodes.iter() {
+        let next_id = ids.len();
+        let (_, shown) = ids.entry(node.obligation.as_str()).or_insert((next_id, Some(node)));
+        if shown.map_or(false, |shown| shown.candidates.is_none()) {
+            *shown = Some(node);
+        }
+    }
+    for node in nodes.iter() {
+        for nested in &node.nested {
+            let next_id = ids.len();
+            ids.entry(nested.as_str()).or_insert((next_id, None));
+        }
+    }
+
+    writeln!(w, "digraph selection_tree {{")?;
+    writeln!(w, r#"    node [shape="box", fontname="monospace"];"#)?;
+    for (obligation, (id, node)) in &ids {
+        let mut label = format!("{obligation}\n");
+        if let Some(node) = node {
+            match &node.candidates {
+                Some(candidates) => {
+                    for candidate in candidates {
+                        label.push_str(&format!("  candidate: {candidate}\n"));
+                    }
+                }
+                None => label.push_str("  (cached)\n"),
+            }
+            label.push_str(&format!("  => {}\n", node.result));
+        } else {
+            label.push_str("  (not selected)\n");
+        }
+        writeln!(w, r#"    n{id} [label="{}"];"#, escape(&label))?;
+    }
+    for (_, (id, node)) in &ids {
+        let Some(node) = node else { continue };
+        for nested in &node.nested {
+            writeln!(w, "    n{id} -> n{};", ids[nested.as_str()].0)?;
+        }
+    }
+    writeln!(w, "}}")
+}
+
+/// Writes the selection tree recorded with `-Zdump-selection-tree` to the
+/// file given to that option.
+pub fn dump_selection_tree(tcx: TyCtxt<'_>) {
+    let Some(filename) = &tcx.sess.opts.unstable_opts.dump_selection_tree else { return };
+    let result = File::create(filename)
+        .and_then(|file| write_selection_tree(tcx, &mut BufWriter::new(file)));
+    if let Err(e) = result {
+        tcx.sess.err(&format!("failed to write selection tree to `{filename}`: {e}"));
+    }
+}
+
+fn write_proof_tree(tree: &ProofTree, depth: usize, w: &mut impl Write) -> io::Result<()> {
+    let certainty = match tree.certainty {
+        ProofTreeCertainty::Yes => "yes",
+        ProofTreeCertainty::Maybe => "maybe",
+        ProofTreeCertainty::No => "no",
+    };
+    write!(w, "{:indent$}`{}`: {certainty}", "", tree.goal, indent = depth * 4)?;
+    if let Some(candidate) = &tree.candidate {
+        write!(w, ", via {candidate}")?;
+    }
+    writeln!(w)?;
+    for nested in &tree.nested {
+        write_proof_tree(nested, depth + 1, w)?;
+    }
+    Ok(())
+}
+
+/// Prints the proof trees recorded with `-Zdump-proof-tree` to stdout, one
+/// line per goal with nested goals indented below the goal they prove.
+pub fn dump_proof_trees(tcx: TyCtxt<'_>) {
+    if tcx.sess.opts.unstable_opts.dump_proof_tree.is_none() {
+        return;
+    }
+    let stdout = io::stdout();
+    let mut w = stdout.lock();
+    let proof_trees = tcx.sess.trait_dumps.proof_trees.lock();
+    let result = proof_trees.values().try_for_each(|tree| write_proof_tree(tree, 0, &mut w));
+    if let Err(e) = result {
+        tcx.sess.err(&format!("failed to print proof trees: {e}"));
+    }
+}
@@ -20,9 +20,9 @@
 use super::util::{closure_trait_ref_and_return_type, predicate_for_trait_def};
 use super::wf;
 use super::{
-    ErrorReporting, ImplDerivedObligation, ImplDerivedObligationCause, Normalized, Obligation,
-    ObligationCause, ObligationCauseCode, Overflow, PredicateObligation, Selection, SelectionError,
-    SelectionResult, TraitObligation, TraitQueryMode,
+    ErrorReporting, ImplDerivedObligation, ImplDerivedObligationCause, ImplSource, Normalized,
+    Obligation, ObligationCause, ObligationCauseCode, Overflow, PredicateObligation, Selection,
+    SelectionError, SelectionResult, TraitObligation, TraitQueryMode,
 };
 
 use crate::infer::{InferCtxt, InferOk, TypeFreshener};
@@ -35,6 +35,7 @@
 use rustc_data_structures::stack::ensure_sufficient_stack;
 use rustc_errors::Diagnostic;
 use rustc_hir as hir;
+use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
 use rustc_infer::infer::LateBoundRegionConversionTime;
 use rustc_middle::dep_graph::{DepKind, DepNodeIndex};
@@ -42,53 +43,127 @@
 use rustc_middle::ty::abstract_const::NotConstEvaluatable;
 use rustc_middle::ty::fast_reject::{DeepRejectCtxt, TreatParams};
 use rustc_middle::ty::fold::BottomUpFolder;
-use rustc_middle::ty::print::{FmtPrinter, Print};
+use rustc_middle::ty::print::{with_no_trimmed_paths, FmtPrinter, Print};
 use rustc_middle::ty::relate::TypeRelation;
 use rustc_middle::ty::SubstsRef;
 use rustc_middle::ty::{self, EarlyBinder, PolyProjectionPredicate, ToPolyTraitRef, ToPredicate};
 use rustc_middle::ty::{Ty, TyCtxt, TypeFoldable, TypeVisitable};
-use rustc_span::symbol::sym;
+use rustc_session::trait_dumps::SelectionTreeNode;
+use rustc_session::Limit;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::Span;
 
 use std::cell::{Cell, RefCell};
 use std::cmp;
 use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
 use std::iter;
+use std::mem;
 
 pub use rustc_middle::traits::select::*;
 
 mod candidate_assembly;
 mod confirmation;
+mod dump;
+mod proof_tree;
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
-pub enum IntercrateAmbiguityCause {
-    DownstreamCrate { trait_desc: String, self_desc: Option<String> },
-    UpstreamCrateUpdate { trait_desc: String, self_desc: Option<String> },
+pub use self::dump::{dump_proof_trees, dump_selection_tree};
+
+/// Why coherence could not rule out an overlap between two impls.
+///
+/// The causes are recorded during selection and only rendered once an
+/// overlap error is actually reported, so they hold the trait reference
+/// and self type rather than pre-printed strings.
+///
+/// The same cause is usually found through several obligations, so the
+/// spans are not part of its identity: equality and hashing only look at
+/// what the rendered note says, see `IntercrateAmbiguityCause::key`.
+#[derive(Clone, Debug)]
+pub enum IntercrateAmbiguityCause<'tcx> {
+    /// A downstream crate may implement `trait_ref`.
+    DownstreamCrate {
+        trait_ref: ty::TraitRef<'tcx>,
+        /// The self type, if it has a concrete skeleton worth mentioning.
+        self_ty: Option<Ty<'tcx>>,
+        /// The span of the impl or bound that required `trait_ref`.
+        span: Span,
+    },
+    /// An upstream crate may add an impl of `trait_ref` in a future version.
+    UpstreamCrateUpdate { trait_ref: ty::TraitRef<'tcx>, self_ty: Option<Ty<'tcx>>, span: Span },
+    /// A `#[rustc_reservation_impl]` applies, with its attached message.
     ReservationImpl { message: String },
 }
 
-impl IntercrateAmbiguityCause {
+impl<'tcx> PartialEq for IntercrateAmbiguityCause<'tcx> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<'tcx> Eq for IntercrateAmbiguityCause<'tcx> {}
+
+impl<'tcx> Hash for IntercrateAmbiguityCause<'tcx> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+impl<'tcx> IntercrateAmbiguityCause<'tcx> {
+    /// The part of the cause that is used for deduplication, which leaves
+    /// out the span of the obligation it was found through.
+    fn key(&self) -> (u8, Option<(ty::TraitRef<'tcx>, Option<Ty<'tcx>>)>, Option<&str>) {
+        match self {
+            IntercrateAmbiguityCause::DownstreamCrate { trait_ref, self_ty, span: _ } => {
+                (0, Some((*trait_ref, *self_ty)), None)
+            }
+            IntercrateAmbiguityCause::UpstreamCrateUpdate { trait_ref, self_ty, span: _ } => {
+                (1, Some((*trait_ref, *self_ty)), None)
+            }
+            IntercrateAmbiguityCause::ReservationImpl { message } => (2, None, Some(message)),
+        }
+    }
+
     /// Emits notes when the overlap is caused by complex intercrate ambiguities.
-    /// See #23980 for details.
+    /// See #23980 for details. The note points at the impl that required the
+    /// ambiguous trait reference, if it is known.
     pub fn add_intercrate_ambiguity_hint(&self, err: &mut Diagnostic) {
-        err.note(&self.intercrate_ambiguity_hint());
+        match self.span() {
+            Some(span) => err.span_note(span, &self.intercrate_ambiguity_hint()),
+            None => err.note(&self.intercrate_ambiguity_hint()),
+        };
     }
 
-    pub fn intercrate_ambiguity_hint(&self) -> String {
+    fn span(&self) -> Option<Span> {
         match self {
-            IntercrateAmbiguityCause::DownstreamCrate { trait_desc, self_desc } => {
-                let self_desc = if let Some(ty) = self_desc {
+            IntercrateAmbiguityCause::DownstreamCrate { span, .. }
+            | IntercrateAmbiguityCause::UpstreamCrateUpdate { span, .. }
+                if !span.is_dummy() =>
+            {
+                Some(*span)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn intercrate_ambiguity_hint(&self) -> String {
+        let describe = |trait_ref: &ty::TraitRef<'tcx>, self_ty: &Option<Ty<'tcx>>| {
+            with_no_trimmed_paths!({
+                let trait_desc = trait_ref.print_only_trait_path().to_string();
+                let self_desc = if let Some(ty) = self_ty {
                     format!(" for type `{}`", ty)
                 } else {
                     String::new()
                 };
+                (trait_desc, self_desc)
+            })
+        };
+        match self {
+            IntercrateAmbiguityCause::DownstreamCrate { trait_ref, self_ty, .. } => {
+                let (trait_desc, self_desc) = describe(trait_ref, self_ty);
                 format!("downstream crates may implement trait `{}`{}", trait_desc, self_desc)
             }
-            IntercrateAmbiguityCause::UpstreamCrateUpdate { trait_desc, self_desc } => {
-                let self_desc = if let Some(ty) = self_desc {
-                    format!(" for type `{}`", ty)
-                } else {
-                    String::new()
-                };
+            IntercrateAmbiguityCause::UpstreamCrateUpdate { trait_ref, self_ty, .. } => {
+                let (trait_desc, self_desc) = describe(trait_ref, self_ty);
                 format!(
                     "upstream crates may add a new impl of trait `{}`{} \
                      in future versions",
@@ -100,6 +175,44 @@ pub fn intercrate_ambiguity_hint(&self) -> String {
     }
 }
 
+/// How a constituent type of an auto trait obligation is reached from the type
+/// it is a part of, see `SelectionContext::constituent_types_with_access`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, TypeFoldable, TypeVisitable)]
+pub enum ConstituentAccess {
+    /// A field of a struct, union or enum variant. `variant` is only set for enums.
+    Field { variant: Option<Symbol>, name: Symbol },
+    /// A positional field of a tuple.
+    TupleField(usize),
+    /// The element type of an array or slice.
+    Element,
+    /// The pointee of a reference or raw pointer.
+    Pointee,
+    /// The type argument of `PhantomData`.
+    PhantomData,
+    /// The tupled captured variables of a closure or generator.
+    Upvars,
+    /// The witness of a generator, i.e. the types it holds across suspension points.
+    Witness,
+    /// A type held by a generator across a suspension point.
+    HeldAcrossYield,
+    /// The hidden type of an opaque type.
+    HiddenType,
+}
+
+/// Why an impl is not a candidate for an obligation, see
+/// `SelectionContext::enable_tracking_rejected_impls`.
+#[derive(Clone, Debug)]
+pub enum ImplRejection<'tcx> {
+    /// The impl header does not unify with the obligation.
+    HeaderMismatch,
+    /// The impl is negative while the obligation is positive, or the other way around.
+    Polarity,
+    /// The obligation is `~const`, but the impl is not `impl const`.
+    NotConst,
+    /// The where clauses of the impl which do not hold for the obligation.
+    Unsatisfied(Vec<PredicateObligation<'tcx>>),
+}
+
 pub struct SelectionContext<'cx, 'tcx> {
     infcx: &'cx InferCtxt<'tcx>,
 
@@ -110,37 +223,74 @@ pub struct SelectionContext<'cx, 'tcx> {
     /// require themselves.
     freshener: TypeFreshener<'cx, 'tcx>,
 
-    /// During coherence we have to assume that other crates may add
-    /// additional impls which we currently don't know about.
-    ///
-    /// To deal with this evaluation should be conservative
-    /// and consider the possibility of impls from outside this crate.
-    /// This comes up primarily when resolving ambiguity. Imagine
-    /// there is some trait reference `$0: Bar` where `$0` is an
-    /// inference variable. If `intercrate` is true, then we can never
-    /// say for sure that this reference is not implemented, even if
-    /// there are *no impls at all for `Bar`*, because `$0` could be
-    /// bound to some type that in a downstream crate that implements
-    /// `Bar`.
-    ///
-    /// Outside of coherence we set this to false because we are only
-    /// interested in types that the user could actually have written.
-    /// In other words, we consider `$0: Bar` to be unimplemented if
-    /// there is no type that the user could *actually name* that
-    /// would satisfy it. This avoids crippling inference, basically.
-    intercrate: bool,
-    /// If `intercrate` is set, we remember predicates which were
-    /// considered ambiguous because of impls potentially added in other crates.
-    /// This is used in coherence to give improved diagnostics.
-    /// We don't do his until we detect a coherence error because it can
-    /// lead to false overflow results (#47139) and because always
-    /// computing it may negatively impact performance.
-    intercrate_ambiguity_causes: Option<FxIndexSet<IntercrateAmbiguityCause>>,
+    /// Whether we are selecting during coherence, see [`CoherenceMode`].
+    coherence_mode: CoherenceMode,
+    /// If the coherence mode collects ambiguity causes, we remember predicates
+    /// which were considered ambiguous because of impls potentially added in
+    /// other crates.
+    intercrate_ambiguity_causes: FxIndexSet<IntercrateAmbiguityCause<'tcx>>,
+
+    /// If this is `Some`, we remember the supertraits of the principal trait of
+    /// object types which have the trait of an obligation but do not match its
+    /// generic arguments. This is used in error reporting to tell which
+    /// instantiation of a trait an object type provides instead.
+    object_supertrait_mismatches: Option<Vec<ty::PolyTraitRef<'tcx>>>,
+
+    /// If this is `Some`, we remember the impls of the trait which were not
+    /// selected for the root obligation and why. This is used in error
+    /// reporting to explain why an impl a user expects to apply does not.
+    rejected_impls: Option<Vec<(DefId, ImplRejection<'tcx>)>>,
 
     /// The mode that trait queries run in, which informs our error handling
     /// policy. In essence, canonicalized queries need their errors propagated
     /// rather than immediately reported because we do not have accurate spans.
     query_mode: TraitQueryMode,
+
+    /// With `-Zdump-selection-tree`, the candidates assembled for the
+    /// obligation currently being selected, or `None` if it was selected
+    /// from the cache.
+    dumped_candidates: Option<Vec<String>>,
+
+    /// The recursion limit that `check_recursion_limit` enforces. This is the
+    /// crate's recursion limit unless the context was created with a lower one
+    /// through `with_recursion_limit`.
+    recursion_limit: Limit,
+}
+
+/// Whether a [`SelectionContext`] is used to check coherence.
+///
+/// During coherence we have to assume that other crates may add
+/// additional impls which we currently don't know about.
+///
+/// To deal with this evaluation should be conservative
+/// and consider the possibility of impls from outside this crate.
+/// This comes up primarily when resolving ambiguity. Imagine
+/// there is some trait reference `$0: Bar` where `$0` is an
+/// inference variable. In coherence mode we can never
+/// say for sure that this reference is not implemented, even if
+/// there are *no impls at all for `Bar`*, because `$0` could be
+/// bound to some type that in a downstream crate that implements
+/// `Bar`.
+///
+/// Outside of coherence we are only interested in types that the user
+/// could actually have written. In other words, we consider `$0: Bar`
+/// to be unimplemented if there is no type that the user could
+/// *actually name* that would satisfy it. This avoids crippling
+/// inference, basically.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CoherenceMode {
+    /// Regular selection, outside of coherence.
+    No,
+    /// Selection during coherence.
+    Yes {
+        /// Whether to remember the predicates which were considered ambiguous
+        /// because of impls potentially added in other crates, to explain
+        /// coherence errors. This is only done when replaying the check of an
+        /// overlap we already detected, as it can lead to false overflow
+        /// results (#47139) and because always computing it may negatively
+        /// impact performance.
+        collect_ambiguity_causes: bool,
+    },
 }
 
 // A stack that walks back up the stack frame.
@@ -201,6 +351,16 @@ struct SelectionCandidateSet<'tcx> {
     // of the input types are type variables, in which case there are
     // various "builtin" rules that might or might not trigger.
     ambiguous: bool,
+
+    // Blanket impls that were skipped because one of the bounds on their
+    // self type parameter can trivially never hold for the obligation's
+    // self type. These are only matched against the obligation if nothing
+    // else applies, so that we still blame the unsatisfied bound of the impl.
+    rejected_blanket_impls: Vec<DefId>,
+
+    // Impls whose header does not unify with the obligation. These are only
+    // collected while rejected impls are tracked.
+    mismatched_impls: Vec<DefId>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -226,14 +386,46 @@ pub fn new(infcx: &'cx InferCtxt<'tcx>) -> SelectionContext<'cx, 'tcx> {
         SelectionContext {
             infcx,
             freshener: infcx.freshener_keep_static(),
-            intercrate: false,
-            intercrate_ambiguity_causes: None,
+            coherence_mode: CoherenceMode::No,
+            intercrate_ambiguity_causes: FxIndexSet::default(),
+            object_supertrait_mismatches: None,
+            rejected_impls: None,
             query_mode: TraitQueryMode::Standard,
+            dumped_candidates: Some(Vec::new()),
+            recursion_limit: infcx.tcx.recursion_limit(),
         }
     }
 
+    /// Creates a selection context that reports overflow once obligations are
+    /// nested more than `limit` levels deep. The limit cannot be raised above the
+    /// crate's recursion limit this way.
+    pub fn with_recursion_limit(
+        infcx: &'cx InferCtxt<'tcx>,
+        limit: Limit,
+    ) -> SelectionContext<'cx, 'tcx> {
+        let recursion_limit = Limit::new(cmp::min(limit.0, infcx.tcx.recursion_limit().0));
+        debug!(?recursion_limit, "with_recursion_limit");
+        SelectionContext { recursion_limit, ..SelectionContext::new(infcx) }
+    }
+
+    /// The recursion limit obligations selected with this context are checked against.
+    pub fn recursion_limit(&self) -> Limit {
+        self.recursion_limit
+    }
+
     pub fn intercrate(infcx: &'cx InferCtxt<'tcx>) -> SelectionContext<'cx, 'tcx> {
-        SelectionContext { intercrate: true, ..SelectionContext::new(infcx) }
+        SelectionContext::with_coherence_mode(
+            infcx,
+            CoherenceMode::Yes { collect_ambiguity_causes: false },
+        )
+    }
+
+    pub fn with_coherence_mode(
+        infcx: &'cx InferCtxt<'tcx>,
+        coherence_mode: CoherenceMode,
+    ) -> SelectionContext<'cx, 'tcx> {
+        debug!(?coherence_mode, "with_coherence_mode");
+        SelectionContext { coherence_mode, ..SelectionContext::new(infcx) }
     }
 
     pub fn with_query_mode(
@@ -244,21 +436,107 @@ pub fn with_query_mode(
         SelectionContext { query_mode, ..SelectionContext::new(infcx) }
     }
 
-    /// Enables tracking of intercrate ambiguity causes. See
-    /// the documentation of [`Self::intercrate_ambiguity_causes`] for more.
-    pub fn enable_tracking_intercrate_ambiguity_causes(&mut self) {
-        assert!(self.intercrate);
-        assert!(self.intercrate_ambiguity_causes.is_none());
-        self.intercrate_ambiguity_causes = Some(FxIndexSet::default());
-        debug!("selcx: enable_tracking_intercrate_ambiguity_causes");
+    /// Gets the intercrate ambiguity causes collected so far. If the coherence
+    /// mode does not collect them, just returns an empty set.
+    pub fn take_intercrate_ambiguity_causes(
+        &mut self,
+    ) -> FxIndexSet<IntercrateAmbiguityCause<'tcx>> {
+        assert!(self.is_intercrate());
+        mem::take(&mut self.intercrate_ambiguity_causes)
+    }
+
+    /// Records `cause` if the coherence mode collects ambiguity causes.
+    fn add_intercrate_ambiguity_cause(&mut self, cause: IntercrateAmbiguityCause<'tcx>) {
+        if self.collects_intercrate_ambiguity_causes() {
+            debug!(?cause, "add_intercrate_ambiguity_cause");
+            self.intercrate_ambiguity_causes.insert(cause);
+        }
+    }
+
+    /// Enables tracking of the supertraits of object types which only
+    /// mismatch the trait-ref of an obligation in their generic arguments.
+    pub fn enable_tracking_object_supertrait_mismatches(&mut self) {
+        assert!(self.object_supertrait_mismatches.is_none());
+        self.object_supertrait_mismatches = Some(Vec::new());
+    }
+
+    /// Gets the supertraits collected since tracking was enabled with
+    /// [`Self::enable_tracking_object_supertrait_mismatches`] and disables
+    /// tracking at the same time.
+    pub fn take_object_supertrait_mismatches(&mut self) -> Vec<ty::PolyTraitRef<'tcx>> {
+        self.object_supertrait_mismatches.take().unwrap_or_default()
+    }
+
+    /// Enables tracking of the impls which are rejected for the root
+    /// obligation of the next selection, along with the reason.
+    pub fn enable_tracking_rejected_impls(&mut self) {
+        assert!(self.rejected_impls.is_none());
+        self.rejected_impls = Some(Vec::new());
+    }
+
+    /// Gets the impls rejected since tracking was enabled with
+    /// [`Self::enable_tracking_rejected_impls`] and disables tracking at
+    /// the same time.
+    pub fn take_rejected_impls(&mut self) -> Vec<(DefId, ImplRejection<'tcx>)> {
+        self.rejected_impls.take().unwrap_or_default()
     }
 
-    /// Gets the intercrate ambiguity causes collected since tracking
-    /// was enabled and disables tracking at the same time. If
-    /// tracking is not enabled, just returns an empty vector.
-    pub fn take_intercrate_ambiguity_causes(&mut self) -> FxIndexSet<IntercrateAmbiguityCause> {
-        assert!(self.intercrate);
-        self.intercrate_ambiguity_causes.take().unwrap_or_default()
+    /// Whether the impls rejected for the obligation of `stack` are tracked.
+    /// Only the root obligation is tracked, not the nested obligations which
+    /// are evaluated while selecting it.
+    fn tracks_rejected_impls(&self, stack: &TraitObligationStack<'_, 'tcx>) -> bool {
+        stack.depth == 1 && self.rejected_impls.is_some()
+    }
+
+    /// Records that `impl_def_id` was rejected for the obligation of `stack`
+    /// if that is tracked. The reason is only computed in that case.
+    fn record_rejected_impl(
+        &mut self,
+        stack: &TraitObligationStack<'_, 'tcx>,
+        impl_def_id: DefId,
+        rejection: impl FnOnce(&mut Self) -> ImplRejection<'tcx>,
+    ) {
+        if self.tracks_rejected_impls(stack) {
+            let rejection = rejection(self);
+            debug!(?impl_def_id, ?rejection, "record_rejected_impl");
+            self.rejected_impls.as_mut().unwrap().push((impl_def_id, rejection));
+        }
+    }
+
+    /// Works out which where clauses of `impl_def_id` do not hold for the
+    /// obligation of `stack`. All of them are evaluated, so that we do not
+    /// only blame the first one.
+    fn impl_rejection(
+        &mut self,
+        stack: &TraitObligationStack<'_, 'tcx>,
+        impl_def_id: DefId,
+    ) -> ImplRejection<'tcx> {
+        let obligation = stack.obligation;
+        self.infcx.probe(|_| {
+            let impl_trait_ref = self.tcx().bound_impl_trait_ref(impl_def_id).unwrap();
+            let Ok(substs) = self.match_impl(impl_def_id, impl_trait_ref, obligation) else {
+                return ImplRejection::HeaderMismatch;
+            };
+            let impl_source = self.vtable_impl(
+                impl_def_id,
+                substs,
+                &obligation.cause,
+                obligation.recursion_depth + 1,
+                obligation.param_env,
+                obligation.predicate,
+            );
+            let mut unsatisfied = vec![];
+            for nested in impl_source.nested {
+                let eval = self.evaluation_probe(|this| {
+                    this.evaluate_predicate_recursively(stack.list(), nested.clone())
+                });
+                // Overflow is reported on its own, so it is not blamed on the impl.
+                if matches!(eval, Ok(eval) if !eval.may_apply()) {
+                    unsatisfied.push(self.infcx.resolve_vars_if_possible(nested));
+                }
+            }
+            ImplRejection::Unsatisfied(unsatisfied)
+        })
     }
 
     pub fn infcx(&self) -> &'cx InferCtxt<'tcx> {
@@ -270,7 +548,11 @@ pub fn tcx(&self) -> TyCtxt<'tcx> {
     }
 
     pub fn is_intercrate(&self) -> bool {
-        self.intercrate
+        self.coherence_mode != CoherenceMode::No
+    }
+
+    fn collects_intercrate_ambiguity_causes(&self) -> bool {
+        self.coherence_mode == CoherenceMode::Yes { collect_ambiguity_causes: true }
     }
 
     ///////////////////////////////////////////////////////////////////////////
@@ -294,6 +576,20 @@ pub fn is_intercrate(&self) -> bool {
     pub fn select(
         &mut self,
         obligation: &TraitObligation<'tcx>,
+    ) -> SelectionResult<'tcx, Selection<'tcx>> {
+        if self.tcx().sess.opts.unstable_opts.dump_selection_tree.is_none() {
+            return self.select_and_confirm(obligation);
+        }
+
+        self.dumped_candidates = Some(Vec::new());
+        let result = self.select_and_confirm(obligation);
+        with_no_trimmed_paths!(self.record_selection_tree_node(obligation, &result));
+        result
+    }
+
+    fn select_and_confirm(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
     ) -> SelectionResult<'tcx, Selection<'tcx>> {
         let candidate = match self.select_from_obligation(obligation) {
             Err(SelectionError::Overflow(OverflowError::Canonical)) => {
@@ -321,6 +617,57 @@ pub fn select(
         }
     }
 
+    /// Records the outcome of selecting `obligation` for `-Zdump-selection-tree`.
+    fn record_selection_tree_node(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
+        result: &SelectionResult<'tcx, Selection<'tcx>>,
+    ) {
+        let tcx = self.tcx();
+        let (result, nested) = match result {
+            Ok(Some(impl_source)) => {
+                let description = self.describe_impl_source(impl_source);
+                let nested = impl_source
+                    .borrow_nested_obligations()
+                    .iter()
+                    .map(|o| self.infcx.resolve_vars_if_possible(o.predicate).to_string())
+                    .collect();
+                (description, nested)
+            }
+            Ok(None) => ("ambiguous".to_string(), vec![]),
+            Err(e) => (format!("error: {e:?}"), vec![]),
+        };
+        let obligation = self.infcx.resolve_vars_if_possible(obligation.predicate).to_string();
+        tcx.sess.trait_dumps.selection_tree.lock().push(SelectionTreeNode {
+            obligation,
+            candidates: mem::take(&mut self.dumped_candidates),
+            result,
+            nested,
+        });
+    }
+
+    /// Describes the kind of candidate `impl_source` was confirmed from, for
+    /// `-Zdump-selection-tree` and `-Zdump-proof-tree`.
+    fn describe_impl_source(&self, impl_source: &Selection<'tcx>) -> String {
+        match impl_source {
+            ImplSource::UserDefined(data) => {
+                format!("impl {}", self.tcx().def_path_str(data.impl_def_id))
+            }
+            ImplSource::AutoImpl(_) => "auto impl".to_string(),
+            ImplSource::Param(..) => "where-clause".to_string(),
+            ImplSource::Object(_) => "object candidate".to_string(),
+            ImplSource::Builtin(_)
+            | ImplSource::DiscriminantKind(_)
+            | ImplSource::Pointee(_)
+            | ImplSource::ConstDestruct(_) => "builtin impl".to_string(),
+            ImplSource::TraitUpcasting(_) => "trait upcasting".to_string(),
+            ImplSource::Closure(_) => "closure".to_string(),
+            ImplSource::FnPointer(_) => "fn pointer".to_string(),
+            ImplSource::Generator(_) => "generator".to_string(),
+            ImplSource::TraitAlias(_) => "trait alias".to_string(),
+        }
+    }
+
     pub(crate) fn select_from_obligation(
         &mut self,
         obligation: &TraitObligation<'tcx>,
@@ -333,6 +680,93 @@ pub(crate) fn select_from_obligation(
         self.candidate_from_obligation(&stack)
     }
 
+    /// Returns the candidates for an ambiguous `obligation` which are left after
+    /// winnowing, so that error reporting can explain why selection gave up.
+    /// Returns no candidates if the ambiguity is found before winnowing.
+    pub(crate) fn winnowed_candidates_for_ambiguity(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
+    ) -> Result<Vec<SelectionCandidate<'tcx>>, SelectionError<'tcx>> {
+        debug_assert!(!obligation.predicate.has_escaping_bound_vars());
+
+        let pec = &ProvisionalEvaluationCache::default();
+        let stack = self.push_stack(TraitObligationStackList::empty(pec), obligation);
+
+        let candidate_set = self.assemble_candidates(&stack)?;
+        if candidate_set.ambiguous {
+            return Ok(vec![]);
+        }
+
+        let candidates = self.filter_impls(candidate_set.vec, &stack);
+        let candidates = self.winnow_candidates(&stack, candidates, false)?;
+        Ok(candidates.into_iter().map(|c| c.candidate).collect())
+    }
+
+    /// Evaluates `candidates`, dropping the ones that cannot apply and the ones
+    /// made redundant by another candidate, including through specialization.
+    /// If `stop_at_ambiguity` is set, this returns as soon as two candidates
+    /// are known to be retained, as the caller then gives up anyway.
+    fn winnow_candidates(
+        &mut self,
+        stack: &TraitObligationStack<'_, 'tcx>,
+        candidates: Vec<SelectionCandidate<'tcx>>,
+        stop_at_ambiguity: bool,
+    ) -> Result<Vec<EvaluatedCandidate<'tcx>>, SelectionError<'tcx>> {
+        let mut candidates = candidates
+            .into_iter()
+            .map(|c| match self.evaluate_candidate(stack, &c) {
+                Ok(eval) if eval.may_apply() => {
+                    Ok(Some(EvaluatedCandidate { candidate: c, evaluation: eval }))
+                }
+                Ok(_) => {
+                    if let ImplCandidate(impl_def_id) = c {
+                        self.record_rejected_impl(stack, impl_def_id, |this| {
+                            this.impl_rejection(stack, impl_def_id)
+                        });
+                    }
+                    Ok(None)
+                }
+                Err(OverflowError::Canonical) => Err(Overflow(OverflowError::Canonical)),
+                Err(OverflowError::ErrorReporting) => Err(ErrorReporting),
+                Err(OverflowError::Error(e)) => Err(Overflow(OverflowError::Error(e))),
+            })
+            .flat_map(Result::transpose)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        debug!(?stack, ?candidates, "winnowed to {} candidates", candidates.len());
+
+        let needs_infer = stack.obligation.predicate.has_non_region_infer();
+
+        // If there are STILL multiple candidates, we can further
+        // reduce the list by dropping duplicates -- including
+        // resolving specializations.
+        if candidates.len() > 1 {
+            let mut i = 0;
+            while i < candidates.len() {
+                let is_dup = (0..candidates.len()).filter(|&j| i != j).any(|j| {
+                    self.candidate_should_be_dropped_in_favor_of(
+                        &candidates[i],
+                        &candidates[j],
+                        needs_infer,
+                    )
+                });
+                if is_dup {
+                    debug!(candidate = ?candidates[i], "Dropping candidate #{}/{}", i, candidates.len());
+                    candidates.swap_remove(i);
+                } else {
+                    debug!(candidate = ?candidates[i], "Retaining candidate #{}/{}", i, candidates.len());
+                    i += 1;
+
+                    if stop_at_ambiguity && i > 1 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(candidates)
+    }
+
     ///////////////////////////////////////////////////////////////////////////
     // EVALUATION
     //
@@ -436,8 +870,8 @@ fn evaluate_predicate_recursively<'o>(
         // use any `Option` combinator method that would force them to be
         // the same.
         match previous_stack.head() {
-            Some(h) => self.check_recursion_limit(&obligation, h.obligation)?,
-            None => self.check_recursion_limit(&obligation, &obligation)?,
+            Some(h) => self.check_recursion_limit(&obligation, h.obligation, previous_stack)?,
+            None => self.check_recursion_limit(&obligation, &obligation, previous_stack)?,
         }
 
         ensure_sufficient_stack(|| {
@@ -751,7 +1185,7 @@ fn evaluate_trait_predicate_recursively<'o>(
         previous_stack: TraitObligationStackList<'o, 'tcx>,
         mut obligation: TraitObligation<'tcx>,
     ) -> Result<EvaluationResult, OverflowError> {
-        if !self.intercrate
+        if !self.is_intercrate()
             && obligation.is_global()
             && obligation.param_env.caller_bounds().iter().all(|bound| bound.needs_subst())
         {
@@ -1014,7 +1448,10 @@ fn check_evaluation_cache(
         // mode, so don't do any caching. In particular, we might
         // re-use the same `InferCtxt` with both an intercrate
         // and non-intercrate `SelectionContext`
-        if self.intercrate {
+        if self.is_intercrate() {
+            return None;
+        }
+        if self.is_opaque_in_defining_scope(trait_pred.skip_binder().self_ty()) {
             return None;
         }
 
@@ -1044,7 +1481,10 @@ fn insert_evaluation_cache(
         // mode, so don't do any caching. In particular, we might
         // re-use the same `InferCtxt` with both an intercrate
         // and non-intercrate `SelectionContext`
-        if self.intercrate {
+        if self.is_intercrate() {
+            return;
+        }
+        if self.is_opaque_in_defining_scope(trait_pred.skip_binder().self_ty()) {
             return;
         }
 
@@ -1086,6 +1526,7 @@ fn check_recursion_depth<T>(
         &self,
         depth: usize,
         error_obligation: &Obligation<'tcx, T>,
+        stack: TraitObligationStackList<'_, 'tcx>,
     ) -> Result<(), OverflowError>
     where
         T: fmt::Display
@@ -1093,13 +1534,16 @@ fn check_recursion_depth<T>(
             + Print<'tcx, FmtPrinter<'tcx, 'tcx>, Output = FmtPrinter<'tcx, 'tcx>>,
         <T as Print<'tcx, FmtPrinter<'tcx, 'tcx>>>::Error: std::fmt::Debug,
     {
-        if !self.infcx.tcx.recursion_limit().value_within_limit(depth) {
+        if !self.recursion_limit.value_within_limit(depth) {
             match self.query_mode {
                 TraitQueryMode::Standard => {
                     if let Some(e) = self.infcx.tainted_by_errors() {
                         return Err(OverflowError::Error(e));
                     }
-                    self.infcx.err_ctxt().report_overflow_error(error_obligation, true);
+                    let stack: Vec<_> = stack.map(|stack| stack.obligation.clone()).collect();
+                    self.infcx
+                        .err_ctxt()
+                        .report_overflow_error_with_stack(error_obligation, &stack);
                 }
                 TraitQueryMode::Canonical => {
                     return Err(OverflowError::Canonical);
@@ -1118,6 +1562,7 @@ fn check_recursion_limit<T: Display + TypeFoldable<'tcx>, V>(
         &self,
         obligation: &Obligation<'tcx, T>,
         error_obligation: &Obligation<'tcx, V>,
+        stack: TraitObligationStackList<'_, 'tcx>,
     ) -> Result<(), OverflowError>
     where
         V: fmt::Display
@@ -1125,7 +1570,7 @@ fn check_recursion_limit<T: Display + TypeFoldable<'tcx>, V>(
             + Print<'tcx, FmtPrinter<'tcx, 'tcx>, Output = FmtPrinter<'tcx, 'tcx>>,
         <V as Print<'tcx, FmtPrinter<'tcx, 'tcx>>>::Error: std::fmt::Debug,
     {
-        self.check_recursion_depth(obligation.recursion_depth, error_obligation)
+        self.check_recursion_depth(obligation.recursion_depth, error_obligation, stack)
     }
 
     fn in_task<OP, R>(&mut self, op: OP) -> (R, DepNodeIndex)
@@ -1144,35 +1589,20 @@ fn in_task<OP, R>(&mut self, op: OP) -> (R, DepNodeIndex)
     fn filter_impls(
         &mut self,
         candidates: Vec<SelectionCandidate<'tcx>>,
-        obligation: &TraitObligation<'tcx>,
+        stack: &TraitObligationStack<'_, 'tcx>,
     ) -> Vec<SelectionCandidate<'tcx>> {
         trace!("{candidates:#?}");
         let tcx = self.tcx();
+        let obligation = stack.obligation;
         let mut result = Vec::with_capacity(candidates.len());
 
         for candidate in candidates {
             // Respect const trait obligations
-            if obligation.is_const() {
-                match candidate {
-                    // const impl
-                    ImplCandidate(def_id) if tcx.constness(def_id) == hir::Constness::Const => {}
-                    // const param
-                    ParamCandidate(trait_pred) if trait_pred.is_const_if_const() => {}
-                    // const projection
-                    ProjectionCandidate(_, ty::BoundConstness::ConstIfConst) => {}
-                    // auto trait impl
-                    AutoImplCandidate => {}
-                    // generator, this will raise error in other places
-                    // or ignore error with const_async_blocks feature
-                    GeneratorCandidate => {}
-                    // FnDef where the function is const
-                    FnPointerCandidate { is_const: true } => {}
-                    ConstDestructCandidate(_) => {}
-                    _ => {
-                        // reject all other types of candidates
-                        continue;
-                    }
+            if obligation.is_const() && !self.candidate_may_be_const(&candidate) {
+                if let ImplCandidate(def_id) = candidate {
+                    self.record_rejected_impl(stack, def_id, |_| ImplRejection::NotConst);
                 }
+                continue;
             }
 
             if let ImplCandidate(def_id) = candidate {
@@ -1180,6 +1610,8 @@ fn filter_impls(
                     || obligation.polarity() == tcx.impl_polarity(def_id)
                 {
                     result.push(candidate);
+                } else {
+                    self.record_rejected_impl(stack, def_id, |_| ImplRejection::Polarity);
                 }
             } else {
                 result.push(candidate);
@@ -1190,6 +1622,28 @@ fn filter_impls(
         result
     }
 
+    /// Returns whether `candidate` can prove a `~const` obligation.
+    fn candidate_may_be_const(&self, candidate: &SelectionCandidate<'tcx>) -> bool {
+        match *candidate {
+            // const impl
+            ImplCandidate(def_id) => self.tcx().constness(def_id) == hir::Constness::Const,
+            // const param
+            ParamCandidate(trait_pred) => trait_pred.is_const_if_const(),
+            // const projection
+            ProjectionCandidate(_, constness) => constness == ty::BoundConstness::ConstIfConst,
+            // auto trait impl
+            AutoImplCandidate => true,
+            // generator, this will raise error in other places
+            // or ignore error with const_async_blocks feature
+            GeneratorCandidate => true,
+            // FnDef where the function is const
+            FnPointerCandidate { is_const } => is_const,
+            ConstDestructCandidate(_) => true,
+            // reject all other types of candidates
+            _ => false,
+        }
+    }
+
     /// filter_reservation_impls filter reservation impl for any goal as ambiguous
     #[instrument(level = "debug", skip(self))]
     fn filter_reservation_impls(
@@ -1201,7 +1655,7 @@ fn filter_reservation_impls(
         // Treat reservation impls as ambiguity.
         if let ImplCandidate(def_id) = candidate {
             if let ty::ImplPolarity::Reservation = tcx.impl_polarity(def_id) {
-                if let Some(intercrate_ambiguity_clauses) = &mut self.intercrate_ambiguity_causes {
+                if self.collects_intercrate_ambiguity_causes() {
                     let value = tcx
                         .get_attr(def_id, sym::rustc_reservation_impl)
                         .and_then(|a| a.value_str());
@@ -1211,7 +1665,7 @@ fn filter_reservation_impls(
                                  reservation impl ambiguity on {:?}",
                             def_id
                         );
-                        intercrate_ambiguity_clauses.insert(
+                        self.add_intercrate_ambiguity_cause(
                             IntercrateAmbiguityCause::ReservationImpl {
                                 message: value.to_string(),
                             },
@@ -1225,9 +1679,9 @@ fn filter_reservation_impls(
     }
 
     fn is_knowable<'o>(&mut self, stack: &TraitObligationStack<'o, 'tcx>) -> Result<(), Conflict> {
-        debug!("is_knowable(intercrate={:?})", self.intercrate);
+        debug!("is_knowable(coherence_mode={:?})", self.coherence_mode);
 
-        if !self.intercrate || stack.obligation.polarity() == ty::ImplPolarity::Negative {
+        if !self.is_intercrate() || stack.obligation.polarity() == ty::ImplPolarity::Negative {
             return Ok(());
         }
 
@@ -1239,7 +1693,55 @@ fn is_knowable<'o>(&mut self, stack: &TraitObligationStack<'o, 'tcx>) -> Result<
         // bound regions.
         let trait_ref = predicate.skip_binder().trait_ref;
 
-        coherence::trait_ref_is_knowable(self.tcx(), trait_ref)
+        let result = coherence::trait_ref_is_knowable(self.tcx(), trait_ref);
+        if result.is_err() && self.explicit_negative_impl_applies(stack, trait_ref) {
+            debug!("is_knowable: ruled out by an explicit negative impl");
+            return Ok(());
+        }
+        result
+    }
+
+    /// With `feature(with_negative_coherence)`, an explicit `impl !Trait for Type`
+    /// is a promise by its author that `Type: Trait` will never hold: adding a
+    /// positive impl later would conflict with it. So if such an impl definitely
+    /// applies to `trait_ref`, the obligation is knowable even though
+    /// `trait_ref_is_knowable` says otherwise.
+    fn explicit_negative_impl_applies<'o>(
+        &mut self,
+        stack: &TraitObligationStack<'o, 'tcx>,
+        trait_ref: ty::TraitRef<'tcx>,
+    ) -> bool {
+        let tcx = self.tcx();
+        // Another crate could still pick a different type for any inference
+        // variable, so only trait refs that are known up to regions can be ruled out.
+        if !tcx.features().with_negative_coherence || trait_ref.has_non_region_infer() {
+            return false;
+        }
+
+        let obligation = &stack.obligation;
+        let mut applies = false;
+        tcx.for_each_relevant_impl(trait_ref.def_id, trait_ref.self_ty(), |impl_def_id| {
+            if applies || tcx.impl_polarity(impl_def_id) != ty::ImplPolarity::Negative {
+                return;
+            }
+            let impl_trait_ref = tcx.bound_impl_trait_ref(impl_def_id).unwrap();
+            let result = self.evaluation_probe(|this| {
+                let Ok(substs) = this.match_impl(impl_def_id, impl_trait_ref, obligation) else {
+                    return Ok(EvaluatedToErr);
+                };
+                let impl_src = this.vtable_impl(
+                    impl_def_id,
+                    substs,
+                    &obligation.cause,
+                    obligation.recursion_depth + 1,
+                    obligation.param_env,
+                    obligation.predicate,
+                );
+                this.evaluate_predicates_recursively(stack.list(), impl_src.nested.into_iter())
+            });
+            applies = matches!(result, Ok(eval) if eval.must_apply_modulo_regions());
+        });
+        applies
     }
 
     /// Returns `true` if the global caches can be used.
@@ -1258,7 +1760,7 @@ fn can_use_global_caches(&self, param_env: ty::ParamEnv<'tcx>) -> bool {
         // the master cache. Since coherence executes pretty quickly,
         // it's not worth going to more trouble to increase the
         // hit-rate, I don't think.
-        if self.intercrate {
+        if self.is_intercrate() {
             return false;
         }
 
@@ -1266,6 +1768,64 @@ fn can_use_global_caches(&self, param_env: ty::ParamEnv<'tcx>) -> bool {
         true
     }
 
+    /// Returns `true` if `self_ty` is an opaque type in its defining scope and
+    /// `-Zreveal-opaque-types-in-defining-scope` is enabled. Whether an obligation
+    /// with such a self type holds may then depend on the hidden type inferred for
+    /// it so far, so its result is never cached.
+    fn is_opaque_in_defining_scope(&self, self_ty: Ty<'tcx>) -> bool {
+        if !self.tcx().sess.opts.unstable_opts.reveal_opaque_types_in_defining_scope {
+            return false;
+        }
+        match *self_ty.kind() {
+            ty::Opaque(def_id, _) => def_id.as_local().map_or(false, |def_id| {
+                self.infcx.opaque_type_origin(def_id, self.tcx().def_span(def_id)).is_some()
+            }),
+            _ => false,
+        }
+    }
+
+    /// Returns the hidden type inferred so far for `self_ty`, if it is an opaque
+    /// type in its defining scope.
+    pub(super) fn opaque_hidden_type(&self, self_ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+        let ty::Opaque(def_id, substs) = *self.infcx.shallow_resolve(self_ty).kind() else {
+            return None;
+        };
+        let def_id = def_id.as_local()?;
+        self.infcx.opaque_type_origin(def_id, self.tcx().def_span(def_id))?;
+        let key = ty::OpaqueTypeKey { def_id, substs };
+        let inner = self.infcx.inner.borrow();
+        inner.opaque_type_storage.opaque_types.get(&key).map(|decl| decl.hidden_type.ty)
+    }
+
+    /// Normalizes `self_ty` if it is the anonymous associated type of a return-position
+    /// `impl Trait` in a trait and the impl providing it is known, which yields the
+    /// type returned by the method of that impl.
+    pub(super) fn normalize_impl_trait_in_trait(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
+        self_ty: Ty<'tcx>,
+    ) -> Option<Normalized<'tcx, Ty<'tcx>>> {
+        let ty::Projection(data) = *self_ty.kind() else { return None };
+        if self.tcx().def_kind(data.item_def_id) != DefKind::ImplTraitPlaceholder
+            || self_ty.has_escaping_bound_vars()
+        {
+            return None;
+        }
+        let normalized = ensure_sufficient_stack(|| {
+            project::normalize_with_depth(
+                self,
+                obligation.param_env,
+                obligation.cause.clone(),
+                obligation.recursion_depth + 1,
+                self_ty,
+            )
+        });
+        match self.infcx.resolve_vars_if_possible(normalized.value).kind() {
+            ty::Projection(..) | ty::Infer(ty::TyVar(_)) | ty::Error(_) => None,
+            _ => Some(normalized),
+        }
+    }
+
     fn check_candidate_cache(
         &mut self,
         mut param_env: ty::ParamEnv<'tcx>,
@@ -1275,13 +1835,22 @@ fn check_candidate_cache(
         // mode, so don't do any caching. In particular, we might
         // re-use the same `InferCtxt` with both an intercrate
         // and non-intercrate `SelectionContext`
-        if self.intercrate {
+        if self.is_intercrate() {
+            return None;
+        }
+        // The candidates have to be assembled again to track their mismatches
+        // and rejections.
+        if self.object_supertrait_mismatches.is_some() || self.rejected_impls.is_some() {
             return None;
         }
         let tcx = self.tcx();
         let mut pred = cache_fresh_trait_pred.skip_binder();
         pred.remap_constness(&mut param_env);
 
+        if self.is_opaque_in_defining_scope(pred.self_ty()) {
+            return None;
+        }
+
         if self.can_use_global_caches(param_env) {
             if let Some(res) = tcx.selection_cache.get(&(param_env, pred), tcx) {
                 return Some(res);
@@ -1314,7 +1883,7 @@ fn can_cache_candidate(
         // mode, so don't do any caching. In particular, we might
         // re-use the same `InferCtxt` with both an intercrate
         // and non-intercrate `SelectionContext`
-        if self.intercrate {
+        if self.is_intercrate() {
             return false;
         }
         match result {
@@ -1336,7 +1905,8 @@ fn insert_candidate_cache(
 
         pred.remap_constness(&mut param_env);
 
-        if !self.can_cache_candidate(&candidate) {
+        if !self.can_cache_candidate(&candidate) || self.is_opaque_in_defining_scope(pred.self_ty())
+        {
             debug!(?pred, ?candidate, "insert_candidate_cache - candidate is not cacheable");
             return;
         }
@@ -1586,6 +2156,13 @@ fn candidate_should_be_dropped_in_favor_of(
                     when there are other valid candidates"
                 );
             }
+            (_, OpaqueHiddenTypeCandidate | RevealedImplTraitInTraitCandidate)
+            | (OpaqueHiddenTypeCandidate | RevealedImplTraitInTraitCandidate, _) => {
+                bug!(
+                    "candidates revealing a hidden type shouldn't be recorded \
+                    when there are other valid candidates"
+                );
+            }
 
             // FIXME(@jswrenn): this should probably be more sophisticated
             (TransmutabilityCandidate, _) | (_, TransmutabilityCandidate) => false,
@@ -1988,6 +2565,17 @@ fn constituent_types_for_ty(
         &self,
         t: ty::Binder<'tcx, Ty<'tcx>>,
     ) -> ty::Binder<'tcx, Vec<Ty<'tcx>>> {
+        self.constituent_types_with_access(t)
+            .map_bound(|types| types.into_iter().map(|(ty, _)| ty).collect())
+    }
+
+    /// Returns the types an auto trait obligation for `t` is made up of, together with
+    /// how each of them is reached from `t`. Error reporting uses this to render the
+    /// path to the type that does not implement the auto trait.
+    pub fn constituent_types_with_access(
+        &self,
+        t: ty::Binder<'tcx, Ty<'tcx>>,
+    ) -> ty::Binder<'tcx, Vec<(Ty<'tcx>, ConstituentAccess)>> {
         match *t.skip_binder().kind() {
             ty::Uint(_)
             | ty::Int(_)
@@ -2012,44 +2600,68 @@ fn constituent_types_for_ty(
             }
 
             ty::RawPtr(ty::TypeAndMut { ty: element_ty, .. }) | ty::Ref(_, element_ty, _) => {
-                t.rebind(vec![element_ty])
+                t.rebind(vec![(element_ty, ConstituentAccess::Pointee)])
             }
 
-            ty::Array(element_ty, _) | ty::Slice(element_ty) => t.rebind(vec![element_ty]),
+            ty::Array(element_ty, _) | ty::Slice(element_ty) => {
+                t.rebind(vec![(element_ty, ConstituentAccess::Element)])
+            }
 
             ty::Tuple(ref tys) => {
                 // (T1, ..., Tn) -- meets any bound that all of T1...Tn meet
-                t.rebind(tys.iter().collect())
+                t.rebind(
+                    tys.iter()
+                        .enumerate()
+                        .map(|(i, ty)| (ty, ConstituentAccess::TupleField(i)))
+                        .collect(),
+                )
             }
 
             ty::Closure(_, ref substs) => {
                 let ty = self.infcx.shallow_resolve(substs.as_closure().tupled_upvars_ty());
-                t.rebind(vec![ty])
+                t.rebind(vec![(ty, ConstituentAccess::Upvars)])
             }
 
             ty::Generator(_, ref substs, _) => {
                 let ty = self.infcx.shallow_resolve(substs.as_generator().tupled_upvars_ty());
                 let witness = substs.as_generator().witness();
-                t.rebind([ty].into_iter().chain(iter::once(witness)).collect())
+                t.rebind(vec![
+                    (ty, ConstituentAccess::Upvars),
+                    (witness, ConstituentAccess::Witness),
+                ])
             }
 
             ty::GeneratorWitness(types) => {
                 debug_assert!(!types.has_escaping_bound_vars());
-                types.map_bound(|types| types.to_vec())
+                types.map_bound(|types| {
+                    types.iter().map(|ty| (ty, ConstituentAccess::HeldAcrossYield)).collect()
+                })
             }
 
             // For `PhantomData<T>`, we pass `T`.
-            ty::Adt(def, substs) if def.is_phantom_data() => t.rebind(substs.types().collect()),
-
-            ty::Adt(def, substs) => {
-                t.rebind(def.all_fields().map(|f| f.ty(self.tcx(), substs)).collect())
+            ty::Adt(def, substs) if def.is_phantom_data() => {
+                t.rebind(substs.types().map(|ty| (ty, ConstituentAccess::PhantomData)).collect())
             }
 
+            ty::Adt(def, substs) => t.rebind(
+                def.variants()
+                    .iter()
+                    .flat_map(|v| {
+                        let variant = def.is_enum().then_some(v.name);
+                        v.fields.iter().map(move |f| {
+                            let access = ConstituentAccess::Field { variant, name: f.name };
+                            (f.ty(self.tcx(), substs), access)
+                        })
+                    })
+                    .collect(),
+            ),
+
             ty::Opaque(def_id, substs) => {
                 // We can resolve the `impl Trait` to its concrete type,
                 // which enforces a DAG between the functions requiring
                 // the auto trait bounds in question.
-                t.rebind(vec![self.tcx().bound_type_of(def_id).subst(self.tcx(), substs)])
+                let ty = self.tcx().bound_type_of(def_id).subst(self.tcx(), substs);
+                t.rebind(vec![(ty, ConstituentAccess::HiddenType)])
             }
         }
     }
@@ -2165,15 +2777,19 @@ fn match_impl(
         debug!(?impl_trait_ref);
 
         let Normalized { value: impl_trait_ref, obligations: mut nested_obligations } =
-            ensure_sufficient_stack(|| {
-                project::normalize_with_depth(
-                    self,
-                    obligation.param_env,
-                    obligation.cause.clone(),
-                    obligation.recursion_depth + 1,
-                    impl_trait_ref,
-                )
-            });
+            if self.tcx().sess.opts.unstable_opts.lazy_normalization_in_selection {
+                self.defer_impl_header_projections(obligation, impl_trait_ref)
+            } else {
+                ensure_sufficient_stack(|| {
+                    project::normalize_with_depth(
+                        self,
+                        obligation.param_env,
+                        obligation.cause.clone(),
+                        obligation.recursion_depth + 1,
+                        impl_trait_ref,
+                    )
+                })
+            };
 
         debug!(?impl_trait_ref, ?placeholder_obligation_trait_ref);
 
@@ -2191,7 +2807,7 @@ fn match_impl(
             .map_err(|e| debug!("match_impl: failed eq_trait_refs due to `{e}`"))?;
         nested_obligations.extend(obligations);
 
-        if !self.intercrate
+        if !self.is_intercrate()
             && self.tcx().impl_polarity(impl_def_id) == ty::ImplPolarity::Reservation
         {
             debug!("reservation impls only apply in intercrate mode");
@@ -2201,6 +2817,40 @@ fn match_impl(
         Ok(Normalized { value: impl_substs, obligations: nested_obligations })
     }
 
+    /// Replaces every projection in the impl header with a fresh inference variable
+    /// and returns the projection predicates relating them, instead of normalizing
+    /// the header eagerly.
+    ///
+    /// Matching the impl then only relates the unnormalized header with the
+    /// obligation. The projections are normalized once the nested obligations are
+    /// evaluated during winnowing or processed after confirmation, so impls whose
+    /// header never needs to be normalized to be rejected don't pay for it.
+    fn defer_impl_header_projections(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
+        impl_trait_ref: ty::TraitRef<'tcx>,
+    ) -> Normalized<'tcx, ty::TraitRef<'tcx>> {
+        let mut obligations = vec![];
+        let value = impl_trait_ref.fold_with(&mut BottomUpFolder {
+            tcx: self.tcx(),
+            ty_op: |ty| match *ty.kind() {
+                ty::Projection(projection_ty) if !ty.has_escaping_bound_vars() => {
+                    self.infcx.infer_projection(
+                        obligation.param_env,
+                        projection_ty,
+                        obligation.cause.clone(),
+                        obligation.recursion_depth + 1,
+                        &mut obligations,
+                    )
+                }
+                _ => ty,
+            },
+            lt_op: |l| l,
+            ct_op: |c| c,
+        });
+        Normalized { value, obligations }
+    }
+
     fn fast_reject_trait_refs(
         &mut self,
         obligation: &TraitObligation<'tcx>,
@@ -2587,7 +3237,7 @@ fn insert_provisional(
     fn on_failure(&self, dfn: usize) {
         debug!(?dfn, "on_failure");
         self.map.borrow_mut().retain(|key, eval| {
-            if !eval.from_dfn >= dfn {
+            if eval.from_dfn >= dfn {
                 debug!("on_failure: removing {:?}", key);
                 false
             } else {
@@ -0,0 +1,69 @@
+//! Builds proof trees of goals, e.g. for `-Zdump-proof-tree`.
+//!
+//! A trait goal is proven by selecting and confirming a candidate for it and
+//! then proving the nested obligations of that candidate. All other goals, as
+//! well as trait goals that are part of a cycle or exceed the recursion limit,
+//! are leaves whose certainty is taken from evaluating them.
+
+use super::SelectionContext;
+use crate::traits::{Overflow, PredicateObligation};
+use rustc_session::trait_dumps::{ProofTree, ProofTreeCertainty};
+
+impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
+    /// Builds the proof tree of `obligation`.
+    ///
+    /// This happens inside of a probe, so it doesn't constrain any inference
+    /// variables.
+    pub fn proof_tree(&mut self, obligation: &PredicateObligation<'tcx>) -> ProofTree {
+        self.infcx.probe(|_| self.proof_tree_inner(obligation, &mut vec![]))
+    }
+
+    fn proof_tree_inner(
+        &mut self,
+        obligation: &PredicateObligation<'tcx>,
+        stack: &mut Vec<String>,
+    ) -> ProofTree {
+        let tcx = self.tcx();
+        let goal = self.infcx.resolve_vars_if_possible(obligation.predicate).to_string();
+
+        let trait_pred = obligation.predicate.to_opt_poly_trait_pred().filter(|_| {
+            !stack.contains(&goal)
+                && self.recursion_limit().value_within_limit(obligation.recursion_depth)
+        });
+        let Some(trait_pred) = trait_pred else {
+            let certainty = match self.evaluate_root_obligation(obligation) {
+                Ok(result) if result.must_apply_modulo_regions() => ProofTreeCertainty::Yes,
+                Ok(result) if result.may_apply() => ProofTreeCertainty::Maybe,
+                Ok(_) => ProofTreeCertainty::No,
+                Err(_) => ProofTreeCertainty::Maybe,
+            };
+            return ProofTree { goal, candidate: None, nested: vec![], certainty };
+        };
+
+        let trait_obligation = obligation.with(tcx, trait_pred);
+        let impl_source = match self.select_and_confirm(&trait_obligation) {
+            Ok(Some(impl_source)) => impl_source,
+            Ok(None) | Err(Overflow(_)) => {
+                let certainty = ProofTreeCertainty::Maybe;
+                return ProofTree { goal, candidate: None, nested: vec![], certainty };
+            }
+            Err(_) => {
+                let certainty = ProofTreeCertainty::No;
+                return ProofTree { goal, candidate: None, nested: vec![], certainty };
+            }
+        };
+
+        let candidate = Some(self.describe_impl_source(&impl_source));
+        stack.push(goal.clone());
+        let nested: Vec<_> = impl_source
+            .nested_obligations()
+            .iter()
+            .map(|nested| self.proof_tree_inner(nested, stack))
+            .collect();
+        stack.pop();
+
+        let certainty =
+            nested.iter().map(|nested| nested.certainty).fold(ProofTreeCertainty::Yes, Ord::max);
+        ProofTree { goal, candidate, nested, certainty }
+    }
+}
@@ -21,6 +21,7 @@
 use rustc_data_structures::fx::FxIndexSet;
 use rustc_errors::{error_code, DelayDm, Diagnostic};
 use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::ty::trait_def::TraitSpecializationKind;
 use rustc_middle::ty::{self, ImplSubject, Ty, TyCtxt};
 use rustc_middle::ty::{InternalSubsts, SubstsRef};
 use rustc_session::lint::builtin::COHERENCE_LEAK_CHECK;
@@ -36,7 +37,7 @@ pub struct OverlapError<'tcx> {
     pub with_impl: DefId,
     pub trait_ref: ty::TraitRef<'tcx>,
     pub self_ty: Option<Ty<'tcx>>,
-    pub intercrate_ambiguity_causes: FxIndexSet<IntercrateAmbiguityCause>,
+    pub intercrate_ambiguity_causes: FxIndexSet<IntercrateAmbiguityCause<'tcx>>,
     pub involves_placeholder: bool,
 }
 
@@ -247,6 +248,9 @@ pub(super) fn specialization_graph_provider(
     trait_impls
         .sort_unstable_by_key(|def_id| (-(def_id.krate.as_u32() as i64), def_id.index.index()));
 
+    let report_all = tcx.sess.opts.unstable_opts.report_all_impl_overlaps;
+    let mut visited_impls = Vec::new();
+
     for impl_def_id in trait_impls {
         if let Some(impl_def_id) = impl_def_id.as_local() {
             // This is where impl overlap checking happens:
@@ -259,17 +263,120 @@ pub(super) fn specialization_graph_provider(
             };
 
             if let Some(overlap) = overlap {
-                report_overlap_conflict(tcx, overlap, impl_def_id, used_to_be_allowed, &mut sg);
+                let additional = if report_all && used_to_be_allowed.is_none() {
+                    additional_overlaps(
+                        tcx,
+                        impl_def_id.to_def_id(),
+                        overlap.with_impl,
+                        &visited_impls,
+                        overlap_mode,
+                    )
+                } else {
+                    Vec::new()
+                };
+                report_overlap_conflict(
+                    tcx,
+                    overlap,
+                    additional,
+                    impl_def_id,
+                    used_to_be_allowed,
+                    &mut sg,
+                );
             }
         } else {
             let parent = tcx.impl_parent(impl_def_id).unwrap_or(trait_id);
             sg.record_impl_from_cstore(tcx, parent, impl_def_id)
         }
+
+        if report_all {
+            visited_impls.push(impl_def_id);
+        }
     }
 
     sg
 }
 
+/// Collects the overlaps of `impl_def_id` with every impl in `earlier_impls`
+/// other than `reported_impl`, for `-Zreport-all-impl-overlaps`.
+///
+/// Only overlaps that would be hard errors on their own are returned, so
+/// impls that are allowed to overlap or that specialize one another are
+/// skipped.
+#[cold]
+#[inline(never)]
+fn additional_overlaps<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    impl_def_id: DefId,
+    reported_impl: DefId,
+    earlier_impls: &[DefId],
+    overlap_mode: specialization_graph::OverlapMode,
+) -> Vec<OverlapError<'tcx>> {
+    let impl_polarity = tcx.impl_polarity(impl_def_id);
+    earlier_impls
+        .iter()
+        .copied()
+        .filter(|&other| other != reported_impl && tcx.impl_polarity(other) == impl_polarity)
+        .filter(|&other| tcx.impls_are_allowed_to_overlap(impl_def_id, other).is_none())
+        .filter_map(|other| {
+            let overlap = traits::overlapping_impls(
+                tcx,
+                other,
+                impl_def_id,
+                traits::SkipLeakCheck::Yes,
+                overlap_mode,
+            )
+            .ok()?;
+            if tcx.specializes((impl_def_id, other)) != tcx.specializes((other, impl_def_id)) {
+                return None;
+            }
+            // Like in `Children::insert`, overlaps which only go away with the
+            // leak check are future-compatibility lints, not errors.
+            traits::overlapping_impls(
+                tcx,
+                other,
+                impl_def_id,
+                traits::SkipLeakCheck::default(),
+                overlap_mode,
+            )
+            .ok()?;
+
+            let trait_ref = overlap.impl_header.trait_ref.unwrap();
+            let self_ty = trait_ref.self_ty();
+            Some(OverlapError {
+                with_impl: other,
+                trait_ref,
+                self_ty: self_ty.has_concrete_skeleton().then_some(self_ty),
+                intercrate_ambiguity_causes: overlap.intercrate_ambiguity_causes,
+                involves_placeholder: overlap.involves_placeholder,
+            })
+        })
+        .collect()
+}
+
+// Query provider for `specialization_dag`.
+pub(super) fn specialization_dag_provider(
+    tcx: TyCtxt<'_>,
+    trait_id: DefId,
+) -> &[specialization_graph::SpecializationDagNode] {
+    let graph = tcx.specialization_graph_of(trait_id);
+    tcx.arena.alloc_from_iter(tcx.all_impls(trait_id).filter_map(|impl_def_id| {
+        // Impls which failed to be inserted into the graph have no parent.
+        let parent = *graph.parent.get(&impl_def_id)?;
+        Some(specialization_graph::SpecializationDagNode {
+            impl_def_id,
+            parent: (parent != trait_id).then_some(parent),
+            always_applicable: tcx.impl_is_always_applicable(impl_def_id),
+        })
+    }))
+}
+
+// Query provider for `reveals_defaults_in_const`.
+pub(super) fn reveals_defaults_in_const(tcx: TyCtxt<'_>, impl_def_id: DefId) -> bool {
+    let trait_def = tcx.trait_def(tcx.trait_id_of_impl(impl_def_id).unwrap());
+    trait_def.specialization_kind == TraitSpecializationKind::AlwaysApplicable
+        || tcx.impl_is_always_applicable(impl_def_id)
+}
+
 // This function is only used when
 // encountering errors and inlining
 // it negatively impacts perf.
@@ -278,6 +385,7 @@ pub(super) fn specialization_graph_provider(
 fn report_overlap_conflict<'tcx>(
     tcx: TyCtxt<'tcx>,
     overlap: OverlapError<'tcx>,
+    additional: Vec<OverlapError<'tcx>>,
     impl_def_id: LocalDefId,
     used_to_be_allowed: Option<FutureCompatOverlapErrorKind>,
     sg: &mut specialization_graph::Graph,
@@ -308,7 +416,14 @@ fn report_overlap_conflict<'tcx>(
         }
 
         _ => {
-            report_conflicting_impls(tcx, overlap, impl_def_id, used_to_be_allowed, sg);
+            report_conflicting_impls(
+                tcx,
+                overlap,
+                &additional,
+                impl_def_id,
+                used_to_be_allowed,
+                sg,
+            );
         }
     }
 }
@@ -334,6 +449,7 @@ fn report_negative_positive_conflict<'tcx>(
 fn report_conflicting_impls<'tcx>(
     tcx: TyCtxt<'tcx>,
     overlap: OverlapError<'tcx>,
+    additional: &[OverlapError<'tcx>],
     impl_def_id: LocalDefId,
     used_to_be_allowed: Option<FutureCompatOverlapErrorKind>,
     sg: &mut specialization_graph::Graph,
@@ -346,6 +462,7 @@ fn report_conflicting_impls<'tcx>(
     fn decorate<'tcx>(
         tcx: TyCtxt<'tcx>,
         overlap: &OverlapError<'tcx>,
+        additional: &[OverlapError<'tcx>],
         impl_span: Span,
         err: &mut Diagnostic,
     ) {
@@ -372,6 +489,21 @@ fn decorate<'tcx>(
             }
         }
 
+        for other in additional {
+            let self_ty = other.self_ty.map_or_else(String::new, |ty| format!(" for `{}`", ty));
+            match tcx.span_of_impl(other.with_impl) {
+                Ok(span) => {
+                    err.span_label(
+                        span,
+                        format!("also conflicts with this implementation{self_ty}"),
+                    );
+                }
+                Err(cname) => {
+                    err.note(&format!("also conflicts with an implementation in crate `{cname}`"));
+                }
+            }
+        }
+
         for cause in &overlap.intercrate_ambiguity_causes {
             cause.add_intercrate_ambiguity_hint(err);
         }
@@ -400,7 +532,7 @@ fn decorate<'tcx>(
             {
                 let mut err = tcx.sess.struct_span_err(impl_span, msg);
                 err.code(error_code!(E0119));
-                decorate(tcx, &overlap, impl_span, &mut err);
+                decorate(tcx, &overlap, additional, impl_span, &mut err);
                 Some(err.emit())
             } else {
                 Some(tcx.sess.delay_span_bug(impl_span, "impl should have failed the orphan check"))
@@ -418,7 +550,7 @@ fn decorate<'tcx>(
                 impl_span,
                 msg,
                 |err| {
-                    decorate(tcx, &overlap, impl_span, err);
+                    decorate(tcx, &overlap, additional, impl_span, err);
                     err
                 },
             );
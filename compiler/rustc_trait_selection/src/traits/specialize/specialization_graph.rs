@@ -1,9 +1,12 @@
 use super::OverlapError;
 
 use crate::traits;
+#[cfg(parallel_compiler)]
+use rustc_data_structures::sync::{par_iter, ParallelIterator};
 use rustc_hir::def_id::DefId;
 use rustc_middle::ty::fast_reject::{self, SimplifiedType, TreatParams};
 use rustc_middle::ty::{self, TyCtxt, TypeVisitable};
+use std::iter;
 
 pub use rustc_middle::traits::specialization_graph::*;
 
@@ -91,12 +94,32 @@ fn insert(
 
         debug!("insert(impl_def_id={:?}, simplified_self={:?})", impl_def_id, simplified_self,);
 
-        let possible_siblings = match simplified_self {
+        let possible_siblings: Vec<_> = match simplified_self {
             Some(st) => PotentialSiblings::Filtered(filtered_children(self, st)),
             None => PotentialSiblings::Unfiltered(iter_children(self)),
-        };
+        }
+        .collect();
 
-        for possible_sibling in possible_siblings {
+        let overlap = |possible_sibling| {
+            traits::overlapping_impls(
+                tcx,
+                possible_sibling,
+                impl_def_id,
+                traits::SkipLeakCheck::Yes,
+                overlap_mode,
+            )
+        };
+        // See `InherentOverlapChecker::run_checks`. Without the parallel compiler, the overlaps
+        // are computed as they are needed, as we may stop early by descending into a sibling.
+        #[cfg(parallel_compiler)]
+        let overlaps = par_iter(&possible_siblings)
+            .map(|&possible_sibling| overlap(possible_sibling))
+            .collect::<Vec<_>>()
+            .into_iter();
+        #[cfg(not(parallel_compiler))]
+        let overlaps = possible_siblings.iter().map(|&possible_sibling| overlap(possible_sibling));
+
+        for (&possible_sibling, overlap) in iter::zip(&possible_siblings, overlaps) {
             debug!(
                 "insert: impl_def_id={:?}, simplified_self={:?}, possible_sibling={:?}",
                 impl_def_id, simplified_self, possible_sibling,
@@ -130,7 +153,7 @@ fn insert(
                     traits::SkipLeakCheck::default(),
                     overlap_mode,
                 )
-                .is_some();
+                .is_ok();
 
                 let error = create_overlap_error(overlap);
 
@@ -146,15 +169,23 @@ fn insert(
                 }
             };
 
+            let overlap = match overlap {
+                Ok(overlap) => Some(overlap),
+                Err(traits::Disjoint { fundamental_trait_ref }) => {
+                    if let Some(trait_ref) = fundamental_trait_ref {
+                        traits::coherence::lint_fundamental_negative_reasoning(
+                            tcx,
+                            possible_sibling,
+                            impl_def_id,
+                            trait_ref,
+                        );
+                    }
+                    None
+                }
+            };
+
             let last_lint_mut = &mut last_lint;
-            let (le, ge) = traits::overlapping_impls(
-                tcx,
-                possible_sibling,
-                impl_def_id,
-                traits::SkipLeakCheck::Yes,
-                overlap_mode,
-            )
-            .map_or(Ok((false, false)), |overlap| {
+            let (le, ge) = overlap.map_or(Ok((false, false)), |overlap| {
                 if let Some(overlap_kind) =
                     tcx.impls_are_allowed_to_overlap(impl_def_id, possible_sibling)
                 {
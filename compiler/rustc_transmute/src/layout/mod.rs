@@ -30,10 +30,24 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 }
 
 pub(crate) trait Def: Debug + Hash + Eq + PartialEq + Copy + Clone {}
-pub trait Ref: Debug + Hash + Eq + PartialEq + Copy + Clone {}
+pub trait Ref: Debug + Hash + Eq + PartialEq + Copy + Clone {
+    /// The size of the reference itself, in bytes.
+    fn size(&self) -> usize;
+
+    /// Whether this is a unique (`&mut`) reference.
+    fn is_mutable(&self) -> bool;
+}
 
 impl Def for ! {}
-impl Ref for ! {}
+impl Ref for ! {
+    fn size(&self) -> usize {
+        match *self {}
+    }
+
+    fn is_mutable(&self) -> bool {
+        match *self {}
+    }
+}
 
 #[cfg(feature = "rustc")]
 pub(crate) mod rustc {
@@ -45,16 +59,22 @@ pub(crate) mod rustc {
     /// A reference in the layout.
     #[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
     pub struct Ref<'tcx> {
-        lifetime: Region<'tcx>,
-        ty: Ty<'tcx>,
-        mutability: Mutability,
+        pub lifetime: Region<'tcx>,
+        pub ty: Ty<'tcx>,
+        pub mutability: Mutability,
+        /// The alignment of `ty`, in bytes.
+        pub align: usize,
+        /// The size of the reference itself, in bytes.
+        pub size: usize,
     }
 
-    impl<'tcx> super::Ref for Ref<'tcx> {}
+    impl<'tcx> super::Ref for Ref<'tcx> {
+        fn size(&self) -> usize {
+            self.size
+        }
 
-    impl<'tcx> Ref<'tcx> {
-        pub fn min_align(&self) -> usize {
-            todo!()
+        fn is_mutable(&self) -> bool {
+            self.mutability == Mutability::Mut
         }
     }
 
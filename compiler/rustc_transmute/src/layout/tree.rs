@@ -292,6 +292,21 @@ pub fn from_ty(ty: Ty<'tcx>, tcx: TyCtxt<'tcx>) -> Result<Self, Err> {
                         .fold(Tree::unit(), |tree, elt| tree.then(elt)))
                 }
 
+                ty::Ref(lifetime, ty, mutability) => {
+                    // Only references to sized types are thin, and thus have a known layout.
+                    if !ty.is_sized(tcx, ParamEnv::reveal_all()) {
+                        return Err(Err::Unspecified);
+                    }
+                    let align = layout_of(tcx, *ty)?.align();
+                    Ok(Tree::Ref(Ref {
+                        lifetime: *lifetime,
+                        ty: *ty,
+                        mutability: *mutability,
+                        align,
+                        size: target.pointer_size.bytes_usize(),
+                    }))
+                }
+
                 ty::Adt(adt_def, substs_ref) => {
                     use rustc_middle::ty::AdtKind;
 
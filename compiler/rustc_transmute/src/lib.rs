@@ -11,7 +11,7 @@
 pub(crate) mod layout;
 pub(crate) mod maybe_transmutable;
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy)]
 pub struct Assume {
     pub alignment: bool,
     pub lifetimes: bool,
@@ -48,12 +48,51 @@ pub enum Reason {
     SrcIsUnspecified,
     /// The layout of the destination type is unspecified.
     DstIsUnspecified,
-    /// The layout of the destination type is bit-incompatible with the source type.
-    DstIsBitIncompatible,
+    /// The layout of the destination type is bit-incompatible with the source type, starting
+    /// with the byte at `offset`.
+    DstIsBitIncompatible { offset: usize },
     /// There aren't any public constructors for `Dst`.
     DstIsPrivate,
-    /// `Dst` is larger than `Src`, and the excess bytes were not exclusively uninitialized.
-    DstIsTooBig,
+    /// `Dst` is larger than `Src`, and the excess bytes, starting with the byte at `offset`,
+    /// were not exclusively uninitialized.
+    DstIsTooBig { offset: usize },
+    /// The referent of the `Dst` reference at `offset` has a stricter alignment than the
+    /// referent of the corresponding `Src` reference.
+    DstHasStricterAlignment { offset: usize, src_min_align: usize, dst_min_align: usize },
+    /// The `Dst` reference at `offset` is a `&mut`, but the corresponding `Src` reference
+    /// is a shared reference.
+    DstIsMoreUnique { offset: usize },
+}
+
+impl Reason {
+    /// The offset of the first byte at which the layouts of `Src` and `Dst` are incompatible,
+    /// if the transmutation was rejected because of a byte-level incompatibility.
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            Self::DstIsBitIncompatible { offset }
+            | Self::DstIsTooBig { offset }
+            | Self::DstHasStricterAlignment { offset, .. }
+            | Self::DstIsMoreUnique { offset } => Some(offset),
+            Self::SrcIsUnspecified | Self::DstIsUnspecified | Self::DstIsPrivate => None,
+        }
+    }
+}
+
+impl<R> Answer<R>
+where
+    R: layout::Ref,
+{
+    /// Finds the first reason in this answer's condition tree for which the transmutation was
+    /// rejected, if any.
+    pub fn first_rejection(&self) -> Option<&Reason> {
+        match self {
+            Self::Yes | Self::IfTransmutable { .. } => None,
+            Self::No(reason) => Some(reason),
+            Self::IfAll(answers) | Self::IfAny(answers) => {
+                answers.iter().find_map(|answer| answer.first_rejection())
+            }
+        }
+    }
 }
 
 #[cfg(feature = "rustc")]
@@ -70,6 +109,8 @@ mod rustc {
     use rustc_middle::ty::Ty;
     use rustc_middle::ty::TyCtxt;
 
+    pub use crate::layout::rustc::Ref;
+
     /// The source and destination types of a transmutation.
     #[derive(TypeFoldable, TypeVisitable, Debug, Clone, Copy)]
     pub struct Types<'tcx> {
@@ -7,7 +7,7 @@
 mod query_context;
 use query_context::QueryContext;
 
-use crate::layout::{self, dfa, Byte, Dfa, Nfa, Tree, Uninhabited};
+use crate::layout::{self, dfa, Byte, Dfa, Nfa, Ref, Tree, Uninhabited};
 pub(crate) struct MaybeTransmutableQuery<L, C>
 where
     C: QueryContext,
@@ -188,9 +188,15 @@ impl<'l, C> MaybeTransmutableQuery<&'l Dfa<<C as QueryContext>::Ref>, C>
     C: QueryContext,
 {
     pub(crate) fn answer(&mut self) -> Answer<<C as QueryContext>::Ref> {
-        self.answer_memo(&mut Map::default(), self.src.start, self.dst.start)
+        self.answer_memo(&mut Map::default(), self.src.start, self.dst.start, 0)
     }
 
+    /// Answers whether the layout suffixes starting at `src_state` and `dst_state` are
+    /// transmutable, where `offset` is the byte offset of `dst_state` from the start of `Dst`.
+    ///
+    /// All paths through a layout have the same length, so each state is only ever reached at
+    /// a single offset, and the memoized answers (and the offsets of their rejections) remain
+    /// accurate when reused.
     #[inline(always)]
     #[instrument(level = "debug", skip(self))]
     fn answer_memo(
@@ -198,6 +204,7 @@ fn answer_memo(
         cache: &mut Map<(dfa::State, dfa::State), Answer<<C as QueryContext>::Ref>>,
         src_state: dfa::State,
         dst_state: dfa::State,
+        offset: usize,
     ) -> Answer<<C as QueryContext>::Ref> {
         if let Some(answer) = cache.get(&(src_state, dst_state)) {
             answer.clone()
@@ -208,37 +215,108 @@ fn answer_memo(
             } else if src_state == self.src.accepting {
                 // extension: `size_of(Src) >= size_of(Dst)`
                 if let Some(dst_state_prime) = self.dst.byte_from(dst_state, Byte::Uninit) {
-                    self.answer_memo(cache, src_state, dst_state_prime)
+                    self.answer_memo(cache, src_state, dst_state_prime, offset + 1)
                 } else {
-                    Answer::No(Reason::DstIsTooBig)
+                    Answer::No(Reason::DstIsTooBig { offset })
                 }
             } else {
-                let src_quantification = if self.assume.validity {
+                let src_exists = if self.assume.validity {
                     // if the compiler may assume that the programmer is doing additional validity checks,
                     // (e.g.: that `src != 3u8` when the destination type is `bool`)
                     // then there must exist at least one transition out of `src_state` such that the transmute is viable...
-                    there_exists
+                    true
                 } else {
                     // if the compiler cannot assume that the programmer is doing additional validity checks,
                     // then for all transitions out of `src_state`, such that the transmute is viable...
                     // then there must exist at least one transition out of `src_state` such that the transmute is viable...
-                    for_all
+                    false
+                };
+
+                let empty_bytes = Map::default();
+                let src_bytes = self.src.bytes_from(src_state).unwrap_or(&empty_bytes);
+                let empty_refs = Map::default();
+                let src_refs = self.src.refs_from(src_state).unwrap_or(&empty_refs);
+
+                let bytes_answer = |cache: &mut Map<_, _>| {
+                    src_quantification(
+                        src_exists,
+                        src_bytes,
+                        Answer::No(Reason::DstIsBitIncompatible { offset }),
+                        |(&src_validity, &src_state_prime)| {
+                            if let Some(dst_state_prime) =
+                                self.dst.byte_from(dst_state, src_validity)
+                            {
+                                self.answer_memo(
+                                    cache,
+                                    src_state_prime,
+                                    dst_state_prime,
+                                    offset + 1,
+                                )
+                            } else if let Some(dst_state_prime) =
+                                self.dst.byte_from(dst_state, Byte::Uninit)
+                            {
+                                self.answer_memo(
+                                    cache,
+                                    src_state_prime,
+                                    dst_state_prime,
+                                    offset + 1,
+                                )
+                            } else {
+                                Answer::No(Reason::DstIsBitIncompatible { offset })
+                            }
+                        },
+                    )
+                };
+
+                // A reference in `Src` can only become a reference in `Dst` (never bytes), with
+                // a referent that is at most as strictly aligned, and it can't gain uniqueness.
+                // Whether the referents themselves are transmutable is left to the caller.
+                let refs_answer = |cache: &mut Map<_, _>| {
+                    src_quantification(
+                        src_exists,
+                        src_refs,
+                        Answer::No(Reason::DstIsBitIncompatible { offset }),
+                        |(&src_ref, &src_state_prime)| {
+                            let dst_refs = self.dst.refs_from(dst_state).unwrap_or(&empty_refs);
+                            there_exists(
+                                dst_refs,
+                                Answer::No(Reason::DstIsBitIncompatible { offset }),
+                                |(&dst_ref, &dst_state_prime)| {
+                                    let src_min_align = self.context.min_align(src_ref);
+                                    let dst_min_align = self.context.min_align(dst_ref);
+                                    if !self.assume.alignment && src_min_align < dst_min_align {
+                                        Answer::No(Reason::DstHasStricterAlignment {
+                                            offset,
+                                            src_min_align,
+                                            dst_min_align,
+                                        })
+                                    } else if dst_ref.is_mutable() && !src_ref.is_mutable() {
+                                        Answer::No(Reason::DstIsMoreUnique { offset })
+                                    } else {
+                                        Answer::IfTransmutable { src: src_ref, dst: dst_ref }.and(
+                                            self.answer_memo(
+                                                cache,
+                                                src_state_prime,
+                                                dst_state_prime,
+                                                offset + src_ref.size(),
+                                            ),
+                                        )
+                                    }
+                                },
+                            )
+                        },
+                    )
                 };
 
-                src_quantification(
-                    self.src.bytes_from(src_state).unwrap_or(&Map::default()),
-                    |(&src_validity, &src_state_prime)| {
-                        if let Some(dst_state_prime) = self.dst.byte_from(dst_state, src_validity) {
-                            self.answer_memo(cache, src_state_prime, dst_state_prime)
-                        } else if let Some(dst_state_prime) =
-                            self.dst.byte_from(dst_state, Byte::Uninit)
-                        {
-                            self.answer_memo(cache, src_state_prime, dst_state_prime)
-                        } else {
-                            Answer::No(Reason::DstIsBitIncompatible)
-                        }
-                    },
-                )
+                if src_refs.is_empty() {
+                    bytes_answer(cache)
+                } else if src_bytes.is_empty() {
+                    refs_answer(cache)
+                } else if src_exists {
+                    bytes_answer(cache).or(refs_answer(cache))
+                } else {
+                    bytes_answer(cache).and(refs_answer(cache))
+                }
             };
             cache.insert((src_state, dst_state), answer.clone());
             answer
@@ -253,7 +331,7 @@ impl<R> Answer<R>
     pub(crate) fn and(self, rhs: Self) -> Self {
         match (self, rhs) {
             (Self::No(reason), _) | (_, Self::No(reason)) => Self::No(reason),
-            (Self::Yes, Self::Yes) => Self::Yes,
+            (Self::Yes, constraint) | (constraint, Self::Yes) => constraint,
             (Self::IfAll(mut lhs), Self::IfAll(ref mut rhs)) => {
                 lhs.append(rhs);
                 Self::IfAll(lhs)
@@ -271,6 +349,7 @@ pub(crate) fn or(self, rhs: Self) -> Self {
         match (self, rhs) {
             (Self::Yes, _) | (_, Self::Yes) => Self::Yes,
             (Self::No(lhr), Self::No(rhr)) => Self::No(lhr),
+            (Self::No(_), constraint) | (constraint, Self::No(_)) => constraint,
             (Self::IfAny(mut lhs), Self::IfAny(ref mut rhs)) => {
                 lhs.append(rhs);
                 Self::IfAny(lhs)
@@ -285,6 +364,18 @@ pub(crate) fn or(self, rhs: Self) -> Self {
     }
 }
 
+/// Quantifies over the transitions out of a `Src` state: produces `there_exists(iter, none, f)`
+/// if `exists`, and `for_all(iter, f)` otherwise.
+fn src_quantification<R, I, F>(exists: bool, iter: I, none: Answer<R>, f: F) -> Answer<R>
+where
+    R: layout::Ref,
+    I: IntoIterator,
+    F: FnMut(<I as IntoIterator>::Item) -> Answer<R>,
+{
+    if exists { there_exists(iter, none, f) } else { for_all(iter, f) }
+}
+
+/// Produces `Yes` if `f` answers `Yes` for all items of `iter`.
 pub fn for_all<R, I, F>(iter: I, f: F) -> Answer<R>
 where
     R: layout::Ref,
@@ -302,19 +393,20 @@ pub fn for_all<R, I, F>(iter: I, f: F) -> Answer<R>
     result
 }
 
-pub fn there_exists<R, I, F>(iter: I, f: F) -> Answer<R>
+/// Produces `Yes` if `f` answers `Yes` for any item of `iter`, and `none` if `iter` is empty.
+pub fn there_exists<R, I, F>(iter: I, none: Answer<R>, f: F) -> Answer<R>
 where
     R: layout::Ref,
     I: IntoIterator,
     F: FnMut(<I as IntoIterator>::Item) -> Answer<R>,
 {
     use std::ops::ControlFlow::{Break, Continue};
-    let (Continue(result) | Break(result)) = iter.into_iter().map(f).try_fold(
-        Answer::No(Reason::DstIsBitIncompatible),
-        |constraints, constraint| match constraint.or(constraints) {
-            Answer::Yes => Break(Answer::Yes),
-            maybe => Continue(maybe),
-        },
-    );
+    let (Continue(result) | Break(result)) =
+        iter.into_iter().map(f).try_fold(none, |constraints, constraint| {
+            match constraint.or(constraints) {
+                Answer::Yes => Break(Answer::Yes),
+                maybe => Continue(maybe),
+            }
+        });
     result
 }
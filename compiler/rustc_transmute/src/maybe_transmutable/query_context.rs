@@ -9,6 +9,7 @@ pub(crate) trait QueryContext {
     /// Is `def` accessible from the defining module of `scope`?
     fn is_accessible_from(&self, def: Self::Def, scope: Self::Scope) -> bool;
 
+    /// The minimum alignment of the referent of `reference`, in bytes.
     fn min_align(&self, reference: Self::Ref) -> usize;
 }
 
@@ -87,7 +88,7 @@ fn is_accessible_from(&self, def: Self::Def, scope: Self::Scope) -> bool {
         }
 
         fn min_align(&self, reference: Self::Ref) -> usize {
-            unimplemented!()
+            reference.align
         }
     }
 }
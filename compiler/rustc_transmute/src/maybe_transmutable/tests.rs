@@ -95,7 +95,7 @@ fn should_permit_validity_expansion_and_reject_contraction() {
                     );
                 } else {
                     assert_eq!(
-                        Answer::No(Reason::DstIsBitIncompatible),
+                        Answer::No(Reason::DstIsBitIncompatible { offset: 0 }),
                         MaybeTransmutableQuery::new(
                             src_layout.clone(),
                             dst_layout.clone(),
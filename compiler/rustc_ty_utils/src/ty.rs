@@ -1,7 +1,10 @@
 use rustc_data_structures::fx::FxIndexSet;
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
-use rustc_middle::ty::{self, Binder, Predicate, PredicateKind, ToPredicate, Ty, TyCtxt};
+use rustc_middle::ty::subst::GenericArgKind;
+use rustc_middle::ty::{
+    self, Binder, Predicate, PredicateKind, ToPredicate, Ty, TyCtxt, TypeVisitable,
+};
 use rustc_trait_selection::traits;
 
 fn sized_constraint_for_ty<'tcx>(
@@ -126,6 +129,10 @@ fn param_env(tcx: TyCtxt<'_>, def_id: DefId) -> ty::ParamEnv<'_> {
         predicates.extend(environment);
     }
 
+    if tcx.sess.opts.unstable_opts.implied_bounds_from_impl_headers {
+        predicates.extend(impl_header_implied_bounds(tcx, def_id));
+    }
+
     let local_did = def_id.as_local();
     let hir_id = local_did.map(|def_id| tcx.hir().local_def_id_to_hir_id(def_id));
 
@@ -219,6 +226,48 @@ fn param_env(tcx: TyCtxt<'_>, def_id: DefId) -> ty::ParamEnv<'_> {
     traits::normalize_param_env_or_error(tcx, unnormalized_env, cause)
 }
 
+/// The where clauses of every ADT named in the header of the impl that `def_id` belongs
+/// to, instantiated with the substs it is named with. For `impl<T> Trait for Foo<T>`
+/// with `struct Foo<T: Clone>`, this is `T: Clone`.
+///
+/// These hold whenever the impl header is well-formed, so the items of the impl can
+/// rely on them without repeating them. Being provable there anyway, they only matter
+/// as where clauses, which take precedence over impls during selection. They are
+/// deliberately left out of the param env of the impl itself: that env is used to check
+/// that the header is well-formed, which would otherwise always succeed. Outlives bounds
+/// such as `T: 'a` from `&'a T` are already implied by region checking and are not
+/// collected here.
+fn impl_header_implied_bounds<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+) -> impl Iterator<Item = Predicate<'tcx>> + 'tcx {
+    let impl_def_id = tcx.impl_of_method(def_id);
+
+    let mut header_tys = vec![];
+    if let Some(impl_def_id) = impl_def_id {
+        header_tys.push(tcx.type_of(impl_def_id).into());
+        if let Some(trait_ref) = tcx.impl_trait_ref(impl_def_id) {
+            header_tys.extend(trait_ref.substs.iter());
+        }
+    }
+
+    header_tys
+        .into_iter()
+        .flat_map(|arg: ty::GenericArg<'tcx>| arg.walk())
+        .filter_map(|arg| match arg.unpack() {
+            GenericArgKind::Type(ty) => match *ty.kind() {
+                ty::Adt(adt, substs) if substs.needs_subst() => Some((adt.did(), substs)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<FxIndexSet<_>>()
+        .into_iter()
+        .flat_map(move |(adt_def_id, substs)| {
+            tcx.predicates_of(adt_def_id).instantiate(tcx, substs).predicates
+        })
+}
+
 /// Elaborate the environment.
 ///
 /// Collect a list of `Predicate`'s used for building the `ParamEnv`. Adds `TypeWellFormedFromEnv`'s
@@ -230,7 +279,6 @@ fn well_formed_types_in_env<'tcx>(
     def_id: DefId,
 ) -> &'tcx ty::List<Predicate<'tcx>> {
     use rustc_hir::{ForeignItemKind, ImplItemKind, ItemKind, Node, TraitItemKind};
-    use rustc_middle::ty::subst::GenericArgKind;
 
     debug!("environment(def_id = {:?})", def_id);
 
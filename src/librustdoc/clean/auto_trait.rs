@@ -293,6 +293,8 @@ fn handle_lifetimes<'cx>(
             }
         }
 
+        Self::remove_implied_outlives(&mut finished);
+
         let lifetime_predicates = names_map
             .iter()
             .flat_map(|(name, lifetime)| {
@@ -317,6 +319,44 @@ fn handle_lifetimes<'cx>(
         lifetime_predicates
     }
 
+    /// Drops the constraints in `finished` (a map from a region to the regions it outlives) that
+    /// follow transitively from the others, e.g. `'a: 'c` when `'a: 'b` and `'b: 'c` are present.
+    /// Regions that outlive each other are left alone, so that a cycle isn't removed entirely.
+    fn remove_implied_outlives<'cx>(finished: &mut FxHashMap<Symbol, Vec<Region<'cx>>>) {
+        fn outlives<'cx>(
+            graph: &FxHashMap<Symbol, Vec<Region<'cx>>>,
+            from: Region<'cx>,
+            to: Region<'cx>,
+        ) -> bool {
+            let mut visited = FxHashSet::default();
+            let mut stack = vec![from];
+            while let Some(region) = stack.pop() {
+                if region == to {
+                    return true;
+                }
+                if !visited.insert(region) {
+                    continue;
+                }
+                if let Some(smaller) = region_name(region).and_then(|name| graph.get(&name)) {
+                    stack.extend(smaller.iter().copied());
+                }
+            }
+            false
+        }
+
+        let graph = finished.clone();
+        for smaller in finished.values_mut() {
+            let all = smaller.clone();
+            smaller.retain(|&region| {
+                !all.iter().any(|&other| {
+                    other != region
+                        && outlives(&graph, other, region)
+                        && !outlives(&graph, region, other)
+                })
+            });
+        }
+    }
+
     fn extract_for_generics(&self, pred: ty::Predicate<'tcx>) -> FxHashSet<GenericParamDef> {
         let bound_predicate = pred.kind();
         let tcx = self.cx.tcx;
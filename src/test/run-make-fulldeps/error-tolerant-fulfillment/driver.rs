@@ -0,0 +1,110 @@
+#![feature(rustc_private)]
+
+//! This program implements a rustc driver that proves a failing and an
+//! ambiguous obligation with both a regular and an error-tolerant
+//! `ObligationCtxt`. The regular one stops at the selection error, while
+//! the error-tolerant one also reports the obligation that is still
+//! ambiguous, which is what consumers like IDEs that want as much inference
+//! done as possible are interested in.
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_infer;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_span;
+extern crate rustc_trait_selection;
+
+use rustc_driver::Compilation;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_infer::infer::type_variable::{TypeVariableOrigin, TypeVariableOriginKind};
+use rustc_interface::Queries;
+use rustc_interface::interface::Compiler;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::DUMMY_SP;
+use rustc_span::symbol::Symbol;
+use rustc_trait_selection::traits::{
+    FulfillmentError, FulfillmentErrorCode, Obligation, ObligationCause, ObligationCtxt,
+};
+
+fn main() {
+    let exit_code = rustc_driver::catch_with_exit_code(move || {
+        let rustc_args: Vec<_> = std::env::args().collect();
+        rustc_driver::RunCompiler::new(&rustc_args, &mut CompilerCalls).run()
+    });
+    std::process::exit(exit_code);
+}
+
+pub struct CompilerCalls;
+
+impl rustc_driver::Callbacks for CompilerCalls {
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        compiler.session().abort_if_errors();
+        queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
+            let foo = find_item(tcx, DefKind::Trait, "Foo");
+            let unimplemented = tcx.type_of(find_item(tcx, DefKind::Struct, "Unimplemented"));
+
+            println!("ObligationCtxt::new:");
+            print_errors(prove(tcx, foo, unimplemented, false));
+            println!("ObligationCtxt::new_error_tolerant:");
+            print_errors(prove(tcx, foo, unimplemented, true));
+        });
+
+        Compilation::Continue
+    }
+}
+
+fn find_item(tcx: TyCtxt<'_>, kind: DefKind, name: &str) -> DefId {
+    tcx.hir_crate_items(())
+        .items()
+        .map(|id| id.owner_id.to_def_id())
+        .find(|&def_id| {
+            tcx.def_kind(def_id) == kind && tcx.item_name(def_id) == Symbol::intern(name)
+        })
+        .unwrap()
+}
+
+/// Proves `Unimplemented: Foo`, which fails, and `?T: Foo`, which is ambiguous.
+fn prove<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    foo: DefId,
+    unimplemented: Ty<'tcx>,
+    error_tolerant: bool,
+) -> Vec<FulfillmentError<'tcx>> {
+    let infcx = tcx.infer_ctxt().build();
+    let ocx = if error_tolerant {
+        ObligationCtxt::new_error_tolerant(&infcx)
+    } else {
+        ObligationCtxt::new(&infcx)
+    };
+    let infer_ty = infcx.next_ty_var(TypeVariableOrigin {
+        kind: TypeVariableOriginKind::MiscVariable,
+        span: DUMMY_SP,
+    });
+    for self_ty in [unimplemented, infer_ty] {
+        let trait_ref = ty::Binder::dummy(tcx.mk_trait_ref(foo, [self_ty]));
+        ocx.register_obligation(Obligation::new(
+            tcx,
+            ObligationCause::dummy(),
+            ty::ParamEnv::empty(),
+            trait_ref.to_poly_trait_predicate(),
+        ));
+    }
+    ocx.select_all_or_error()
+}
+
+fn print_errors(errors: Vec<FulfillmentError<'_>>) {
+    for error in errors {
+        match error.code {
+            FulfillmentErrorCode::CodeSelectionError(_) => println!("selection error"),
+            FulfillmentErrorCode::CodeAmbiguity => println!("ambiguity"),
+            code => println!("{:?}", code),
+        }
+    }
+}
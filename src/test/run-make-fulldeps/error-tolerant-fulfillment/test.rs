@@ -0,0 +1,8 @@
+trait Foo {}
+
+struct Implemented;
+struct Unimplemented;
+
+impl Foo for Implemented {}
+
+fn main() {}
@@ -0,0 +1,96 @@
+#![feature(rustc_private)]
+
+//! This program implements a rustc driver that proves `Deep: Foo`, which
+//! takes 8 nested obligations, with `ObligationCtxt`s with various recursion
+//! limits, and with the obligation registered at various depths. The
+//! obligation has no inference variables, so this also checks that such
+//! obligations are not proven at the crate's recursion limit instead.
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_infer;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_session;
+extern crate rustc_span;
+extern crate rustc_trait_selection;
+
+use rustc_driver::{catch_fatal_errors, Compilation};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_interface::Queries;
+use rustc_interface::interface::Compiler;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::Limit;
+use rustc_span::symbol::Symbol;
+use rustc_trait_selection::traits::{Obligation, ObligationCause, ObligationCtxt};
+
+fn main() {
+    let exit_code = rustc_driver::catch_with_exit_code(move || {
+        let rustc_args: Vec<_> = std::env::args().collect();
+        rustc_driver::RunCompiler::new(&rustc_args, &mut CompilerCalls).run()
+    });
+    std::process::exit(exit_code);
+}
+
+pub struct CompilerCalls;
+
+impl rustc_driver::Callbacks for CompilerCalls {
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        compiler.session().abort_if_errors();
+        queries.global_ctxt().unwrap().peek_mut().enter(|tcx| {
+            let foo = find_item(tcx, DefKind::Trait, "Foo");
+            let deep = tcx.type_of(find_item(tcx, DefKind::TyAlias, "Deep"));
+
+            for (limit, depth) in [(None, 0), (Some(16), 0), (Some(4), 0), (Some(16), 12)] {
+                let result = match catch_fatal_errors(|| prove(tcx, foo, deep, limit, depth)) {
+                    Ok(true) => "holds",
+                    Ok(false) => "does not hold",
+                    Err(_) => "overflow",
+                };
+                println!("limit {:?}, depth {}: {}", limit, depth, result);
+            }
+        });
+
+        Compilation::Stop
+    }
+}
+
+fn find_item(tcx: TyCtxt<'_>, kind: DefKind, name: &str) -> DefId {
+    tcx.hir_crate_items(())
+        .items()
+        .map(|id| id.owner_id.to_def_id())
+        .find(|&def_id| {
+            tcx.def_kind(def_id) == kind && tcx.item_name(def_id) == Symbol::intern(name)
+        })
+        .unwrap()
+}
+
+/// Proves `self_ty: Foo` at `depth`, with `limit` as the recursion limit if given.
+fn prove<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    foo: DefId,
+    self_ty: Ty<'tcx>,
+    limit: Option<usize>,
+    depth: usize,
+) -> bool {
+    let infcx = tcx.infer_ctxt().build();
+    let ocx = match limit {
+        Some(limit) => ObligationCtxt::with_recursion_limit(&infcx, Limit::new(limit)),
+        None => ObligationCtxt::new(&infcx),
+    };
+    let trait_ref = ty::Binder::dummy(tcx.mk_trait_ref(foo, [self_ty]));
+    let obligation = Obligation::new(
+        tcx,
+        ObligationCause::dummy(),
+        ty::ParamEnv::empty(),
+        trait_ref.to_poly_trait_predicate(),
+    );
+    ocx.register_obligation_at_depth(obligation, depth);
+    ocx.select_all_or_error().is_empty()
+}
@@ -0,0 +1,11 @@
+trait Foo {}
+
+struct Wrap<T>(T);
+
+impl Foo for () {}
+impl<T: Foo> Foo for Wrap<T> {}
+
+// Proving `Deep: Foo` takes 8 nested obligations.
+type Deep = Wrap<Wrap<Wrap<Wrap<Wrap<Wrap<Wrap<Wrap<()>>>>>>>>;
+
+fn main() {}
@@ -0,0 +1,14 @@
+pub trait Speak {}
+
+impl Speak for u8 {}
+impl Speak for u16 {}
+
+fn speak<T: Speak>(t: T) -> T {
+    t
+}
+
+pub fn stalled() -> u8 {
+    // `_: Speak` is ambiguous until the return type constrains `x` to `u8`.
+    let x = speak(Default::default());
+    x
+}
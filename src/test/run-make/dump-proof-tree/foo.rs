@@ -0,0 +1,11 @@
+pub trait Speak {}
+
+pub struct Wrapper<T>(T);
+
+impl<T: Speak> Speak for Wrapper<T> {}
+
+fn speak<T: Speak>(_: T) {}
+
+pub fn generic<T: Speak>(t: T) {
+    speak(Wrapper(t));
+}
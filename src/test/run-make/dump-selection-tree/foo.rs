@@ -0,0 +1,15 @@
+pub trait Speak {}
+
+pub struct Leaf;
+pub struct Pair<A, B>(A, B);
+pub struct Wrapper<T>(T);
+
+impl Speak for Leaf {}
+impl<A: Speak, B: Speak> Speak for Pair<A, B> {}
+impl<T: Speak> Speak for Wrapper<T> {}
+
+fn speak<T: Speak>(_: T) {}
+
+pub fn speak_with_leaf<T: Speak>(t: T) {
+    speak(Wrapper(Pair(Leaf, t)));
+}
@@ -0,0 +1,30 @@
+// Check that a bound which is added while the constituent types of a struct are still
+// being selected is used to select the remaining ones, and is only shown once.
+
+pub struct Inner<T> {
+    field: T,
+}
+
+unsafe impl<T> Send for Inner<T> where T: Copy {}
+
+pub struct Wrapper<T> {
+    field: T,
+}
+
+unsafe impl<T> Send for Wrapper<T>
+where
+    T: Send,
+    Inner<T>: Send,
+{
+}
+
+// @has env_rebuild/struct.Outer.html
+// @has - '//*[@id="synthetic-implementations-list"]//*[@class="impl has-srclink"]//h3[@class="code-header"]' \
+// "impl<T> Send for Outer<T>where T: Send + Copy"
+//
+// @has - '//*[@id="synthetic-implementations-list"]//*[@class="impl has-srclink"]//h3[@class="code-header"]' \
+// "impl<T> Sync for Outer<T>where T: Sync"
+pub struct Outer<T> {
+    first: T,
+    second: Wrapper<T>,
+}
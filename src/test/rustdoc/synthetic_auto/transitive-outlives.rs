@@ -0,0 +1,30 @@
+pub struct Inner<'a, 'b, 'c> {
+    field: *const (&'a u8, &'b u8, &'c u8),
+}
+
+unsafe impl<'a, 'b, 'c> Send for Inner<'a, 'b, 'c>
+where
+    'a: 'b,
+    'b: 'c,
+    'a: 'c,
+{
+}
+
+// `'x: 'z` follows from the other two bounds, so it isn't shown.
+//
+// @has transitive_outlives/struct.Foo.html
+// @has - '//*[@id="synthetic-implementations-list"]//*[@class="impl has-srclink"]//h3[@class="code-header"]' \
+// "impl<'x, 'y, 'z> Send for Foo<'x, 'y, 'z>where"
+// @has - '//*[@id="synthetic-implementations-list"]//*[@class="impl has-srclink"]//h3[@class="code-header"]' \
+// "'x: 'y"
+// @has - '//*[@id="synthetic-implementations-list"]//*[@class="impl has-srclink"]//h3[@class="code-header"]' \
+// "'y: 'z"
+// @!has - '//*[@id="synthetic-implementations-list"]//*[@class="impl has-srclink"]//h3[@class="code-header"]' \
+// "'x: 'z"
+// @!has - '//*[@id="synthetic-implementations-list"]//*[@class="impl has-srclink"]//h3[@class="code-header"]' \
+// "'y + 'z"
+// @!has - '//*[@id="synthetic-implementations-list"]//*[@class="impl has-srclink"]//h3[@class="code-header"]' \
+// "'z + 'y"
+pub struct Foo<'x, 'y, 'z> {
+    inner_field: Inner<'x, 'y, 'z>,
+}
@@ -0,0 +1,38 @@
+// check-pass
+// edition: 2021
+
+#![feature(async_fn_in_trait)]
+#![allow(incomplete_features)]
+
+use std::future::Future;
+
+trait MyTrait {
+    async fn foo(&self) -> i32;
+}
+
+impl MyTrait for i32 {
+    async fn foo(&self) -> i32 {
+        *self
+    }
+}
+
+fn needs_future<F: Future<Output = i32>>(_: F) {}
+
+fn needs_send<T: Send>(_: T) {}
+
+// The `Future` bound is proven from the item bounds of the desugared
+// return-position `impl Trait`.
+fn generic<T: MyTrait>(x: &T) {
+    needs_future(x.foo());
+}
+
+// With a known impl, `Send` leaks through the impl's hidden type.
+fn concrete(x: &i32) {
+    needs_future(x.foo());
+    needs_send(x.foo());
+}
+
+fn main() {
+    generic(&5);
+    concrete(&5);
+}
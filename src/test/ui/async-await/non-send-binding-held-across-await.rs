@@ -0,0 +1,49 @@
+// edition:2018
+// revisions: no_drop_tracking drop_tracking
+// [no_drop_tracking]compile-flags:-Zdrop-tracking=no
+// [drop_tracking]compile-flags:-Zdrop-tracking
+// Check that a non-`Send` local binding held across an await gets a
+// suggestion to drop it earlier, and that the binding is found through its
+// pattern even when it is declared `mut`. No suggestion is given if the
+// binding is still used after the await.
+
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+async fn other() {}
+
+async fn simple() {
+    let rc = Rc::new(1);
+    println!("{}", rc);
+    other().await;
+}
+
+async fn mutable() {
+    let mut rc = Rc::new(1);
+    *Rc::get_mut(&mut rc).unwrap() += 1;
+    other().await;
+}
+
+async fn destructured() {
+    let (rc, _) = (Rc::new(1), 2);
+    println!("{}", rc);
+    other().await;
+}
+
+async fn used_after() {
+    let rc = Rc::new(1);
+    other().await;
+    println!("{}", rc);
+}
+
+fn main() {
+    is_send(simple());
+    //~^ ERROR future cannot be sent between threads safely
+    is_send(mutable());
+    //~^ ERROR future cannot be sent between threads safely
+    is_send(destructured());
+    //~^ ERROR future cannot be sent between threads safely
+    is_send(used_after());
+    //~^ ERROR future cannot be sent between threads safely
+}
@@ -0,0 +1,12 @@
+#![feature(fundamental)]
+
+#[fundamental] //~ ERROR `fundamental` attribute should be applied to a struct, enum, union, or trait
+fn foo() {}
+
+#[fundamental]
+struct Wrapper<T>(T);
+
+#[fundamental]
+trait Trait {}
+
+fn main() {}
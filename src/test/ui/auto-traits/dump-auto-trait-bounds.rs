@@ -0,0 +1,36 @@
+// Check the bounds that `AutoTraitFinder::find_auto_trait_bounds` synthesizes
+// for the `Send` and `Sync` impls of a type.
+#![feature(rustc_attrs)]
+
+use std::cell::Cell;
+
+#[rustc_dump_auto_trait_bounds]
+struct Plain<T>(T);
+//~^ ERROR `Send` bounds
+//~| ERROR `Sync` bounds
+
+#[rustc_dump_auto_trait_bounds]
+struct Shared<'a, T>(&'a T, Cell<u8>);
+//~^ ERROR `Send` bounds
+//~| ERROR `Sync` is not implemented
+
+#[rustc_dump_auto_trait_bounds]
+struct AlreadyBounded<T: Send>(T);
+//~^ ERROR `Send` bounds: []
+//~| ERROR `Sync` bounds
+
+struct Pair<'a, 'b>(&'a u8, &'b u8);
+unsafe impl<'a, 'b: 'a> Send for Pair<'a, 'b> {}
+
+#[rustc_dump_auto_trait_bounds]
+struct Outer<'x, 'y>(Pair<'x, 'y>);
+//~^ ERROR `Send` bounds
+//~| ERROR `Sync` bounds: []
+
+#[rustc_dump_auto_trait_bounds]
+struct Explicit<T>(T);
+//~^ ERROR `Send` is implemented explicitly
+//~| ERROR `Sync` bounds
+unsafe impl<T> Send for Explicit<T> {}
+
+fn main() {}
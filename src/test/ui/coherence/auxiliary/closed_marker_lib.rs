@@ -0,0 +1,7 @@
+#![crate_type = "lib"]
+#![feature(closed_marker_traits)]
+
+#[closed_marker]
+pub trait Sealed {}
+
+impl Sealed for u8 {}
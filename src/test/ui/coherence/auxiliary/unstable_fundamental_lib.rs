@@ -0,0 +1,6 @@
+#![feature(fundamental)]
+
+pub trait Trait {}
+
+#[fundamental]
+pub struct Wrapper<T>(pub T);
@@ -0,0 +1,17 @@
+// check-pass
+// aux-build:closed_marker_lib.rs
+//
+// Check that coherence does not hedge against other crates implementing a
+// `#[closed_marker]` trait: neither `u16` nor `Local` can ever implement
+// `Sealed`, so none of these impls overlap with the blanket impl.
+
+extern crate closed_marker_lib as lib;
+
+struct Local;
+
+trait Foo {}
+impl<T: lib::Sealed> Foo for T {}
+impl Foo for u16 {}
+impl Foo for Local {}
+
+fn main() {}
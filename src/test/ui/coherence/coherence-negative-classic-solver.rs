@@ -0,0 +1,22 @@
+// check-pass
+// revisions: chalk classic
+//[chalk] compile-flags: -Zchalk
+//[classic] compile-flags: -Zchalk -Zclassic-coherence-solver
+//
+// Check that the negative reasoning of coherence works both with chalk and
+// with the classic solver when the rest of the compilation uses chalk.
+
+#![feature(negative_impls)]
+#![feature(rustc_attrs)]
+#![feature(with_negative_coherence)]
+
+#[rustc_strict_coherence]
+trait Foo {}
+impl<T> !Foo for &T where T: 'static {}
+
+#[rustc_strict_coherence]
+trait Bar {}
+impl<T: Foo> Bar for T {}
+impl<T> Bar for &T where T: 'static {}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// Check that a positive where clause of one impl is not used to prove the
+// negative obligation `T: !Foo` when checking whether the other impl can apply.
+
+#![feature(with_negative_coherence)]
+
+trait Foo {}
+trait Baz {}
+trait Bar {}
+
+impl<T: Foo> Bar for T {}
+impl<T: Foo + Baz> Bar for T {}
+//~^ ERROR conflicting implementations of trait `Bar`
+
+fn main() {}
@@ -0,0 +1,23 @@
+// check-pass
+
+// Outlives bounds can only make impls disjoint through negative reasoning, and
+// there the bounds implied by the impl headers are already assumed: `T: 'a` is
+// implied by `&'a T`, so `&'a T: !Foo` holds and the impls of `Bar` don't
+// overlap. See `coherence-overlap-with-regions.rs` for a bound that isn't
+// implied, and `coherence-no-direct-lifetime-dispatch.rs` for the overlap check
+// ignoring outlives bounds otherwise.
+
+#![feature(negative_impls)]
+#![feature(rustc_attrs)]
+#![feature(with_negative_coherence)]
+
+#[rustc_strict_coherence]
+trait Foo {}
+impl<'a, T: 'a> !Foo for &'a T {}
+
+#[rustc_strict_coherence]
+trait Bar {}
+impl<T: Foo> Bar for T {}
+impl<'a, T> Bar for &'a T {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// compile-flags: -Zalways-explain-intercrate-ambiguity
+
+pub struct Local;
+
+pub trait Foo<X> {}
+pub trait Bar<X> {}
+impl Bar<Local> for i32 {}
+impl<X, T> Foo<X> for T where T: Bar<X> {}
+impl<X> Foo<X> for i32 {}
+//~^ ERROR E0119
+
+fn main() { }
@@ -0,0 +1,34 @@
+// Check that the overlap errors of several conflicting trait impls, and of several
+// overlapping inherent impls, are reported in the order of the impls.
+
+trait Shape {}
+
+impl<T> Shape for T {}
+
+impl Shape for u8 {}
+//~^ ERROR conflicting implementations of trait `Shape` for type `u8`
+
+impl Shape for u16 {}
+//~^ ERROR conflicting implementations of trait `Shape` for type `u16`
+
+impl Shape for u32 {}
+//~^ ERROR conflicting implementations of trait `Shape` for type `u32`
+
+struct Square;
+
+impl Square {
+    fn side(&self) {}
+    //~^ ERROR duplicate definitions with name `side`
+}
+
+impl Square {
+    fn side(&self) {}
+    fn area(&self) {}
+    //~^ ERROR duplicate definitions with name `area`
+}
+
+impl Square {
+    fn area(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// check-pass
+// aux-build:error_lib.rs
+//
+// Check that an upstream `impl !Error for &str` makes `&str: Error` knowable
+// even when it is only reached through a local blanket impl, so the two
+// `Foo` impls below are disjoint.
+
+#![feature(with_negative_coherence)]
+
+extern crate error_lib as lib;
+use lib::Error;
+
+trait Local {}
+impl<T: Error> Local for T {}
+
+trait Foo {}
+impl<T: Local> Foo for T {}
+impl Foo for &str {}
+
+fn main() {}
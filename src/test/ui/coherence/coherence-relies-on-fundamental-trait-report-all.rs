@@ -0,0 +1,17 @@
+// compile-flags: -Zreport-all-impl-overlaps
+//
+// Check that the lint is emitted once when an impl is also checked against
+// the other impls to report all of its overlaps.
+
+#![deny(coherence_relies_on_fundamental_trait)]
+
+trait Handler {}
+
+impl<F: Fn()> Handler for F {}
+impl Handler for u8 {}
+//~^ ERROR impl is only disjoint from another impl because `u8` does not implement `FnOnce<()>`
+impl Handler for u8 {}
+//~^ ERROR impl is only disjoint from another impl because `u8` does not implement `FnOnce<()>`
+//~| ERROR conflicting implementations of trait `Handler` for type `u8`
+
+fn main() {}
@@ -0,0 +1,25 @@
+#![deny(coherence_relies_on_fundamental_trait)]
+#![allow(dead_code)]
+
+trait Handler {}
+
+impl<F: Fn()> Handler for F {}
+impl Handler for u8 {}
+//~^ ERROR impl is only disjoint from another impl because `u8` does not implement `FnOnce<()>`
+
+struct Local;
+
+// `Local: Fn()` could only be implemented by this crate, so this is fine.
+impl Handler for Local {}
+
+struct Wrapper<T>(T);
+
+// The lint only covers trait impls, so inherent impls are not linted.
+impl<F: Fn()> Wrapper<F> {
+    fn handle(&self) {}
+}
+impl Wrapper<u8> {
+    fn handle(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+// compile-flags: -Zreport-all-impl-overlaps
+
+trait Foo {}
+impl Foo for u8 {}
+impl Foo for u16 {}
+impl<T: Copy> Foo for T {}
+//~^ ERROR conflicting implementations of trait `Foo` for type `u8`
+
+fn main() {}
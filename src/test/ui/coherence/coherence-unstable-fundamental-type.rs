@@ -0,0 +1,18 @@
+// Impls which only pass the orphan check because an upstream crate marked
+// one of its types `#[fundamental]` are linted, as the upstream crate may
+// remove the attribute again. `Box` is fundamental for good and not linted.
+
+// aux-build:unstable_fundamental_lib.rs
+
+#![deny(unstable_fundamental_type)]
+
+extern crate unstable_fundamental_lib as lib;
+
+struct Local;
+
+impl lib::Trait for Local {}
+impl lib::Trait for Box<Local> {}
+impl lib::Trait for lib::Wrapper<Local> {}
+//~^ ERROR impl relies on the upstream type `Wrapper` being `#[fundamental]`
+
+fn main() {}
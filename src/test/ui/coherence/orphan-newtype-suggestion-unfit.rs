@@ -0,0 +1,35 @@
+// Don't suggest a local new type wrapper if the name of the wrapper is taken,
+// if the impl would need to be adjusted by hand to go through the wrapper, e.g.
+// because its items mention `Self`, or if the uncovered type parameter is not
+// the self type.
+// aux-build:coherence_lib.rs
+
+#![allow(dead_code)]
+
+extern crate coherence_lib as lib;
+use lib::{Remote, Remote1};
+use std::rc::Rc as LocalBox;
+
+impl Remote for Box<u8> {}
+//~^ ERROR only traits defined in the current crate
+
+impl Remote for u16 {}
+//~^ ERROR only traits defined in the current crate
+
+impl Remote1<u8> for u16 {}
+//~^ ERROR only traits defined in the current crate
+
+impl Remote for i8 {
+//~^ ERROR only traits defined in the current crate
+    fn foo(&self) {
+        let _: Self = *self;
+    }
+}
+
+impl Remote for [u8] {}
+//~^ ERROR only traits defined in the current crate
+
+impl<T> Remote1<T> for i64 {}
+//~^ ERROR type parameter `T` must be used as the type parameter for some local type
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Suggest a local new type wrapper when implementing a foreign trait for a
+// foreign type, forwarding the uses of `self` in the impl to the wrapped value.
+// aux-build:coherence_lib.rs
+// run-rustfix
+
+#![allow(dead_code)]
+
+extern crate coherence_lib as lib;
+use lib::Remote;
+
+impl Remote for Vec<u8> {}
+//~^ ERROR only traits defined in the current crate
+
+impl Remote for u32 {}
+//~^ ERROR only traits defined in the current crate
+
+impl Remote for i16 { //~ ERROR only traits defined in the current crate
+    fn foo(&self) {
+        let _ = self.abs();
+        let _: &i16 = self;
+        let _ = *self + 1;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// revisions: full min
+//[full] check-pass
+#![cfg_attr(full, feature(generic_const_exprs))]
+#![cfg_attr(full, allow(incomplete_features))]
+
+// Without `generic_const_exprs`, defaults of const parameters are checked at the
+// definition, even if they are preceded by other generic parameters. With it, such
+// defaults have the substs of the preceding parameters whether or not they use them,
+// so they are only checked where they are used.
+
+struct Baz<const N: u8, const M: u8 = { N + 255 }>;
+//[min]~^ ERROR generic parameters may not be used in const operations
+
+struct Foo<const N: usize, const M: u8 = { 255 + 1 }>;
+//[min]~^ ERROR evaluation of constant value failed
+
+struct Bar<T, const M: u8 = { 255 + 1 }>(T);
+//[min]~^ ERROR evaluation of constant value failed
+
+fn main() {}
@@ -0,0 +1,22 @@
+// check-pass
+
+// The `ConstEquate` obligation between `4` and `?N + 1` is stalled on the const
+// inference variable `?N` and has to be processed again once `?N` is inferred.
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+
+struct Foo<const N: usize>;
+
+fn make<const N: usize>(_: &Foo<N>) -> [u8; N + 1]
+where
+    [(); N + 1]:,
+{
+    [0; N + 1]
+}
+
+fn main() {
+    let foo = Foo;
+    let arr: [u8; 4] = make(&foo);
+    let _: Foo<3> = foo;
+    assert_eq!(arr, [0; 4]);
+}
@@ -0,0 +1,21 @@
+struct Foo<const N: usize>;
+
+trait Trait {}
+impl Trait for Foo<2> {}
+impl Trait for Foo<4> {}
+
+trait Small {}
+macro_rules! impl_small {
+    ($($n:literal)*) => { $(impl Small for [u8; $n] {})* };
+}
+impl_small!(0 1 2 3 4 5 6 7 8);
+
+fn requires_trait<T: Trait>(_: T) {}
+fn requires_small<T: Small>(_: T) {}
+
+fn main() {
+    requires_trait(Foo::<3>);
+    //~^ ERROR the trait bound `Foo<3>: Trait` is not satisfied
+    requires_small([0u8; 12]);
+    //~^ ERROR the trait bound `[u8; 12]: Small` is not satisfied
+}
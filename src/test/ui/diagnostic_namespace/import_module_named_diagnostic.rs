@@ -0,0 +1,15 @@
+// check-pass
+// edition:2018
+
+// The implicit `diagnostic` tool module doesn't make imports of a module
+// called `diagnostic` ambiguous.
+
+mod diagnostic {
+    pub struct Diagnostic;
+}
+
+use diagnostic::Diagnostic;
+
+fn main() {
+    let _ = Diagnostic;
+}
@@ -0,0 +1,13 @@
+// check-pass
+#![feature(diagnostic_namespace)]
+
+#[diagnostic::non_existing_attribute]
+//~^WARN unknown diagnostic attribute
+pub trait Bar {
+}
+
+#[diagnostic::non_sense(foo)]
+//~^WARN unknown diagnostic attribute
+pub struct Foo;
+
+fn main() {}
@@ -0,0 +1,15 @@
+#![feature(diagnostic_namespace)]
+
+#[diagnostic::on_unimplemented(
+    message = "my message about `{Self}`",
+    label = "my label",
+    note = "my note"
+)]
+trait Foo {}
+
+fn takes_foo(_: impl Foo) {}
+
+fn main() {
+    takes_foo(());
+    //~^ ERROR my message about `()`
+}
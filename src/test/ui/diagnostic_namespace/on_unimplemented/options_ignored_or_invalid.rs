@@ -0,0 +1,43 @@
+#![feature(diagnostic_namespace)]
+
+#[diagnostic::on_unimplemented(unsupported = "foo")]
+//~^ WARN malformed `on_unimplemented` attribute
+trait Foo {}
+
+#[diagnostic::on_unimplemented(message = "Boom", unsupported = "Bar", label = "Boom")]
+//~^ WARN malformed `on_unimplemented` attribute
+trait Baz {}
+
+#[diagnostic::on_unimplemented = "Message"]
+//~^ WARN malformed `on_unimplemented` attribute
+trait Doom {}
+
+#[diagnostic::on_unimplemented(message = "Baz")]
+//~^ WARN `#[diagnostic::on_unimplemented]` can only be applied to trait definitions
+impl Doom for u8 {}
+
+#[diagnostic::on_unimplemented(message = "Baz")]
+//~^ WARN `#[diagnostic::on_unimplemented]` can only be applied to trait definitions
+struct Bar;
+
+#[diagnostic::on_unimplemented(message = "Baz")]
+//~^ WARN `#[diagnostic::on_unimplemented]` can only be applied to trait definitions
+fn bar() {}
+
+#[diagnostic::on_unimplemented(message = "{Missing}", label = "{}", note = "a note")]
+//~^ WARN there is no parameter `Missing` on trait `Qux`
+//~| WARN only named substitution parameters are allowed
+trait Qux {}
+
+fn takes_foo(_: impl Foo) {}
+fn takes_baz(_: impl Baz) {}
+fn takes_qux(_: impl Qux) {}
+
+fn main() {
+    takes_foo(());
+    //~^ ERROR the trait bound `(): Foo` is not satisfied
+    takes_baz(());
+    //~^ ERROR Boom
+    takes_qux(());
+    //~^ ERROR the trait bound `(): Qux` is not satisfied
+}
@@ -1,4 +1,5 @@
 // revisions: normal over_aligned
+// normalize-stderr-test "expected size \d+, align \d+" -> "expected size $$SIZE, align $$ALIGN"
 //[normal] check-pass
 
 #![feature(dyn_star)]
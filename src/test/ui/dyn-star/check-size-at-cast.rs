@@ -1,3 +1,5 @@
+// normalize-stderr-test "expected size \d+, align \d+" -> "expected size $$SIZE, align $$ALIGN"
+
 #![feature(dyn_star)]
 #![allow(incomplete_features)]
 
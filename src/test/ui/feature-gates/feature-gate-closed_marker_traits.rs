@@ -0,0 +1,4 @@
+#[closed_marker] trait Sealed {}
+//~^ ERROR the `#[closed_marker]` attribute is an experimental feature
+
+fn main() {}
@@ -0,0 +1,10 @@
+trait Bar {
+    const X: usize = 1;
+}
+
+fn make_bar<T: Bar>(t: &T) -> &dyn Bar {
+    //~^ ERROR E0038
+    t
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+trait X {
+    type Y<'a>
+    where
+        Self: 'a;
+
+    fn y(&self) -> Self::Y<'_>
+    where
+        Self: Sized;
+}
+
+fn _func1<'a>(_x: Box<dyn X<Y<'a>=&'a ()>>) {}
+//~^ ERROR the trait `X` cannot be made into an object
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[specialization_marker] trait Marker {}
+//~^ ERROR the `#[specialization_marker]` attribute is an experimental feature
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Check that a generic associated type that is only used by `where Self: Sized` methods of its
+// own trait still keeps a subtrait that mentions it in a method from being object safe.
+
+#![feature(object_safe_sized_gats)]
+
+trait Super {
+    type Gat<'a>
+    where
+        Self: 'a;
+
+    fn gat(&self) -> Self::Gat<'_>
+    where
+        Self: Sized;
+}
+
+trait Sub: Super {
+    fn f(&self) -> Self::Gat<'_>;
+}
+
+fn as_super(_: &dyn Super) {}
+
+fn as_sub<'a>(_: &dyn Sub<Gat<'a> = &'a ()>) {}
+//~^ ERROR the trait `Sub` cannot be made into an object
+
+fn main() {}
@@ -0,0 +1,44 @@
+// Check that a generic associated type that is only used by `where Self: Sized`
+// methods doesn't keep the trait from being used as a trait object.
+
+// run-pass
+
+#![feature(object_safe_sized_gats)]
+
+trait Lender {
+    type Item<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>>
+    where
+        Self: Sized;
+
+    fn remaining(&self) -> usize;
+}
+
+struct Windows(Vec<u8>, usize);
+
+impl Lender for Windows {
+    type Item<'a> = &'a [u8];
+
+    fn next(&mut self) -> Option<&[u8]> {
+        let start = self.1;
+        self.1 += 1;
+        self.0.get(start..start + 2)
+    }
+
+    fn remaining(&self) -> usize {
+        self.0.len().saturating_sub(self.1 + 1)
+    }
+}
+
+fn total(lenders: &[&dyn Lender]) -> usize {
+    lenders.iter().map(|l| l.remaining()).sum()
+}
+
+fn main() {
+    let mut w = Windows(vec![1, 2, 3], 0);
+    assert_eq!(w.next(), Some(&[1, 2][..]));
+    assert_eq!(total(&[&w, &Windows(vec![4, 5], 0)]), 2);
+}
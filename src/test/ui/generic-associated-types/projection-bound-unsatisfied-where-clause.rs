@@ -0,0 +1,29 @@
+// Check that the bounds of a generic associated type are still used to prove
+// obligations on it when its own where-clauses don't hold, so that the error
+// blames the unsatisfied where-clause and not the bounds.
+
+trait Tr {}
+
+trait Foo {
+    type Assoc<'a>: Tr
+    where
+        Self: 'a + Copy;
+
+    fn get<'a>(&'a self) -> Self::Assoc<'a>
+    where
+        Self: Copy;
+}
+
+fn need<X: Tr>(_: X) {}
+
+fn holds<T: Foo + Copy>(t: &T) {
+    need(t.get());
+}
+
+fn does_not_hold<T: Foo>(t: &T) {
+    need(t.get());
+    //~^ ERROR the trait bound `T: Copy` is not satisfied
+    //~| ERROR the trait bound `T: Copy` is not satisfied
+}
+
+fn main() {}
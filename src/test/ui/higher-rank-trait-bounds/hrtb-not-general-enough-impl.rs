@@ -0,0 +1,31 @@
+trait Trait {}
+
+struct S;
+
+impl Trait for &'static S {}
+
+fn require<X>(_: X)
+where
+    for<'b> &'b X: Trait,
+{
+}
+
+trait Other<'a> {}
+
+struct T;
+
+// The suggested lifetime must not collide with the one the impl already has.
+impl<'a> Other<'a> for &'static T {}
+
+fn require_other<X>(_: X)
+where
+    for<'b> &'b X: Other<'static>,
+{
+}
+
+fn main() {
+    require(S);
+    //~^ ERROR implementation of `Trait` is not general enough
+    require_other(T);
+    //~^ ERROR implementation of `Other` is not general enough
+}
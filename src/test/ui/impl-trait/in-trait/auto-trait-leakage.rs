@@ -0,0 +1,35 @@
+#![feature(return_position_impl_trait_in_trait)]
+#![allow(incomplete_features)]
+
+trait Foo {
+    fn foo(&self) -> impl Sized;
+}
+
+struct Local;
+
+impl Foo for Local {
+    fn foo(&self) -> impl Sized {
+        0u8
+    }
+}
+
+fn is_send<T: Send>(_: T) {}
+
+fn wrap<T: Foo>(x: T) -> impl Sized {
+    x.foo()
+}
+
+fn concrete() {
+    // Auto traits leak through the `impl Sized` of the impl providing `foo`...
+    is_send(Local.foo());
+    // ...including when it is the hidden type of another opaque type.
+    is_send(wrap(Local));
+}
+
+fn generic<T: Foo>(x: T) {
+    // Without a known impl, only the bounds written in the trait can be used.
+    is_send(x.foo());
+    //~^ ERROR `impl Sized` cannot be sent between threads safely
+}
+
+fn main() {}
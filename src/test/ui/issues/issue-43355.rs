@@ -7,12 +7,12 @@ pub trait Trait2<X> {}
 pub struct A;
 
 impl<X, T> Trait1<X> for T where T: Trait2<X> {
+//~^ downstream crates may implement trait `Trait2<std::boxed::Box<_>>` for type `A`
     type Output = ();
 }
 
 impl<X> Trait1<Box<X>> for A {
 //~^ ERROR conflicting implementations of trait
-//~| downstream crates may implement trait `Trait2<std::boxed::Box<_>>` for type `A`
     type Output = i32;
 }
 
@@ -0,0 +1,15 @@
+// compile-flags: --error-format json
+// Check that errors about unsatisfied trait bounds carry the chain of causes in the
+// `cause_chain` field of the JSON output, innermost cause first.
+
+#[derive(Clone)]
+struct Wrapper<T>(T);
+
+struct NotClone;
+
+fn requires_clone<T: Clone>(_: T) {}
+
+fn main() {
+    requires_clone(Wrapper(NotClone));
+    //~^ ERROR the trait bound `NotClone: Clone` is not satisfied
+}
@@ -0,0 +1,28 @@
+// Test that impls of a marker trait may overlap even if they differ in their
+// where-clauses or `~const` bounds, and that selection picks either of them.
+
+// check-pass
+
+#![feature(const_trait_impl)]
+#![feature(marker_trait_attr)]
+
+#[const_trait]
+trait Foo {}
+
+impl const Foo for u8 {}
+
+#[marker]
+#[const_trait]
+trait Marker {}
+
+impl<T: ~const Foo> const Marker for T {}
+impl<T: Copy> Marker for T {}
+impl<T: Clone + Default> Marker for T {}
+
+fn is_marker<T: Marker>() {}
+
+fn main() {
+    is_marker::<u8>();
+    is_marker::<u16>();
+    is_marker::<String>();
+}
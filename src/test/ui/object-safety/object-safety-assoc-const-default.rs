@@ -0,0 +1,34 @@
+// Check that `object_safe_assoc_consts` allows trait objects of traits whose
+// associated consts all have a default value, but not of other traits.
+
+#![feature(object_safe_assoc_consts)]
+
+trait Plugin {
+    const VERSION: u32 = 1;
+    fn name(&self) -> &'static str;
+}
+
+trait NoDefault {
+    const VERSION: u32;
+}
+
+struct Foo;
+
+impl Plugin for Foo {
+    fn name(&self) -> &'static str {
+        "foo"
+    }
+}
+
+fn as_plugin(p: &Foo) -> &dyn Plugin {
+    p
+}
+
+fn no_default<T: NoDefault>(t: &T) -> &dyn NoDefault {
+    //~^ ERROR E0038
+    t
+}
+
+fn main() {
+    assert_eq!(as_plugin(&Foo).name(), "foo");
+}
@@ -0,0 +1,31 @@
+// Check that with `object_safe_assoc_consts`, an associated const with a default value can't
+// be read through a trait object, which would have to use the default even for types whose
+// impl overrides it.
+
+#![feature(object_safe_assoc_consts)]
+
+trait Plugin {
+    const VERSION: u32 = 1;
+}
+
+struct Foo;
+
+impl Plugin for Foo {
+    const VERSION: u32 = 2;
+}
+
+fn version_of_dyn(_: &dyn Plugin) -> u32 {
+    <dyn Plugin as Plugin>::VERSION
+    //~^ ERROR the size for values of type `dyn Plugin` cannot be known at compilation time
+}
+
+fn version<T: Plugin + ?Sized>(_: &T) -> u32 {
+    T::VERSION
+    //~^ ERROR the size for values of type `T` cannot be known at compilation time
+}
+
+fn main() {
+    assert_eq!(Foo::VERSION, 2);
+    version_of_dyn(&Foo);
+    version(&Foo as &dyn Plugin);
+}
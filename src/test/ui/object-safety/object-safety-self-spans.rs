@@ -0,0 +1,16 @@
+// Check that the object safety error points at the uses of `Self` that make a
+// method not object safe, and not at the whole parameter or return type, nor at
+// uses of `Self` in projections.
+
+trait Tr {
+    type Item;
+
+    fn by_vec(&self, v: Vec<(&Self, &Self::Item)>);
+
+    fn pair(&self) -> (Option<Box<Self>>, Box<Self::Item>, Box<Self>);
+}
+
+fn f(_: &dyn Tr<Item = ()>) {}
+//~^ ERROR the trait `Tr` cannot be made into an object
+
+fn main() {}
@@ -0,0 +1,19 @@
+#![feature(rustc_attrs)]
+
+#[rustc_on_unimplemented(on(implements = "U: Clone", message = "a"))]
+//~^ ERROR invalid `implements` condition in `#[rustc_on_unimplemented]`
+trait UnknownParam<T> {}
+
+#[rustc_on_unimplemented(on(implements = "T: DoesNotExist", message = "b"))]
+//~^ ERROR invalid `implements` condition in `#[rustc_on_unimplemented]`
+trait UnknownTrait<T> {}
+
+#[rustc_on_unimplemented(on(implements = "T: std::clone::Clone", message = "c"))]
+//~^ ERROR invalid `implements` condition in `#[rustc_on_unimplemented]`
+trait NotADiagnosticItem<T> {}
+
+#[rustc_on_unimplemented(on(implements = "T", message = "d"))]
+//~^ ERROR invalid `implements` condition in `#[rustc_on_unimplemented]`
+trait MissingTrait<T> {}
+
+fn main() {}
@@ -0,0 +1,43 @@
+// Test that the `on` clauses of `#[rustc_on_unimplemented]` can depend on whether a generic
+// argument implements a trait.
+
+#![feature(rustc_attrs)]
+
+#[rustc_on_unimplemented(
+    on(
+        implements = "Self: Iterator",
+        message = "`{Self}` is an iterator, not a collection",
+        label = "consider collecting it first"
+    ),
+    on(implements = "T: Describe", note = "`{T}` can be described, but `{Self}` can't hold it"),
+    message = "`{Self}` is not a collection of `{T}`"
+)]
+trait Collection<T> {}
+
+#[rustc_diagnostic_item = "Describe"]
+trait Describe {}
+
+impl Describe for u8 {}
+
+struct Numbers;
+
+impl Iterator for Numbers {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        None
+    }
+}
+
+struct Label;
+
+fn takes_collection<C: Collection<T>, T>(_: C) {}
+
+fn main() {
+    takes_collection::<_, u8>(Numbers);
+    //~^ ERROR `Numbers` is an iterator, not a collection
+    takes_collection::<_, u8>(Label);
+    //~^ ERROR `Label` is not a collection of `u8`
+    takes_collection::<_, Label>(Label);
+    //~^ ERROR `Label` is not a collection of `Label`
+}
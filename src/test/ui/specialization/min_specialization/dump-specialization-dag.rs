@@ -0,0 +1,37 @@
+// Check the parents and always-applicable flags of the `specialization_dag` query.
+#![feature(min_specialization, rustc_attrs)]
+
+#[rustc_specialization_trait]
+trait Special {}
+
+#[rustc_dump_specialization_dag]
+trait Describe {
+    fn describe(&self);
+}
+
+impl<T> Describe for Option<T> {
+    //~^ ERROR always applicable: true
+    default fn describe(&self) {}
+}
+
+impl<T: Special> Describe for Option<Box<T>> {
+    //~^ ERROR always applicable: true
+    fn describe(&self) {}
+}
+
+impl<T: Clone> Describe for Vec<T> {
+    //~^ ERROR always applicable: false
+    fn describe(&self) {}
+}
+
+impl Describe for (&'static str,) {
+    //~^ ERROR always applicable: false
+    fn describe(&self) {}
+}
+
+impl<T> Describe for (T, T) {
+    //~^ ERROR always applicable: false
+    fn describe(&self) {}
+}
+
+fn main() {}
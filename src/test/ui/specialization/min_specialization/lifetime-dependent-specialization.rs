@@ -0,0 +1,41 @@
+// Check that `lifetime_dependent_specialization` points out every use of a
+// specializing impl which is only known to apply for some lifetimes, including
+// uses whose selection is already cached.
+#![feature(min_specialization, rustc_attrs)]
+#![deny(lifetime_dependent_specialization)]
+
+#[rustc_unsafe_specialization_marker]
+trait StaticMarker {}
+impl StaticMarker for &'static str {}
+
+trait Describe {
+    fn describe(&self) -> &'static str;
+}
+
+impl<T> Describe for T {
+    default fn describe(&self) -> &'static str {
+        "any"
+    }
+}
+
+impl<T: StaticMarker> Describe for T {
+    fn describe(&self) -> &'static str {
+        "static"
+    }
+}
+
+fn first(s: &'static str) -> &'static str {
+    s.describe()
+    //~^ ERROR specializing impl selected for `&str: Describe` based on lifetimes
+}
+
+fn second(s: &'static str) -> &'static str {
+    <&'static str as Describe>::describe(&s)
+    //~^ ERROR specializing impl selected for `&str: Describe` based on lifetimes
+}
+
+fn not_specialized(x: u8) -> &'static str {
+    x.describe()
+}
+
+fn main() {}
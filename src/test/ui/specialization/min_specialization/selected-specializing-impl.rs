@@ -0,0 +1,36 @@
+// Check that `selected_specializing_impl` points out every use of an impl overridden by a
+// specializing impl, including uses whose selection is already cached.
+#![feature(min_specialization)]
+#![deny(selected_specializing_impl)]
+
+trait Describe {
+    fn describe(&self) -> &'static str;
+}
+
+impl<T> Describe for T {
+    default fn describe(&self) -> &'static str {
+        "something"
+    }
+}
+
+impl Describe for u8 {
+    fn describe(&self) -> &'static str {
+        "a byte"
+    }
+}
+
+fn first() -> &'static str {
+    0u8.describe()
+    //~^ ERROR specializing impl selected for `u8: Describe`
+}
+
+fn second() -> &'static str {
+    <u8 as Describe>::describe(&1)
+    //~^ ERROR specializing impl selected for `u8: Describe`
+}
+
+fn main() {
+    first();
+    second();
+    0u16.describe();
+}
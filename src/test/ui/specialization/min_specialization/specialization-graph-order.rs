@@ -0,0 +1,48 @@
+// Test that the overlap check orders impls which min_specialization accepts
+// as always applicable, regardless of the order they are declared in.
+
+// run-pass
+
+#![feature(min_specialization)]
+#![feature(rustc_attrs)]
+
+#[rustc_specialization_trait]
+trait SpecTrait {}
+
+trait X {
+    fn f(&self) -> u32;
+}
+
+// Declared before the impls it specializes.
+impl X for u8 {
+    fn f(&self) -> u32 {
+        2
+    }
+}
+
+impl<T: SpecTrait> X for Option<T> {
+    default fn f(&self) -> u32 {
+        1
+    }
+}
+
+impl<T> X for T {
+    default fn f(&self) -> u32 {
+        0
+    }
+}
+
+impl SpecTrait for u16 {}
+
+impl X for Option<u16> {
+    fn f(&self) -> u32 {
+        3
+    }
+}
+
+fn main() {
+    assert_eq!('a'.f(), 0);
+    assert_eq!(0u8.f(), 2);
+    assert_eq!(Some(0u32).f(), 0);
+    assert_eq!(Some(0u16).f(), 3);
+}
@@ -0,0 +1,43 @@
+// Check that `#[specialization_marker]` traits can be specialized on, but can't
+// have impls that depend on lifetimes.
+
+#![feature(min_specialization)]
+#![feature(specialization_marker_traits)]
+
+#[specialization_marker]
+trait Marker {}
+
+impl Marker for u8 {}
+impl<'a, T> Marker for &'a [T] {}
+impl Marker for &'static str {}
+//~^ ERROR impls of the specialization marker trait `Marker` cannot depend on lifetimes
+impl<'a> Marker for (&'a u16, &'a u16) {}
+//~^ ERROR impls of the specialization marker trait `Marker` cannot depend on lifetimes
+impl<'a, T: 'a> Marker for (&'a u32, T) {}
+//~^ ERROR impls of the specialization marker trait `Marker` cannot depend on lifetimes
+
+trait WithLifetime<'a> {}
+
+impl<T> Marker for [T; 1] where T: WithLifetime<'static> {}
+//~^ ERROR impls of the specialization marker trait `Marker` cannot depend on lifetimes
+impl<'a, T> Marker for (&'a u64, [T; 2]) where T: WithLifetime<'a> {}
+//~^ ERROR impls of the specialization marker trait `Marker` cannot depend on lifetimes
+impl<T> Marker for [T; 3] where T: for<'a> WithLifetime<'a> {}
+
+trait Spec {
+    fn spec_me(&self) -> bool;
+}
+
+impl<T> Spec for T {
+    default fn spec_me(&self) -> bool {
+        false
+    }
+}
+
+impl<T: Marker> Spec for T {
+    fn spec_me(&self) -> bool {
+        true
+    }
+}
+
+fn main() {}
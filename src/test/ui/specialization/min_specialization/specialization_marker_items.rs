@@ -0,0 +1,16 @@
+// Check that `#[specialization_marker]` traits can't have associated items.
+
+#![feature(min_specialization)]
+#![feature(specialization_marker_traits)]
+
+#[specialization_marker]
+trait Marker {
+    fn method(&self);
+    //~^ ERROR marker traits cannot have associated items
+    type Assoc;
+    //~^ ERROR marker traits cannot have associated items
+    const CONST: u8;
+    //~^ ERROR marker traits cannot have associated items
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+// Test that associated type equalities with concrete, lifetime-free types can
+// be specialized on if the base impl has the corresponding trait bound.
+
+// run-pass
+
+#![feature(min_specialization)]
+
+trait SpecSum {
+    fn spec_sum(self) -> u64;
+}
+
+impl<I: Iterator> SpecSum for I {
+    default fn spec_sum(self) -> u64 {
+        self.count() as u64
+    }
+}
+
+impl<I: Iterator<Item = u8>> SpecSum for I {
+    fn spec_sum(self) -> u64 {
+        self.map(u64::from).sum()
+    }
+}
+
+fn main() {
+    assert_eq!(vec![1u8, 2, 3].into_iter().spec_sum(), 6);
+}
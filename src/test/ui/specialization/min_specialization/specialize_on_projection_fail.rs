@@ -0,0 +1,37 @@
+// Test that associated type equalities can't be specialized on if they
+// mention lifetimes or the base impl lacks the trait bound.
+
+#![feature(min_specialization)]
+
+trait Tr {
+    type Assoc;
+}
+
+trait Spec {
+    fn f(self);
+}
+
+impl<T: Tr> Spec for T {
+    default fn f(self) {}
+}
+
+impl<T: Tr<Assoc = &'static u8>> Spec for T {
+    //~^ ERROR cannot specialize on associated type
+    fn f(self) {}
+}
+
+trait Spec2 {
+    fn g(self);
+}
+
+impl<T> Spec2 for T {
+    default fn g(self) {}
+}
+
+impl<T: Tr<Assoc = u8>> Spec2 for T {
+    //~^ ERROR cannot specialize on trait `Tr`
+    //~| ERROR cannot specialize on associated type
+    fn g(self) {}
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// Test that a `default` associated type stays opaque in const contexts if the
+// impl defining it isn't always applicable.
+
+#![feature(specialization)]
+#![allow(incomplete_features)]
+
+trait Tr {
+    type Out;
+}
+
+impl<T: Clone> Tr for T {
+    default type Out = u8;
+}
+
+const N: () = {
+    let _x: <u32 as Tr>::Out = 3u8;
+    //~^ ERROR mismatched types
+};
+
+fn main() {
+    let _ = N;
+}
@@ -0,0 +1,24 @@
+// Test that a `default` associated type of an always applicable impl can be
+// projected out of a monomorphic trait ref in const contexts.
+
+// check-pass
+
+#![feature(specialization)]
+#![allow(incomplete_features)]
+
+trait Tr {
+    type Out;
+}
+
+impl<T> Tr for T {
+    default type Out = u8;
+}
+
+const N: usize = {
+    let x: <u32 as Tr>::Out = 3u8;
+    x as usize
+};
+
+fn main() {
+    let _ = N;
+}
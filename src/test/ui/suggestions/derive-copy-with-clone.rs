@@ -0,0 +1,19 @@
+// Check that deriving `Copy` is suggested together with the missing `Clone`.
+#![allow(dead_code)]
+
+struct NotCopy;
+
+struct HasDrop;
+
+impl Drop for HasDrop {
+    fn drop(&mut self) {}
+}
+
+fn requires_copy<T: Copy>(_: T) {}
+
+fn main() {
+    requires_copy(NotCopy);
+    //~^ ERROR the trait bound `NotCopy: Copy` is not satisfied
+    requires_copy(HasDrop);
+    //~^ ERROR the trait bound `HasDrop: Copy` is not satisfied
+}
@@ -0,0 +1,21 @@
+trait Convert<T: ?Sized> {
+    fn convert(&self) -> &T;
+}
+
+struct Local;
+
+impl Convert<u32> for Local {
+    fn convert(&self) -> &u32 {
+        &1
+    }
+}
+
+fn ambiguous<T: ?Sized, U: ?Sized>(x: Local)
+where
+    Local: Convert<T> + Convert<U>,
+{
+    let _ = x.convert();
+    //~^ ERROR type annotations needed
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// Check that the access path note of a generator that is not `async` talks about yield points
+// rather than await points.
+
+#![feature(generators)]
+
+use std::rc::Rc;
+
+struct Foo {
+    rc: Rc<u8>,
+}
+
+fn is_send<T: Send>(_: T) {}
+
+fn main() {
+    is_send(|| {
+        //~^ ERROR generator cannot be sent between threads safely
+        let foo = Foo { rc: Rc::new(0) };
+        yield;
+    });
+}
@@ -0,0 +1,30 @@
+// Check that an unsatisfied auto trait obligation on a deeply nested field
+// notes the path leading to it, including enum variants, array elements and
+// pointees.
+
+struct Foo {
+    bar: Bar,
+}
+
+struct Bar {
+    baz: (u8, Baz),
+}
+
+struct Baz {
+    ptr: *mut u8,
+}
+
+enum Wrapper {
+    Empty,
+    Full { items: [&'static Baz; 2] },
+}
+
+fn is_send<T: Send>() {}
+fn is_sync<T: Sync>() {}
+
+fn main() {
+    is_send::<Foo>();
+    //~^ ERROR `*mut u8` cannot be sent between threads safely
+    is_sync::<Wrapper>();
+    //~^ ERROR `*mut u8` cannot be shared between threads safely
+}
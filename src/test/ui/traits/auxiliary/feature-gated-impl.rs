@@ -0,0 +1,6 @@
+pub trait Serialize {}
+
+pub struct DateTime;
+
+#[cfg(feature = "serde")]
+impl Serialize for DateTime {}
@@ -0,0 +1,10 @@
+#![feature(marker_trait_attr)]
+
+#[marker]
+pub trait Tagged {}
+
+pub struct Label<T>(pub T);
+
+impl<T: Copy> Tagged for Label<T> {}
+
+impl<T: Clone + Default> Tagged for Label<T> {}
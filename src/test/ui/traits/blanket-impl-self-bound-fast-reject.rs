@@ -0,0 +1,35 @@
+// run-pass
+
+// Check that a blanket impl whose self type bound can be cheaply rejected
+// during candidate assembly does not affect selecting the other impls.
+
+trait Bound {}
+trait Trait {
+    fn method(&self) -> u32;
+}
+
+impl<T: Bound> Trait for T {
+    fn method(&self) -> u32 {
+        0
+    }
+}
+
+struct Local;
+impl Trait for Local {
+    fn method(&self) -> u32 {
+        1
+    }
+}
+
+struct Bounded;
+impl Bound for Bounded {}
+
+fn generic<T: Trait>(t: T) -> u32 {
+    t.method()
+}
+
+fn main() {
+    assert_eq!(generic(Local), 1);
+    assert_eq!(generic(Bounded), 0);
+    assert_eq!(Local.method(), 1);
+}
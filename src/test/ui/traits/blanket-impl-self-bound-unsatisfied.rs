@@ -0,0 +1,23 @@
+// Check that blanket impls which are rejected early because of a bound on
+// their self type still lead to ordinary errors when nothing else applies,
+// including when their header does not match the obligation either.
+
+trait Bound {}
+
+trait Trait {}
+impl<T: Bound> Trait for T {}
+
+trait Generic<U> {}
+impl<T: Bound> Generic<T> for T {}
+
+struct Local;
+
+fn requires_trait<T: Trait>(_: T) {}
+fn requires_generic<T: Generic<u16>>(_: T) {}
+
+fn main() {
+    requires_trait(Local);
+    //~^ ERROR the trait bound `Local: Bound` is not satisfied
+    requires_generic(Local);
+    //~^ ERROR the trait bound `Local: Generic<u16>` is not satisfied
+}
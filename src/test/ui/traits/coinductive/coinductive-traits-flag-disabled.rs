@@ -0,0 +1,21 @@
+// Without `-Zcoinductive-traits`, a cycle between trait goals is still
+// reported as overflow. See `coinductive-traits-flag.rs` for the same cycle
+// with the flag.
+
+struct List<T> {
+    head: T,
+    tail: Option<Box<List<T>>>,
+}
+
+trait Trait {}
+
+impl<T> Trait for List<T> where Option<Box<List<T>>>: Trait {}
+impl<T: Trait> Trait for Option<T> {}
+impl<T: Trait> Trait for Box<T> {}
+
+fn requires_trait<T: Trait>() {}
+
+fn main() {
+    requires_trait::<List<u8>>();
+    //~^ ERROR overflow evaluating the requirement
+}
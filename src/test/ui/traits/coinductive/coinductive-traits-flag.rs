@@ -0,0 +1,23 @@
+// check-pass
+// compile-flags: -Zcoinductive-traits
+
+// With `-Zcoinductive-traits`, a cycle between trait goals is accepted
+// instead of being reported as overflow, the same way it already is for
+// auto traits.
+
+struct List<T> {
+    head: T,
+    tail: Option<Box<List<T>>>,
+}
+
+trait Trait {}
+
+impl<T> Trait for List<T> where Option<Box<List<T>>>: Trait {}
+impl<T: Trait> Trait for Option<T> {}
+impl<T: Trait> Trait for Box<T> {}
+
+fn requires_trait<T: Trait>() {}
+
+fn main() {
+    requires_trait::<List<u8>>();
+}
@@ -0,0 +1,21 @@
+// compile-flags: -Zimplied-bounds-from-impl-headers
+
+// The where clauses of `Wrapper` are only assumed within the items of the impl.
+// The impl header itself must still be well-formed without them.
+
+struct Wrapper<T: Clone>(T);
+
+trait Duplicate {
+    fn duplicate(&self) -> (Self, Self)
+    where
+        Self: Sized;
+}
+
+impl<T> Duplicate for Wrapper<T> {
+    //~^ ERROR the trait bound `T: Clone` is not satisfied
+    fn duplicate(&self) -> (Self, Self) {
+        (Wrapper(self.0.clone()), Wrapper(self.0.clone()))
+    }
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+// revisions: implied not_implied
+//[implied] check-pass
+//[implied] compile-flags: -Zimplied-bounds-from-impl-headers
+
+// `Wrapper<T>` is well-formed for every `T` through the blanket impl below, so the header
+// does not need to restate `T: Convert<u8>`. Only with the flag is that bound a where
+// clause within the items of the impl, where it selects which `convert` gets called.
+
+trait Convert<T> {
+    fn convert(&self) -> T;
+}
+
+impl<X> Convert<u8> for X {
+    fn convert(&self) -> u8 {
+        8
+    }
+}
+
+impl<X> Convert<u16> for X {
+    fn convert(&self) -> u16 {
+        16
+    }
+}
+
+struct Wrapper<T: Convert<u8>>(T);
+
+trait Bits {
+    fn bits(&self) -> u32;
+}
+
+impl<T> Bits for Wrapper<T> {
+    fn bits(&self) -> u32 {
+        let x = self.0.convert();
+        //[not_implied]~^ ERROR type annotations needed
+        x.count_ones()
+    }
+}
+
+fn main() {
+    assert_eq!(Wrapper(()).bits(), 1);
+}
@@ -0,0 +1,40 @@
+// run-pass
+// compile-flags: -Zlazy-normalization-in-selection
+
+// Impl headers containing projections are matched without normalizing them
+// first; the projections are normalized through the nested obligations.
+
+trait Id {
+    type Assoc;
+}
+
+impl<T> Id for T {
+    type Assoc = T;
+}
+
+trait Trait<U> {
+    fn method(&self) -> U;
+}
+
+impl<T: Copy> Trait<<T as Id>::Assoc> for Vec<T> {
+    fn method(&self) -> T {
+        self[0]
+    }
+}
+
+impl Trait<String> for Vec<u8> {
+    fn method(&self) -> String {
+        String::from_utf8_lossy(self).into_owned()
+    }
+}
+
+fn call<U, T: Trait<U>>(t: &T) -> U {
+    t.method()
+}
+
+fn main() {
+    let x: u32 = call(&vec![1u32]);
+    let s: String = call(&vec![b'a']);
+    let b: u8 = call(&vec![b'a']);
+    assert_eq!((x, s.as_str(), b), (1, "a", b'a'));
+}
@@ -0,0 +1,15 @@
+// Check that overly long self types in unsatisfied trait bound errors are shortened, with the
+// full type written out to a file instead.
+// normalize-stderr-test: "long-type-\d+" -> "long-type-hash"
+
+type Nested = Option<Option<Option<Option<Option<Option<Option<Option<i32>>>>>>>>;
+
+trait Trait {}
+
+fn require<T: Trait>(_: T) {}
+
+fn main() {
+    let x: Nested = None;
+    require(x);
+    //~^ ERROR the trait bound `Option<Option<Option<Option<Option<Option<...>>>>>>: Trait` is not satisfied
+}
@@ -0,0 +1,17 @@
+// Check that we point at the negative impl when it is the reason a
+// trait bound isn't satisfied.
+
+#![feature(negative_impls)]
+
+trait Greet {}
+
+struct Quiet;
+
+impl !Greet for Quiet {}
+
+fn greet<T: Greet>(_: T) {}
+
+fn main() {
+    greet(Quiet);
+    //~^ ERROR the trait bound `Quiet: Greet` is not satisfied
+}
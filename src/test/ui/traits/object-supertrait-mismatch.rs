@@ -0,0 +1,12 @@
+trait Bar<T> {}
+trait Mid: Bar<u32> {}
+trait Foo: Mid {}
+
+fn requires_bar<B: Bar<i32> + ?Sized>(_: &B) {}
+
+fn test(foo: &dyn Foo) {
+    requires_bar(foo);
+    //~^ ERROR the trait bound `dyn Foo: Bar<i32>` is not satisfied
+}
+
+fn main() {}
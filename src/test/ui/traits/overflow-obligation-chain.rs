@@ -0,0 +1,35 @@
+// Check that an overflow without a repeating cycle of obligations notes the
+// chain of obligations that caused it, eliding its middle.
+#![recursion_limit = "8"]
+
+trait Trait {}
+
+struct A<T>(T);
+struct B<T>(T);
+struct C<T>(T);
+struct D<T>(T);
+struct E<T>(T);
+struct F<T>(T);
+struct G<T>(T);
+struct H<T>(T);
+struct I<T>(T);
+struct J<T>(T);
+
+impl<T: Trait> Trait for A<T> {}
+impl<T: Trait> Trait for B<T> {}
+impl<T: Trait> Trait for C<T> {}
+impl<T: Trait> Trait for D<T> {}
+impl<T: Trait> Trait for E<T> {}
+impl<T: Trait> Trait for F<T> {}
+impl<T: Trait> Trait for G<T> {}
+impl<T: Trait> Trait for H<T> {}
+impl<T: Trait> Trait for I<T> {}
+impl<T: Trait> Trait for J<T> {}
+impl Trait for () {}
+
+fn require_trait<T: Trait>() {}
+
+fn main() {
+    require_trait::<A<B<C<D<E<F<G<H<I<J<()>>>>>>>>>>>();
+    //~^ ERROR overflow evaluating the requirement
+}
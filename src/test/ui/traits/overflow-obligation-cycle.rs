@@ -0,0 +1,14 @@
+// Check that an overflow points out the cycle of obligations that caused it.
+#![recursion_limit = "16"]
+
+trait Trait {}
+
+struct A<T>(T);
+struct B<T>(T);
+
+impl<T> Trait for A<T> where B<T>: Trait {}
+//~^ ERROR overflow evaluating the requirement
+impl<T> Trait for B<T> where A<A<T>>: Trait {}
+//~^ ERROR overflow evaluating the requirement
+
+fn main() {}
@@ -0,0 +1,33 @@
+// Thin pointers and function pointers are pointer-sized without their layout
+// being computed, but wide pointers must still go through the layout check.
+
+#![feature(pointer_sized_trait)]
+
+use std::fmt::Debug;
+use std::marker::PointerSized;
+
+fn is_pointer_sized<T: PointerSized + ?Sized>() {}
+
+fn thin<T>() {
+    is_pointer_sized::<&u8>();
+    is_pointer_sized::<&mut [u8; 4]>();
+    is_pointer_sized::<*const String>();
+    is_pointer_sized::<*mut (u8, u16)>();
+    is_pointer_sized::<&&str>();
+    is_pointer_sized::<fn(&str) -> usize>();
+    is_pointer_sized::<&T>();
+    is_pointer_sized::<*const T>();
+}
+
+fn wide<T: ?Sized>() {
+    is_pointer_sized::<&[u8]>();
+    //~^ ERROR `&[u8]` needs to be a pointer-sized type
+    is_pointer_sized::<&str>();
+    //~^ ERROR `&str` needs to be a pointer-sized type
+    is_pointer_sized::<*const dyn Debug>();
+    //~^ ERROR `*const dyn Debug` needs to be a pointer-sized type
+    is_pointer_sized::<&T>();
+    //~^ ERROR `&T` needs to be a pointer-sized type
+}
+
+fn main() {}
@@ -0,0 +1,55 @@
+// check-pass
+
+// When evaluating an obligation failed, we used to discard every provisional
+// result instead of only the ones that were added while evaluating it. Here
+// `Bad<()>: Send` fails (through an inductive cycle, so the remaining fields are
+// still evaluated) between the two evaluations of `PhantomData<L{n+1}>: Send`,
+// whose result is provisional because of the cycle back to `L0`. Discarding it
+// made evaluating `L0: Send`, which happens when probing for `m` below, take time
+// exponential in the number of levels.
+
+#![allow(dead_code)]
+
+use std::marker::PhantomData;
+
+trait Tr {}
+impl<T> Tr for Bad<T> where Bad<T>: Tr {}
+
+struct Bad<T>(T);
+unsafe impl<T> Send for Bad<T> where Bad<T>: Tr {}
+
+macro_rules! levels {
+    ($last:ident) => {
+        struct $last(PhantomData<L0>);
+    };
+    ($level:ident $next:ident $($rest:ident)*) => {
+        struct $level {
+            a: PhantomData<$next>,
+            bad: Bad<()>,
+            b: PhantomData<$next>,
+        }
+        levels!($next $($rest)*);
+    };
+}
+
+levels!(
+    L0 L1 L2 L3 L4 L5 L6 L7 L8 L9 L10 L11 L12 L13 L14 L15
+    L16 L17 L18 L19 L20 L21 L22 L23 L24 L25 L26 L27 L28 L29 L30 L31 L32
+);
+
+trait A {
+    fn m(&self) {}
+}
+impl<T: Send> A for T {}
+
+trait B {
+    fn m(&self) {}
+}
+impl<T> B for T {}
+
+fn call(x: &L0) {
+    // `A::m` does not apply because `L0: Send` does not hold.
+    x.m();
+}
+
+fn main() {}
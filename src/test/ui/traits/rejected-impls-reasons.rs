@@ -0,0 +1,44 @@
+// aux-build:rejected_impls_reasons.rs
+
+// Check that we explain every reason why the impls of a trait were rejected: all the
+// unsatisfied bounds of an impl, the types an impl requires generic parameters to be,
+// and the impls of other crates.
+
+#![feature(marker_trait_attr)]
+
+extern crate rejected_impls_reasons;
+
+use rejected_impls_reasons::{Label, Tagged};
+
+#[marker]
+trait Measure {}
+
+struct Meters<T>(T);
+
+struct Plain;
+
+impl<T: Copy + Default> Measure for Meters<T> {}
+
+impl<T: Ord> Measure for Meters<T> {}
+
+fn measure<T: Measure>(_: T) {}
+
+trait Unit {}
+
+impl Unit for Meters<u32> {}
+
+fn unit<T: Unit>(_: T) {}
+
+fn tag<T: Tagged>(_: T) {}
+
+fn generic<T>(value: T) {
+    unit(Meters(value));
+    //~^ ERROR the trait bound `Meters<T>: Unit` is not satisfied
+}
+
+fn main() {
+    measure(Meters(Plain));
+    //~^ ERROR the trait bound `Meters<Plain>: Measure` is not satisfied
+    tag(Label(Plain));
+    //~^ ERROR the trait bound `Label<Plain>: Tagged` is not satisfied
+}
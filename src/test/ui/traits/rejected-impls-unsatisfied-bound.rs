@@ -0,0 +1,22 @@
+// Check that we point at the bounds of the impls which would have applied if they were
+// satisfied, when none of the impls is selected.
+
+#![feature(marker_trait_attr)]
+
+#[marker]
+trait Describe {}
+
+struct Meters<T>(T);
+
+struct Plain;
+
+impl<T: Copy> Describe for Meters<T> {}
+
+impl<T: Default> Describe for Meters<T> {}
+
+fn describe<T: Describe>(_: T) {}
+
+fn main() {
+    describe(Meters(Plain));
+    //~^ ERROR the trait bound `Meters<Plain>: Describe` is not satisfied
+}
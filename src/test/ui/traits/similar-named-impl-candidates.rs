@@ -0,0 +1,19 @@
+// Check that impls for local types whose name is close to the self type of an
+// unsatisfied obligation are listed among the similar impl candidates.
+
+trait Speak {}
+
+struct Wrapper<T>(T);
+struct Wrappr<T>(T);
+struct Unrelated;
+
+impl Speak for Wrapper<u8> {}
+impl Speak for Wrappr<u16> {}
+impl Speak for Unrelated {}
+
+fn needs<T: Speak>(_: T) {}
+
+fn main() {
+    needs(Wrapper(0u32));
+    //~^ ERROR the trait bound `Wrapper<u32>: Speak` is not satisfied
+}
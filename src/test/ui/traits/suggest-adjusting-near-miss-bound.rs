@@ -0,0 +1,17 @@
+// Check that when a where-clause for the right trait but with different
+// generic arguments is in scope, we suggest adjusting it.
+
+trait Convert<T> {
+    fn convert(&self) -> T;
+}
+
+fn wants_u64<T: Convert<u64>>(t: &T) -> u64 {
+    t.convert()
+}
+
+fn has_u32<T: Convert<u32>>(t: &T) -> u64 {
+    wants_u64(t)
+    //~^ ERROR the trait bound `T: Convert<u64>` is not satisfied
+}
+
+fn main() {}
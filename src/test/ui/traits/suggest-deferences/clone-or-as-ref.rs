@@ -0,0 +1,20 @@
+trait Consume {}
+impl Consume for u32 {}
+impl Consume for String {}
+impl Consume for Option<&String> {}
+
+fn consume<T: Consume>(_: T) {}
+
+fn main() {
+    let n = &5u32;
+    consume(n);
+    //~^ ERROR the trait bound `&u32: Consume` is not satisfied
+
+    let s = &String::new();
+    consume(s);
+    //~^ ERROR the trait bound `&String: Consume` is not satisfied
+
+    let o: Option<String> = None;
+    consume(o);
+    //~^ ERROR the trait bound `Option<String>: Consume` is not satisfied
+}
@@ -0,0 +1,22 @@
+// Check that we don't suggest enabling the feature of a dependency for a trait or a type which
+// only has the same name as the ones in the impl the feature would provide.
+// aux-build:feature-gated-impl.rs
+
+extern crate feature_gated_impl;
+
+mod local {
+    pub trait Serialize {}
+
+    pub struct DateTime;
+}
+
+fn serialize_local<T: local::Serialize>(_: T) {}
+
+fn serialize<T: feature_gated_impl::Serialize>(_: T) {}
+
+fn main() {
+    serialize_local(feature_gated_impl::DateTime);
+    //~^ ERROR the trait bound `DateTime: local::Serialize` is not satisfied
+    serialize(local::DateTime);
+    //~^ ERROR the trait bound `local::DateTime: feature_gated_impl::Serialize` is not satisfied
+}
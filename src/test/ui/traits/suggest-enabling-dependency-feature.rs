@@ -0,0 +1,13 @@
+// Check that we suggest enabling the feature of a dependency that would provide a missing impl.
+// aux-build:feature-gated-impl.rs
+
+extern crate feature_gated_impl;
+
+use feature_gated_impl::{DateTime, Serialize};
+
+fn serialize<T: Serialize>(_: T) {}
+
+fn main() {
+    serialize(DateTime);
+    //~^ ERROR the trait bound `DateTime: Serialize` is not satisfied
+}
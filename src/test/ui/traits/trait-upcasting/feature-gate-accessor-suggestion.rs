@@ -0,0 +1,27 @@
+// Check that an accessor method is only described when trait upcasting is used without the
+// feature if the subtrait is local, and that it keeps the supertrait's generic arguments.
+
+trait Foo {}
+
+trait Bar: Foo {}
+
+trait Gen<T> {}
+
+trait Sub: Gen<u8> {}
+
+fn mutable(bar: &mut dyn Bar) -> &mut dyn Foo {
+    bar
+    //~^ ERROR trait upcasting coercion is experimental [E0658]
+}
+
+fn generic(sub: &dyn Sub) -> &dyn Gen<u8> {
+    sub
+    //~^ ERROR trait upcasting coercion is experimental [E0658]
+}
+
+fn foreign(err: &dyn std::error::Error) -> &dyn std::fmt::Debug {
+    err
+    //~^ ERROR trait upcasting coercion is experimental [E0658]
+}
+
+fn main() {}
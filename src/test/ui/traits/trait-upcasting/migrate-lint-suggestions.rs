@@ -0,0 +1,30 @@
+// run-rustfix
+// rustfix-only-machine-applicable
+#![deny(deref_into_dyn_supertrait)]
+#![allow(dead_code)]
+
+use std::ops::{Deref, DerefMut};
+
+trait A {}
+trait B: A {}
+impl<'a> Deref for dyn 'a + B {
+    type Target = dyn A + 'a;
+    fn deref(&self) -> &Self::Target {
+        todo!()
+    }
+}
+impl<'a> DerefMut for dyn 'a + B {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        todo!()
+    }
+}
+
+fn take_a_mut(_: &mut dyn A) {}
+
+fn whoops(b: &mut dyn B) {
+    take_a_mut(b)
+    //~^ ERROR `dyn B` implements `Deref` with supertrait `A` as output
+    //~| WARN this was previously accepted by the compiler but is being phased out
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+// Check that the notes for obligations introduced by impls point at the where-clause
+// that introduced them, including through chains of blanket impls.
+
+trait Shape {}
+trait Named {}
+trait Describe {}
+trait Report {}
+
+impl<T: Shape> Named for T {}
+
+impl<T> Describe for T
+where
+    T: Named,
+{
+}
+
+impl<T> Report for Wrapper<T> where T: Describe {}
+
+struct Wrapper<T>(T);
+struct Blob;
+
+fn report<R: Report>(_: R) {}
+
+fn main() {
+    report(Wrapper(Blob));
+    //~^ ERROR the trait bound `Blob: Shape` is not satisfied
+}
@@ -0,0 +1,36 @@
+//! Transmutations between references must not increase alignment or uniqueness.
+
+#![crate_type = "lib"]
+#![feature(transmutability)]
+#![allow(dead_code, incomplete_features, non_camel_case_types)]
+
+mod assert {
+    use std::mem::{Assume, BikeshedIntrinsicFrom};
+    pub struct Context;
+
+    pub fn is_maybe_transmutable<Src, Dst>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, Context, {
+            Assume {
+                alignment: false,
+                lifetimes: false,
+                safety: true,
+                validity: false,
+            }
+        }>
+    {}
+}
+
+fn stricter_alignment() {
+    assert::is_maybe_transmutable::<&'static [u8; 2], &'static u16>(); //~ ERROR cannot be safely transmuted
+}
+
+fn shared_to_mut() {
+    assert::is_maybe_transmutable::<&'static u8, &'static mut u8>(); //~ ERROR cannot be safely transmuted
+}
+
+fn no_referent() {
+    #[derive(Clone, Copy)] #[repr(u8)] enum Ox01 { V = 0x01 }
+    #[repr(C)] union BoolOrOx01 { b: &'static bool, o: &'static Ox01 }
+    assert::is_maybe_transmutable::<&'static u8, BoolOrOx01>(); //~ ERROR cannot be safely transmuted
+}
@@ -1,4 +1,6 @@
-//! Transmutations involving references are not yet supported.
+// check-pass
+
+//! Transmutations between references are allowed when the referents are transmutable.
 
 #![crate_type = "lib"]
 #![feature(transmutability)]
@@ -9,6 +11,18 @@ mod assert {
     pub struct Context;
 
     pub fn is_maybe_transmutable<Src, Dst>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, Context, {
+            Assume {
+                alignment: false,
+                lifetimes: false,
+                safety: true,
+                validity: false,
+            }
+        }>
+    {}
+
+    pub fn is_maybe_transmutable_assuming_all<Src, Dst>()
     where
         Dst: BikeshedIntrinsicFrom<Src, Context, {
             Assume {
@@ -21,7 +35,33 @@ pub fn is_maybe_transmutable<Src, Dst>()
     {}
 }
 
-fn not_yet_implemented() {
+fn same_referent() {
+    #[repr(C)] struct Unit;
+    assert::is_maybe_transmutable::<&'static Unit, &'static Unit>();
+}
+
+fn transmutable_referent() {
+    assert::is_maybe_transmutable::<&'static u8, &'static i8>();
+    assert::is_maybe_transmutable::<&'static [u8; 2], &'static u8>();
+}
+
+fn mut_to_shared() {
+    assert::is_maybe_transmutable::<&'static mut u8, &'static u8>();
+    assert::is_maybe_transmutable::<&'static mut u8, &'static mut i8>();
+}
+
+fn shorter_lifetime<'a>() {
+    assert::is_maybe_transmutable::<&'static u8, &'a u8>();
+}
+
+fn all_assumptions<'a>() {
     #[repr(C)] struct Unit;
-    assert::is_maybe_transmutable::<&'static Unit, &'static Unit>(); //~ ERROR cannot be safely transmuted
+    assert::is_maybe_transmutable_assuming_all::<&'static Unit, &'static Unit>();
+    // The source lifetime need not outlive the destination lifetime.
+    assert::is_maybe_transmutable_assuming_all::<&'a u8, &'static u8>();
+}
+
+fn any_referent() {
+    #[repr(C)] union BoolOrI8 { b: &'static bool, i: &'static i8 }
+    assert::is_maybe_transmutable::<&'static u8, BoolOrI8>();
 }
@@ -0,0 +1,28 @@
+//! When a transmutation is rejected, the first incompatible byte should be attributed to the
+//! fields (or padding) it belongs to in both layouts.
+
+#![crate_type = "lib"]
+#![feature(transmutability)]
+#![allow(dead_code)]
+
+mod assert {
+    use std::mem::{Assume, BikeshedIntrinsicFrom};
+
+    pub fn is_transmutable<Src, Dst, Context>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, Context, {
+            Assume::ALIGNMENT
+                .and(Assume::LIFETIMES)
+                .and(Assume::SAFETY)
+                .and(Assume::VALIDITY)
+        }>
+    {}
+}
+
+#[repr(C)] struct Src { tag: u8, len: u32 }
+#[repr(C)] struct Dst { tag: u8, reserved: [u8; 3], len: u32 }
+
+fn should_explain_padding() {
+    struct Context;
+    assert::is_transmutable::<Src, Dst, Context>(); //~ ERROR cannot be safely transmuted
+}
@@ -0,0 +1,22 @@
+// Check that global where clauses are still honored now that the evaluation of global
+// goals without caller bounds is shared between bodies.
+#![feature(trivial_bounds)]
+#![allow(trivial_bounds)]
+
+fn move_out_string(t: &String) -> String {
+    *t //~ ERROR
+}
+
+fn copy_out_string(t: &String) -> String where String: Copy {
+    *t
+}
+
+fn copy_out_string_with_param<T: Clone>(t: &String, _: T) -> String where String: Copy {
+    *t
+}
+
+fn move_out_string_again(t: &String) -> String {
+    *t //~ ERROR
+}
+
+fn main() {}
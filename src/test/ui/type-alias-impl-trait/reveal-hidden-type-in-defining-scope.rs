@@ -0,0 +1,29 @@
+// check-pass
+// compile-flags: -Zreveal-opaque-types-in-defining-scope
+
+// With `-Zreveal-opaque-types-in-defining-scope`, an opaque type that already has a
+// hidden type is revealed to it inside of its defining scope if nothing else can prove
+// an obligation on the opaque type. Without the flag, this is an error, see `reveal_local.rs`.
+
+#![feature(type_alias_impl_trait)]
+
+trait Trait {}
+
+impl Trait for u32 {}
+
+type Tait = impl Sized;
+
+fn needs_trait<T: Trait>(_: &T) {}
+
+fn needs_send<T: Send>(_: &T) {}
+
+fn define() -> Tait {
+    let x: Tait = 1u32;
+    needs_trait(&x);
+    needs_send(&x);
+    x
+}
+
+fn main() {
+    define();
+}